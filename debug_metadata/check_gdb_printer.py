@@ -0,0 +1,115 @@
+# Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+# Licensed under the MIT License. See LICENSE in the project root for license information.
+#
+# Smoke check for seq_map_gdb.py that runs without gdb. It loads the printer with a stand-in
+# `gdb` module and prints a fake SeqMap laid out like the crate and the standard library lay
+# it out: `entries: Entries { deque: VecDeque { head, len, buf: RawVec { inner: RawVecInner {
+# ptr: Unique { pointer: NonNull { pointer } }, cap: Cap(usize) } } } }`. Run by
+# tests/debugger_visualizer.rs.
+
+import os
+import sys
+import types
+
+TYPE_CODE_PTR = 1
+TYPE_CODE_STRUCT = 3
+TYPE_CODE_INT = 8
+
+
+class Field:
+    def __init__(self, name):
+        self.name = name
+
+
+class Type:
+    def __init__(self, code, tag=None, fields=(), arguments=(), target=None):
+        self.code = code
+        self.tag = tag
+        self._fields = list(fields)
+        self._arguments = list(arguments)
+        self._target = target
+
+    def strip_typedefs(self):
+        return self
+
+    def fields(self):
+        return [Field(name) for name in self._fields]
+
+    def template_argument(self, n):
+        return self._arguments[n]
+
+    def pointer(self):
+        return Type(TYPE_CODE_PTR, target=self)
+
+
+class Value:
+    def __init__(self, type, value):
+        self.type = type
+        self.value = value
+
+    def __getitem__(self, field):
+        name = field.name if isinstance(field, Field) else field
+        return self.value[name]
+
+    def __int__(self):
+        return self.value
+
+    def cast(self, type):
+        return Value(type, self.value)
+
+    def __add__(self, offset):
+        memory, address = self.value
+        return Value(self.type, (memory, address + offset))
+
+    def dereference(self):
+        memory, address = self.value
+        return memory[address]
+
+
+def struct(tag, **fields):
+    return Value(Type(TYPE_CODE_STRUCT, tag, fields), fields)
+
+
+def integer(value):
+    return Value(Type(TYPE_CODE_INT), value)
+
+
+def fake_map(entries, head, cap):
+    entry_type = Type(TYPE_CODE_STRUCT, "(&str, u32)", ["__0", "__1"])
+    memory = [None] * cap
+    for index, (key, value) in enumerate(entries):
+        memory[(head + index) % cap] = struct("(&str, u32)", __0=key, __1=value)
+    pointer = Value(Type(TYPE_CODE_PTR, target=Type(TYPE_CODE_INT)), (memory, 0))
+    inner = struct(
+        "alloc::raw_vec::RawVecInner",
+        ptr=struct("core::ptr::unique::Unique<u8>", pointer=struct("NonNull<u8>", pointer=pointer)),
+        cap=struct("alloc::raw_vec::Cap", __0=integer(cap)),
+    )
+    deque = struct(
+        "alloc::collections::vec_deque::VecDeque<(&str, u32), alloc::alloc::Global>",
+        head=integer(head),
+        len=integer(len(entries)),
+        buf=struct("alloc::raw_vec::RawVec", inner=inner),
+    )
+    deque.type._arguments = [entry_type]
+    entries_value = struct("seq_map::entries::Entries<(&str, u32)>", deque=deque)
+    return struct("seq_map::SeqMap<&str, u32, seq_map::hasher::FxBuildHasher>", entries=entries_value)
+
+
+gdb = types.ModuleType("gdb")
+gdb.TYPE_CODE_PTR = TYPE_CODE_PTR
+gdb.TYPE_CODE_INT = TYPE_CODE_INT
+gdb.pretty_printers = []
+gdb.current_objfile = lambda: None
+sys.modules["gdb"] = gdb
+
+with open(os.path.join(os.path.dirname(os.path.abspath(__file__)), "seq_map_gdb.py")) as script:
+    exec(script.read(), {"__name__": "seq_map_gdb"})
+
+(lookup,) = gdb.pretty_printers
+printer = lookup(fake_map([("a", 1), ("b", 2), ("c", 3)], head=6, cap=8))
+assert printer is not None, "SeqMap is recognized"
+assert printer.to_string() == "SeqMap(len=3)", printer.to_string()
+children = [value for _, value in printer.children()]
+assert children == ["a", 1, "b", 2, "c", 3], children
+assert lookup(struct("seq_map::SeqMapIndex<&str, u32>")) is None
@@ -0,0 +1,51 @@
+# Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+# Licensed under the MIT License. See LICENSE in the project root for license information.
+#
+# GDB pretty printer for seq_map::SeqMap. It shows the entries in insertion order as
+# key => value children and hides the key index. The entries are a VecDeque, read from its
+# `head`, `len` and `buf` fields the same way the VecDeque printer that ships with rustc does.
+
+import re
+
+import gdb
+
+
+def _unwrap(val, code):
+    # Steps into single field wrappers like `Cap(usize)`, `Unique` and `NonNull`
+    while val.type.strip_typedefs().code != code:
+        val = val[val.type.strip_typedefs().fields()[0]]
+    return val
+
+
+class SeqMapPrinter:
+    def __init__(self, val):
+        deque = val["entries"]["deque"]
+        self.head = int(deque["head"])
+        self.len = int(deque["len"])
+        inner = deque["buf"]["inner"]
+        self.cap = int(_unwrap(inner["cap"], gdb.TYPE_CODE_INT))
+        entry_type = deque.type.strip_typedefs().template_argument(0)
+        self.data = _unwrap(inner["ptr"], gdb.TYPE_CODE_PTR).cast(entry_type.pointer())
+
+    def to_string(self):
+        return "SeqMap(len={})".format(self.len)
+
+    def children(self):
+        for index in range(self.len):
+            entry = (self.data + (self.head + index) % self.cap).dereference()
+            yield "[{}]".format(2 * index), entry["__0"]
+            yield "[{}]".format(2 * index + 1), entry["__1"]
+
+    def display_hint(self):
+        return "map"
+
+
+def lookup(val):
+    type_name = val.type.strip_typedefs().tag
+    if type_name is not None and re.match(r"^seq_map::SeqMap<.*>$", type_name):
+        return SeqMapPrinter(val)
+    return None
+
+
+objfile = gdb.current_objfile()
+(objfile.pretty_printers if objfile is not None else gdb.pretty_printers).append(lookup)
@@ -0,0 +1,320 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    hash::{BuildHasher, Hash},
+};
+
+use allocator_api2::{
+    alloc::{Allocator, Global},
+    vec::Vec,
+};
+
+use crate::{
+    key_index::{IndexEntry, KeyIndex},
+    map_index::Generation,
+    DefaultHashBuilder, SeqMap, SeqMapError, SeqMapIndex, SMALL_MAP_LEN,
+};
+
+/// An insertion ordered map whose entries and key index live in the allocator `A`.
+///
+/// Both the entries and the hash table of positions are allocated with `A`, for example a
+/// bump or arena allocator that is reset once per frame. Dropping the map into an arena that
+/// is reset anyway frees everything at once, without deallocating entry by entry. Uses the
+/// `Allocator` trait from `allocator-api2`, so it works on stable Rust.
+///
+/// The index works like the one of [`SeqMap`]: it caches the hash of every key, and after a
+/// [`remove`](Self::remove) the positions of the later entries are renumbered in batches, so
+/// removing does not walk the whole index every time. Unlike `SeqMap`, the index exists from
+/// the first entry on, and the entries are a plain `Vec`, so removing an entry still moves all
+/// the entries after it.
+///
+/// Keys are hashed with `S`, which defaults to [`DefaultHashBuilder`].
+///
+/// # Examples
+///
+/// ```
+/// use allocator_api2::alloc::Global;
+/// use seq_map::AllocSeqMap;
+/// let mut map = AllocSeqMap::new_in(Global);
+/// map.insert("a", 1).unwrap();
+/// map.insert("b", 2).unwrap();
+/// assert_eq!(map.get(&"b"), Some(&2));
+/// assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec!["a", "b"]);
+/// ```
+pub struct AllocSeqMap<K, V, S = DefaultHashBuilder, A: Allocator + Clone = Global> {
+    // Positions in `entries` and the hashes of their keys
+    key_index: KeyIndex<A>,
+    hash_builder: S,
+    entries: Vec<(K, V), A>,
+    // Changed whenever entries are removed, to catch stale `SeqMapIndex` values in debug builds
+    generation: Generation,
+}
+
+impl<K, V> AllocSeqMap<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new, empty `AllocSeqMap` in the global allocator.
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<K, V, A> AllocSeqMap<K, V, DefaultHashBuilder, A>
+where
+    K: Eq + Hash,
+    A: Allocator + Clone,
+{
+    /// Creates a new, empty `AllocSeqMap` that allocates with `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(0, alloc)
+    }
+
+    /// Creates a new, empty `AllocSeqMap` in `alloc`, with room for `capacity` entries.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self::with_capacity_and_hasher_in(capacity, DefaultHashBuilder::default(), alloc)
+    }
+}
+
+impl<K, V, S, A> AllocSeqMap<K, V, S, A>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    A: Allocator + Clone,
+{
+    /// Creates a new, empty `AllocSeqMap` in `alloc`, that hashes keys with `hash_builder`.
+    pub fn with_hasher_in(hash_builder: S, alloc: A) -> Self {
+        Self::with_capacity_and_hasher_in(0, hash_builder, alloc)
+    }
+
+    /// Creates a new, empty `AllocSeqMap` in `alloc`, with room for `capacity` entries, that
+    /// hashes keys with `hash_builder`.
+    pub fn with_capacity_and_hasher_in(capacity: usize, hash_builder: S, alloc: A) -> Self {
+        Self {
+            key_index: KeyIndex::with_capacity_in(capacity, alloc.clone()),
+            hash_builder,
+            entries: Vec::with_capacity_in(capacity, alloc),
+            generation: Generation::new(),
+        }
+    }
+
+    /// Returns the allocator the map allocates with.
+    pub fn allocator(&self) -> &A {
+        self.entries.allocator()
+    }
+
+    /// Returns the hasher the map hashes keys with.
+    pub const fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Inserts a key-value pair at the end of the insertion order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), SeqMapError<K>> {
+        self.insert_full(key, value).map(|_| ())
+    }
+
+    /// Inserts a key-value pair like [`insert`](Self::insert), returning the position of the
+    /// new entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    pub fn insert_full(&mut self, key: K, value: V) -> Result<SeqMapIndex<K, V>, SeqMapError<K>> {
+        let hash = self.hash_builder.hash_one(&key);
+        match self
+            .key_index
+            .entry(hash, |index| self.entries[index].0 == key)
+        {
+            IndexEntry::Occupied(_) => Err(SeqMapError::KeyAlreadyExists(key)),
+            IndexEntry::Vacant(slot) => {
+                let index = self.entries.len();
+                self.entries.push((key, value));
+                slot.insert();
+                self.generation.draw_once();
+                Ok(self.generation.index(index))
+            }
+        }
+    }
+
+    /// Removes a key from the map, returning the value if it existed.
+    ///
+    /// The order of the remaining entries is kept.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        let index = self
+            .key_index
+            .remove(hash, |index| self.entries[index].0.borrow() == key)?;
+        self.generation.bump();
+        let (_, value) = self.entries.remove(index);
+        Some(value)
+    }
+
+    /// Removes all elements from the map, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.generation.bump();
+        self.key_index.clear();
+        self.entries.clear();
+    }
+
+    /// Retrieves a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| &mut self.entries[index].1)
+    }
+
+    /// Returns the insertion index of the key.
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| self.generation.index(index))
+    }
+
+    /// Returns the key and value at `index`, or `None` if it is out of bounds.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `index` was taken before entries were removed, or was taken
+    /// from another map.
+    pub fn get_at(&self, index: SeqMapIndex<K, V>) -> Option<(&K, &V)> {
+        self.entries
+            .get(self.generation.position(index))
+            .map(|(k, v)| (k, v))
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).is_some()
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the entries in insertion order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Returns an iterator over the entries in insertion order, with mutable values.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (&K, &mut V)> + ExactSizeIterator {
+        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    /// Returns an iterator over the keys in insertion order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Returns an iterator over the values in insertion order.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    /// Moves the entries into a [`SeqMap`] in the global allocator, keeping the insertion
+    /// order and the hasher.
+    ///
+    /// The index is rebuilt from the hashes it already holds, so no key is hashed.
+    pub fn into_seq_map(self) -> SeqMap<K, V, S> {
+        let mut map = SeqMap::from_entries(
+            self.hash_builder,
+            self.entries.into_iter().collect(),
+            self.generation,
+        );
+        // Small maps have no index
+        if map.entries.len() > SMALL_MAP_LEN {
+            map.key_index.build(self.key_index.hashes().iter().copied());
+        }
+        map.check_invariants();
+        map
+    }
+
+    fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        self.key_index
+            .find(hash, |index| self.entries[index].0.borrow() == key)
+    }
+}
+
+impl<K, V> Default for AllocSeqMap<K, V>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S, A> PartialEq for AllocSeqMap<K, V, S, A>
+where
+    K: PartialEq,
+    V: PartialEq,
+    A: Allocator + Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.entries[..] == other.entries[..]
+    }
+}
+
+impl<K, V, S, A> Eq for AllocSeqMap<K, V, S, A>
+where
+    K: Eq,
+    V: Eq,
+    A: Allocator + Clone,
+{
+}
+
+impl<K, V, S, A> Debug for AllocSeqMap<K, V, S, A>
+where
+    K: Debug,
+    V: Debug,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "AllocSeqMap ")?;
+        f.debug_map()
+            .entries(self.entries.iter().map(|(k, v)| (k, v)))
+            .finish()
+    }
+}
@@ -0,0 +1,205 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    any::Any,
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+};
+
+use crate::{SeqMap, SeqMapError, SeqMapIndex};
+
+/// A value stored in an [`AnySeqMap`].
+pub type AnyValue = Box<dyn Any + Send + Sync>;
+
+/// An insertion ordered map whose values can be of any type, accessed through typed getters.
+///
+/// Every value is boxed as `dyn Any`, and the getters downcast it to the type asked for,
+/// returning `None` when the key is missing or holds a value of another type. Entries keep
+/// the order they were registered in, which suits plugin and resource registries.
+///
+/// Keys are taken borrowed, like by the other maps, so the getters have a second type
+/// parameter for the borrowed key. Name the value type and let the key be inferred, as in
+/// `get::<f32, _>(..)`.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::AnySeqMap;
+/// let mut registry = AnySeqMap::new();
+/// registry.insert("gravity", 9.81f32).unwrap();
+/// registry.insert("title", String::from("demo")).unwrap();
+/// assert_eq!(registry.get::<f32, _>("gravity"), Some(&9.81));
+/// assert_eq!(registry.get::<u32, _>("gravity"), None);
+/// assert_eq!(registry.keys().copied().collect::<Vec<_>>(), vec!["gravity", "title"]);
+/// ```
+pub struct AnySeqMap<K> {
+    map: SeqMap<K, AnyValue>,
+}
+
+impl<K> AnySeqMap<K>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new, empty `AnySeqMap`.
+    pub fn new() -> Self {
+        Self { map: SeqMap::new() }
+    }
+
+    /// Inserts `value` under `key`, at the end of the registration order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present, whatever the
+    /// type of its value.
+    pub fn insert<T>(&mut self, key: K, value: T) -> Result<(), SeqMapError<K>>
+    where
+        T: Any + Send + Sync,
+    {
+        self.map.insert(key, Box::new(value))
+    }
+
+    /// Returns the value for `key` if it is a `T`.
+    pub fn get<T: Any, Q>(&self, key: &Q) -> Option<&T>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get(key)?.downcast_ref()
+    }
+
+    /// Returns a mutable reference to the value for `key` if it is a `T`.
+    pub fn get_mut<T: Any, Q>(&mut self, key: &Q) -> Option<&mut T>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get_mut(key)?.downcast_mut()
+    }
+
+    /// Returns the boxed value for `key`, whatever its type.
+    pub fn get_any<Q>(&self, key: &Q) -> Option<&(dyn Any + Send + Sync)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get(key).map(|value| &**value)
+    }
+
+    /// Returns `true` if `key` holds a value of type `T`.
+    pub fn is<T: Any, Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.get::<T, Q>(key).is_some()
+    }
+
+    /// Checks if the map contains a key, whatever the type of its value.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Removes the value for `key` and returns it, if it is a `T`.
+    ///
+    /// A value of another type is left in the map. The order of the remaining entries is kept.
+    pub fn remove<T: Any, Q>(&mut self, key: &Q) -> Option<T>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        if !self.is::<T, Q>(key) {
+            return None;
+        }
+        let value = self.map.remove(key)?;
+        value.downcast().ok().map(|value| *value)
+    }
+
+    /// Removes the boxed value for `key`, whatever its type.
+    pub fn remove_any<Q>(&mut self, key: &Q) -> Option<AnyValue>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.remove(key)
+    }
+
+    /// Returns the registration index of the key.
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, AnyValue>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get_index(key)
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Removes all entries from the map.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Returns an iterator over the keys in registration order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.map.keys()
+    }
+
+    /// Returns an iterator over the keys and boxed values in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &(dyn Any + Send + Sync))> {
+        self.map.iter().map(|(key, value)| (key, &**value))
+    }
+
+    /// Returns an iterator over the keys and values of type `T`, in registration order,
+    /// skipping values of other types.
+    pub fn iter_of<T: Any>(&self) -> impl Iterator<Item = (&K, &T)> {
+        self.map
+            .iter()
+            .filter_map(|(key, value)| value.downcast_ref().map(|value| (key, value)))
+    }
+
+    /// Consumes the registry and returns the underlying map of boxed values.
+    pub fn into_inner(self) -> SeqMap<K, AnyValue> {
+        self.map
+    }
+}
+
+impl<K> Default for AnySeqMap<K>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> From<SeqMap<K, AnyValue>> for AnySeqMap<K> {
+    fn from(map: SeqMap<K, AnyValue>) -> Self {
+        Self { map }
+    }
+}
+
+/// Lists the keys only, since the values have no known type.
+impl<K> Debug for AnySeqMap<K>
+where
+    K: Eq + Hash + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "AnySeqMap ")?;
+        f.debug_list().entries(self.map.keys()).finish()
+    }
+}
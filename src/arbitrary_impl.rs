@@ -0,0 +1,30 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::hash::{BuildHasher, Hash};
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::SeqMap;
+
+/// Generates maps through [`FromIterator`], so a generated key that is already present is
+/// dropped and the map always holds unique keys with consistent indices.
+impl<'a, K, V, S> Arbitrary<'a> for SeqMap<K, V, S>
+where
+    K: Arbitrary<'a> + Eq + Hash,
+    V: Arbitrary<'a>,
+    S: BuildHasher + Default,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?.collect()
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
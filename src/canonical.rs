@@ -0,0 +1,186 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    hash::{BuildHasher, Hash},
+    str::FromStr,
+};
+
+use crate::SeqMap;
+
+/// Error returned by [`SeqMap::parse_canonical`]. Every variant holds the 1-based line number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseCanonicalError {
+    /// The line has no ` = ` between the key and the value.
+    MissingSeparator { line: usize },
+    /// The line contains a `\` that is not followed by `\`, `n`, `r` or `=`.
+    InvalidEscape { line: usize },
+    /// The key could not be parsed with `FromStr`.
+    InvalidKey { line: usize },
+    /// The value could not be parsed with `FromStr`.
+    InvalidValue { line: usize },
+    /// The key was already defined on an earlier line.
+    DuplicateKey { line: usize },
+}
+
+impl ParseCanonicalError {
+    /// Returns the 1-based line number the error was found on.
+    pub fn line(&self) -> usize {
+        match self {
+            Self::MissingSeparator { line }
+            | Self::InvalidEscape { line }
+            | Self::InvalidKey { line }
+            | Self::InvalidValue { line }
+            | Self::DuplicateKey { line } => *line,
+        }
+    }
+}
+
+impl Display for ParseCanonicalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let problem = match self {
+            Self::MissingSeparator { .. } => "expected `key = value`",
+            Self::InvalidEscape { .. } => "invalid escape sequence",
+            Self::InvalidKey { .. } => "the key could not be parsed",
+            Self::InvalidValue { .. } => "the value could not be parsed",
+            Self::DuplicateKey { .. } => "the key is already defined",
+        };
+        write!(f, "line {}: {problem}", self.line())
+    }
+}
+
+impl Error for ParseCanonicalError {}
+
+impl<K, V, S> SeqMap<K, V, S>
+where
+    K: Display,
+    V: Display,
+{
+    /// Writes the map as one `key = value` line per entry, in insertion order.
+    ///
+    /// Keys and values are written with their `Display` impl. Backslashes, newlines and
+    /// carriage returns are escaped as `\\`, `\n` and `\r`, and `=` is escaped as `\=` in keys,
+    /// so every entry stays on one line and can be read back with
+    /// [`parse_canonical`](SeqMap::parse_canonical).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a=b", "one\ntwo"), ("c", "x = y")]);
+    /// assert_eq!(map.to_canonical_string(), "a\\=b = one\\ntwo\nc = x = y\n");
+    /// ```
+    pub fn to_canonical_string(&self) -> String {
+        let mut out = String::new();
+        for (key, value) in &self.entries {
+            escape_into(&mut out, &key.to_string(), true);
+            out.push_str(" = ");
+            escape_into(&mut out, &value.to_string(), false);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<K, V, S> SeqMap<K, V, S>
+where
+    K: Eq + Hash + FromStr,
+    V: FromStr,
+    S: BuildHasher + Default,
+{
+    /// Parses the format written by [`to_canonical_string`](SeqMap::to_canonical_string),
+    /// keeping the order of the lines. Empty lines are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseCanonicalError`] with the line number for malformed lines, keys or
+    /// values that `FromStr` rejects, and keys that occur more than once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map: SeqMap<String, u32> = SeqMap::parse_canonical("b = 2\na = 1\n").unwrap();
+    /// assert_eq!(map.to_canonical_string(), "b = 2\na = 1\n");
+    /// assert_eq!(SeqMap::<String, u32>::parse_canonical("a = x").unwrap_err().line(), 1);
+    /// ```
+    pub fn parse_canonical(text: &str) -> Result<Self, ParseCanonicalError> {
+        let mut map = Self::default();
+        for (index, line_text) in text.lines().enumerate() {
+            let line = index + 1;
+            if line_text.is_empty() {
+                continue;
+            }
+            let separator = find_separator(line_text, line)?;
+            let key = unescape(&line_text[..separator - 1], line)?;
+            let value = unescape(&line_text[separator + 2..], line)?;
+            let key = key
+                .parse()
+                .map_err(|_| ParseCanonicalError::InvalidKey { line })?;
+            let value = value
+                .parse()
+                .map_err(|_| ParseCanonicalError::InvalidValue { line })?;
+            map.insert(key, value)
+                .map_err(|_| ParseCanonicalError::DuplicateKey { line })?;
+        }
+        Ok(map)
+    }
+}
+
+fn escape_into(out: &mut String, text: &str, is_key: bool) {
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '=' if is_key => out.push_str("\\="),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Returns the byte position of the first unescaped `=`, which must be surrounded by spaces.
+fn find_separator(text: &str, line: usize) -> Result<usize, ParseCanonicalError> {
+    let bytes = text.as_bytes();
+    let mut position = 0;
+    while position < bytes.len() {
+        match bytes[position] {
+            b'\\' => position += 2,
+            b'=' => {
+                let spaced = position > 0
+                    && bytes[position - 1] == b' '
+                    && bytes.get(position + 1) == Some(&b' ');
+                return if spaced {
+                    Ok(position)
+                } else {
+                    Err(ParseCanonicalError::MissingSeparator { line })
+                };
+            }
+            _ => position += 1,
+        }
+    }
+    Err(ParseCanonicalError::MissingSeparator { line })
+}
+
+fn unescape(text: &str, line: usize) -> Result<String, ParseCanonicalError> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => out.push('\\'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('=') => out.push('='),
+            _ => return Err(ParseCanonicalError::InvalidEscape { line }),
+        }
+    }
+    Ok(out)
+}
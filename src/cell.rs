@@ -0,0 +1,207 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    cell::{Ref, RefCell, RefMut},
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+};
+
+use crate::{SeqMap, SeqMapError, SeqMapIndex};
+
+/// A [`SeqMap`] that can be changed through a shared reference, for use on a single thread.
+///
+/// The map sits in a [`RefCell`], and every method borrows it only for the duration of the
+/// call. Many owners can then hold an `Rc<SeqMapCell<K, V>>` and insert or update entries
+/// without borrowing the map themselves. Values are reached through closures, so no borrow
+/// outlives the call.
+///
+/// # Panics
+///
+/// Methods that take a closure panic if the closure uses the same `SeqMapCell` to change it,
+/// since the map is already borrowed while the closure runs. Reading from the closure is fine
+/// within [`with`](Self::with).
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use seq_map::SeqMapCell;
+/// let registry = Rc::new(SeqMapCell::new());
+/// let handle = Rc::clone(&registry);
+/// handle.insert("node", 1).unwrap();
+/// registry.update(&"node", |v| *v += 1);
+/// assert_eq!(registry.get_cloned(&"node"), Some(2));
+/// ```
+pub struct SeqMapCell<K, V> {
+    map: RefCell<SeqMap<K, V>>,
+}
+
+impl<K, V> SeqMapCell<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new, empty `SeqMapCell`.
+    pub fn new() -> Self {
+        Self::from(SeqMap::new())
+    }
+
+    /// Inserts a key-value pair at the end of the insertion order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    pub fn insert(&self, key: K, value: V) -> Result<(), SeqMapError<K>> {
+        self.map.borrow_mut().insert(key, value)
+    }
+
+    /// Removes a key from the map, returning the value if it existed.
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.borrow_mut().remove(key)
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.borrow().contains_key(key)
+    }
+
+    /// Returns the insertion index of the key.
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.borrow().get_index(key)
+    }
+
+    /// Returns a clone of the value for `key`.
+    pub fn get_cloned<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        V: Clone,
+    {
+        self.map.borrow().get(key).cloned()
+    }
+
+    /// Calls `f` with the value for `key` and returns its result, or `None` if the key is
+    /// missing.
+    pub fn with<Q, R, F>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        F: FnOnce(&V) -> R,
+    {
+        self.map.borrow().get(key).map(f)
+    }
+
+    /// Calls `f` with the value for `key`, which it may change, and returns `true` if the key
+    /// was present.
+    pub fn update<Q, F>(&self, key: &Q, f: F) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        F: FnOnce(&mut V),
+    {
+        self.map.borrow_mut().get_mut(key).map(f).is_some()
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.map.borrow().len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.borrow().is_empty()
+    }
+
+    /// Removes all elements from the map.
+    pub fn clear(&self) {
+        self.map.borrow_mut().clear();
+    }
+
+    /// Returns clones of the keys in insertion order.
+    pub fn keys_cloned(&self) -> Vec<K>
+    where
+        K: Clone,
+    {
+        self.map.borrow().keys().cloned().collect()
+    }
+
+    /// Borrows the whole map, for operations not covered by `SeqMapCell` itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map is currently borrowed mutably.
+    pub fn borrow(&self) -> Ref<'_, SeqMap<K, V>> {
+        self.map.borrow()
+    }
+
+    /// Borrows the whole map mutably, for operations not covered by `SeqMapCell` itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map is currently borrowed.
+    pub fn borrow_mut(&self) -> RefMut<'_, SeqMap<K, V>> {
+        self.map.borrow_mut()
+    }
+
+    /// Consumes the cell and returns the map.
+    pub fn into_inner(self) -> SeqMap<K, V> {
+        self.map.into_inner()
+    }
+}
+
+impl<K, V> Default for SeqMapCell<K, V>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> From<SeqMap<K, V>> for SeqMapCell<K, V> {
+    fn from(map: SeqMap<K, V>) -> Self {
+        Self {
+            map: RefCell::new(map),
+        }
+    }
+}
+
+impl<K, V> Clone for SeqMapCell<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    /// Clones the map inside, which must not be borrowed mutably at the time.
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<K, V> Debug for SeqMapCell<K, V>
+where
+    K: Eq + Hash + Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.map.try_borrow() {
+            Ok(map) => write!(f, "SeqMapCell({map:?})"),
+            Err(_) => write!(f, "SeqMapCell(<borrowed>)"),
+        }
+    }
+}
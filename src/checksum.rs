@@ -0,0 +1,267 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use crate::SeqMap;
+
+/// A sink for the bytes of a [`StableEncode`] value.
+///
+/// All the provided methods write numbers in little-endian byte order, whatever the platform,
+/// and `usize` and `isize` as 64 bits, so the bytes written only depend on the values.
+pub trait StableWrite {
+    /// Writes raw bytes.
+    fn write_bytes(&mut self, bytes: &[u8]);
+
+    fn write_u8(&mut self, value: u8) {
+        self.write_bytes(&[value]);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    /// Writes a length or count, as a `u64`.
+    fn write_len(&mut self, len: usize) {
+        self.write_u64(len as u64);
+    }
+}
+
+/// Types that encode to the same bytes on every platform, for [`SeqMap::checksum64`] and
+/// [`SeqMap::checksum128`].
+///
+/// Unlike [`Hash`](std::hash::Hash), whose output may depend on the platform and the
+/// compiler version, the encoding is fixed. Sequences and strings are prefixed with their
+/// length, and floats are encoded by their bits, so `-0.0` and `0.0` differ.
+pub trait StableEncode {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W);
+}
+
+macro_rules! stable_encode_int {
+    ($($ty:ty => $write:ident as $as:ty),* $(,)?) => {
+        $(
+            impl StableEncode for $ty {
+                fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+                    out.$write(*self as $as);
+                }
+            }
+        )*
+    };
+}
+
+stable_encode_int! {
+    u8 => write_u8 as u8,
+    u16 => write_u16 as u16,
+    u32 => write_u32 as u32,
+    u64 => write_u64 as u64,
+    u128 => write_u128 as u128,
+    usize => write_u64 as u64,
+    i8 => write_u8 as u8,
+    i16 => write_u16 as u16,
+    i32 => write_u32 as u32,
+    i64 => write_u64 as u64,
+    i128 => write_u128 as u128,
+    isize => write_u64 as u64,
+}
+
+impl StableEncode for bool {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        out.write_u8(u8::from(*self));
+    }
+}
+
+impl StableEncode for char {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        out.write_u32(u32::from(*self));
+    }
+}
+
+impl StableEncode for f32 {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        out.write_u32(self.to_bits());
+    }
+}
+
+impl StableEncode for f64 {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        out.write_u64(self.to_bits());
+    }
+}
+
+impl StableEncode for () {
+    fn encode<W: StableWrite + ?Sized>(&self, _out: &mut W) {}
+}
+
+impl StableEncode for str {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        out.write_len(self.len());
+        out.write_bytes(self.as_bytes());
+    }
+}
+
+impl StableEncode for String {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        self.as_str().encode(out);
+    }
+}
+
+impl<T: StableEncode> StableEncode for [T] {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        out.write_len(self.len());
+        for item in self {
+            item.encode(out);
+        }
+    }
+}
+
+impl<T: StableEncode, const N: usize> StableEncode for [T; N] {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        self.as_slice().encode(out);
+    }
+}
+
+impl<T: StableEncode> StableEncode for Vec<T> {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        self.as_slice().encode(out);
+    }
+}
+
+impl<T: StableEncode> StableEncode for Option<T> {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        match self {
+            None => out.write_u8(0),
+            Some(value) => {
+                out.write_u8(1);
+                value.encode(out);
+            }
+        }
+    }
+}
+
+impl<T: StableEncode + ?Sized> StableEncode for &T {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        (**self).encode(out);
+    }
+}
+
+impl<T: StableEncode + ?Sized> StableEncode for Box<T> {
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        (**self).encode(out);
+    }
+}
+
+macro_rules! stable_encode_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: StableEncode),+> StableEncode for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+                let ($($name,)+) = self;
+                $($name.encode(out);)+
+            }
+        }
+    };
+}
+
+stable_encode_tuple!(A);
+stable_encode_tuple!(A, B);
+stable_encode_tuple!(A, B, C);
+stable_encode_tuple!(A, B, C, D);
+
+/// Encodes the number of entries, then every key followed by its value, in insertion order.
+impl<K, V, S> StableEncode for SeqMap<K, V, S>
+where
+    K: StableEncode,
+    V: StableEncode,
+{
+    fn encode<W: StableWrite + ?Sized>(&self, out: &mut W) {
+        out.write_len(self.entries.len());
+        for (key, value) in &self.entries {
+            key.encode(out);
+            value.encode(out);
+        }
+    }
+}
+
+/// 64-bit FNV-1a.
+struct Fnv64(u64);
+
+impl Fnv64 {
+    const fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+/// Hashes the stable encoding of `value` with 64-bit FNV-1a.
+pub(crate) fn stable_hash64<T: StableEncode + ?Sized>(value: &T) -> u64 {
+    let mut hasher = Fnv64::new();
+    value.encode(&mut hasher);
+    hasher.0
+}
+
+impl StableWrite for Fnv64 {
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+/// 128-bit FNV-1a.
+struct Fnv128(u128);
+
+impl StableWrite for Fnv128 {
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 =
+                (self.0 ^ u128::from(byte)).wrapping_mul(0x0000_0000_0100_0000_0000_0000_0000_013b);
+        }
+    }
+}
+
+impl<K, V, S> SeqMap<K, V, S>
+where
+    K: StableEncode,
+    V: StableEncode,
+{
+    /// Returns a 64-bit checksum of the entries, in insertion order, that is the same on
+    /// every platform.
+    ///
+    /// The map is encoded with [`StableEncode`] and hashed with 64-bit FNV-1a, so the checksum
+    /// only depends on the keys, the values and their order. It is meant for detecting that
+    /// two peers have diverged, not for resisting deliberate collisions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let a = SeqMap::from([("hp", 10u32), ("mp", 4)]);
+    /// let b = SeqMap::from([("mp", 4u32), ("hp", 10)]);
+    /// assert_eq!(a.checksum64(), a.clone().checksum64());
+    /// assert_ne!(a.checksum64(), b.checksum64());
+    /// ```
+    pub fn checksum64(&self) -> u64 {
+        stable_hash64(self)
+    }
+
+    /// Returns a 128-bit checksum of the entries, in insertion order, that is the same on
+    /// every platform.
+    ///
+    /// This is [`checksum64`](Self::checksum64) with 128-bit FNV-1a, for when many states
+    /// are compared and 64-bit collisions start to matter.
+    pub fn checksum128(&self) -> u128 {
+        let mut hasher = Fnv128(0x6c62_272e_07bb_0142_62b8_2175_6295_c58d);
+        self.encode(&mut hasher);
+        hasher.0
+    }
+}
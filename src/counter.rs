@@ -0,0 +1,189 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+};
+
+use crate::SeqMap;
+
+impl<K> SeqMap<K, usize>
+where
+    K: Eq + Hash,
+{
+    /// Counts how often every key occurs in `iter`, with the keys in the order they were
+    /// first seen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let counts = SeqMap::count_from_iter("abracadabra".chars());
+    /// assert_eq!(
+    ///     counts.into_vec(),
+    ///     vec![('a', 5), ('b', 2), ('r', 2), ('c', 1), ('d', 1)]
+    /// );
+    /// ```
+    pub fn count_from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let mut counter = SeqCounter::new();
+        counter.extend(iter);
+        counter.into_seq_map()
+    }
+}
+
+/// Counts occurrences of keys, keeping the keys in the order they were first seen.
+///
+/// Since the order is deterministic, so is [`most_common`](Self::most_common): keys with the
+/// same count come in the order they were first seen, which makes reports reproducible.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::SeqCounter;
+/// let mut words = SeqCounter::new();
+/// for word in "the cat saw the dog and the cat".split(' ') {
+///     words.add(word);
+/// }
+/// assert_eq!(words.count(&"the"), 3);
+/// assert_eq!(words.most_common().take(2).collect::<Vec<_>>(), vec![(&"the", 3), (&"cat", 2)]);
+/// ```
+#[derive(Clone)]
+pub struct SeqCounter<K> {
+    counts: SeqMap<K, usize>,
+    total: usize,
+}
+
+impl<K> SeqCounter<K>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new, empty `SeqCounter`.
+    pub fn new() -> Self {
+        Self {
+            counts: SeqMap::new(),
+            total: 0,
+        }
+    }
+
+    /// Counts one occurrence of `key` and returns its new count.
+    pub fn add(&mut self, key: K) -> usize {
+        self.add_n(key, 1)
+    }
+
+    /// Counts `n` occurrences of `key` and returns its new count.
+    ///
+    /// A key added with `n` of zero is still listed, with a count of zero.
+    pub fn add_n(&mut self, key: K, n: usize) -> usize {
+        self.total += n;
+        let count = self.counts.get_mut_or_insert_with(key, || 0);
+        *count += n;
+        *count
+    }
+
+    /// Returns how often `key` was counted, which is zero for keys never seen.
+    pub fn count<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+
+    /// Returns the keys with their counts, highest count first.
+    ///
+    /// Keys with the same count are in the order they were first seen.
+    pub fn most_common(&self) -> impl DoubleEndedIterator<Item = (&K, usize)> + ExactSizeIterator {
+        let mut ranked: Vec<_> = self.iter().collect();
+        ranked.sort_by(|(_, a), (_, b)| b.cmp(a));
+        ranked.into_iter()
+    }
+
+    /// Returns the sum of all counts.
+    pub const fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the number of distinct keys.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns `true` if nothing has been counted.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Returns an iterator over the keys and their counts, in the order the keys were first
+    /// seen.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, usize)> {
+        self.counts.iter().map(|(key, count)| (key, *count))
+    }
+
+    /// Returns the counts as a map, in the order the keys were first seen.
+    pub const fn as_seq_map(&self) -> &SeqMap<K, usize> {
+        &self.counts
+    }
+
+    /// Consumes the counter and returns the counts as a map.
+    pub fn into_seq_map(self) -> SeqMap<K, usize> {
+        self.counts
+    }
+}
+
+impl<K> Default for SeqCounter<K>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Extend<K> for SeqCounter<K>
+where
+    K: Eq + Hash,
+{
+    fn extend<T: IntoIterator<Item = K>>(&mut self, iter: T) {
+        for key in iter {
+            self.add(key);
+        }
+    }
+}
+
+impl<K> FromIterator<K> for SeqCounter<K>
+where
+    K: Eq + Hash,
+{
+    fn from_iter<T: IntoIterator<Item = K>>(iter: T) -> Self {
+        let mut counter = Self::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<K> PartialEq for SeqCounter<K>
+where
+    K: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.counts.entries == other.counts.entries
+    }
+}
+
+impl<K> Eq for SeqCounter<K> where K: Eq {}
+
+impl<K> Debug for SeqCounter<K>
+where
+    K: Eq + Hash + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "SeqCounter ")?;
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
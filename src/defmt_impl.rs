@@ -0,0 +1,19 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use defmt::{Format, Formatter};
+
+use crate::SeqMap;
+
+/// Logs the entries as a slice of `(key, value)` pairs in insertion order, so the host does
+/// the formatting and only the encoded keys and values are sent from the target.
+impl<K, V, S> Format for SeqMap<K, V, S>
+where
+    K: Format,
+    V: Format,
+{
+    fn format(&self, f: Formatter<'_>) {
+        defmt::write!(f, "SeqMap({=[?]})", self.entries.as_slice());
+    }
+}
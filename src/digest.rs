@@ -0,0 +1,305 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+    mem,
+};
+
+use crate::{
+    checksum::stable_hash64, entries::Entries, rng::SplitMix64, SeqMap, SeqMapError, SeqMapIndex,
+    StableEncode,
+};
+
+/// Stands in for the entry before the first one.
+const START: u64 = 0x243f_6a88_85a3_08d3;
+/// Stands in for the entry after the last one.
+const END: u64 = 0x1319_8a2e_0370_7344;
+
+fn mix(value: u64) -> u64 {
+    SplitMix64::new(value).next_u64()
+}
+
+/// The contribution of `before` being directly followed by `after`.
+fn link(before: u64, after: u64) -> u64 {
+    mix(before ^ mix(after).rotate_left(23))
+}
+
+/// The contribution of the entries between `before` and `after`, which is either a single
+/// entry or none.
+fn path(before: u64, through: Option<u64>, after: u64) -> u64 {
+    match through {
+        Some(hash) => link(before, hash).wrapping_add(link(hash, after)),
+        None => link(before, after),
+    }
+}
+
+/// A [`SeqMap`] that keeps a digest of its entries up to date as it is changed.
+///
+/// The digest only depends on the keys, the values and their order, like
+/// [`checksum64`](SeqMap::checksum64), and is the same on every platform. It is not the same
+/// number as the checksum, though: every entry is hashed with [`StableEncode`], and the digest
+/// adds up a mix of every pair of neighbouring entries. Inserting, replacing or removing an
+/// entry anywhere then only rehashes that entry and relinks its neighbours, so reading the
+/// digest every tick costs nothing.
+///
+/// Values can only be changed through [`replace`](Self::replace) and
+/// [`update`](Self::update), since the digest could not follow changes made through a
+/// mutable reference.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::DigestSeqMap;
+/// let mut peer_a = DigestSeqMap::new();
+/// let mut peer_b = DigestSeqMap::new();
+/// for peer in [&mut peer_a, &mut peer_b] {
+///     peer.insert("ship", 3u32).unwrap();
+///     peer.insert("rock", 7).unwrap();
+/// }
+/// assert_eq!(peer_a.current_digest(), peer_b.current_digest());
+/// peer_b.update(&"ship", |hp| *hp -= 1);
+/// assert_ne!(peer_a.current_digest(), peer_b.current_digest());
+/// ```
+pub struct DigestSeqMap<K, V> {
+    map: SeqMap<K, V>,
+    // The hash of the entry at every position of `map`, cheap to remove at the front
+    entry_hashes: Entries<u64>,
+    digest: u64,
+}
+
+impl<K, V> DigestSeqMap<K, V>
+where
+    K: Eq + Hash + StableEncode,
+    V: StableEncode,
+{
+    /// Creates a new, empty `DigestSeqMap`.
+    pub fn new() -> Self {
+        Self::from(SeqMap::new())
+    }
+
+    /// Returns the digest of the current entries.
+    pub const fn current_digest(&self) -> u64 {
+        self.digest
+    }
+
+    /// Inserts a key-value pair at the end of the insertion order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), SeqMapError<K>> {
+        let hash = stable_hash64(&(&key, &value));
+        self.map.insert(key, value)?;
+        let before = self.entry_hashes.last().copied().unwrap_or(START);
+        self.relink(before, None, Some(hash), END);
+        self.entry_hashes.push(hash);
+        Ok(())
+    }
+
+    /// Replaces the value for `key`, keeping its position, or appends the entry if the key is
+    /// new. Returns the value that was replaced.
+    pub fn replace(&mut self, key: K, value: V) -> Option<V> {
+        let Some(index) = self.map.index_of(&key) else {
+            // The key was just looked up, so the insert succeeds
+            let _ = self.insert(key, value);
+            return None;
+        };
+        let old = mem::replace(&mut self.map.entries[index].1, value);
+        self.rehash(index);
+        Some(old)
+    }
+
+    /// Modifies the value for `key` in place with `f`, returning `true` if the key was present.
+    ///
+    /// If `f` panics, the digest is still brought up to date with whatever `f` left in the
+    /// value.
+    pub fn update<Q, F>(&mut self, key: &Q, f: F) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        F: FnOnce(&mut V),
+    {
+        let Some(index) = self.map.index_of(key) else {
+            return false;
+        };
+        let guard = RehashGuard { map: self, index };
+        f(&mut guard.map.map.entries[index].1);
+        true
+    }
+
+    /// Removes a key from the map, returning the value if it existed.
+    ///
+    /// The order of the remaining entries is kept.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let (index, _, value) = self.map.remove_full(key)?;
+        let hash = self.entry_hashes.remove(index);
+        let (before, after) = self.neighbours(index, index);
+        self.relink(before, Some(hash), None, after);
+        Some(value)
+    }
+
+    /// Removes all elements from the map.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.entry_hashes.clear();
+        self.digest = link(START, END);
+    }
+
+    /// Retrieves a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get(key)
+    }
+
+    /// Returns the insertion index of the key.
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get_index(key)
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator over the entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.map.iter()
+    }
+
+    /// Returns an iterator over the keys in insertion order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.map.keys()
+    }
+
+    /// Returns an iterator over the values in insertion order.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.map.values()
+    }
+
+    /// Returns the underlying map.
+    pub const fn as_seq_map(&self) -> &SeqMap<K, V> {
+        &self.map
+    }
+
+    /// Consumes the digest map and returns the underlying map.
+    pub fn into_inner(self) -> SeqMap<K, V> {
+        self.map
+    }
+
+    /// Returns the hashes of the entries just before `index` and just after `last`.
+    fn neighbours(&self, index: usize, last: usize) -> (u64, u64) {
+        let before = index.checked_sub(1).map_or(START, |i| self.entry_hashes[i]);
+        let after = self.entry_hashes.get(last).copied().unwrap_or(END);
+        (before, after)
+    }
+
+    /// Rehashes the entry at `index` after its value changed.
+    fn rehash(&mut self, index: usize) {
+        let (key, value) = &self.map.entries[index];
+        let hash = stable_hash64(&(key, value));
+        let old = mem::replace(&mut self.entry_hashes[index], hash);
+        let (before, after) = self.neighbours(index, index + 1);
+        self.relink(before, Some(old), Some(hash), after);
+    }
+
+    /// Replaces the entry between `before` and `after`, or its absence, in the digest.
+    fn relink(&mut self, before: u64, old: Option<u64>, new: Option<u64>, after: u64) {
+        self.digest = self
+            .digest
+            .wrapping_sub(path(before, old, after))
+            .wrapping_add(path(before, new, after));
+    }
+}
+
+/// Rehashes the entry at `index` when dropped, which happens even if the closure given to
+/// [`DigestSeqMap::update`] panics.
+struct RehashGuard<'a, K, V>
+where
+    K: Eq + Hash + StableEncode,
+    V: StableEncode,
+{
+    map: &'a mut DigestSeqMap<K, V>,
+    index: usize,
+}
+
+impl<K, V> Drop for RehashGuard<'_, K, V>
+where
+    K: Eq + Hash + StableEncode,
+    V: StableEncode,
+{
+    fn drop(&mut self) {
+        self.map.rehash(self.index);
+    }
+}
+
+impl<K, V> Default for DigestSeqMap<K, V>
+where
+    K: Eq + Hash + StableEncode,
+    V: StableEncode,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> From<SeqMap<K, V>> for DigestSeqMap<K, V>
+where
+    K: StableEncode,
+    V: StableEncode,
+{
+    /// Hashes every entry of `map`, once.
+    fn from(map: SeqMap<K, V>) -> Self {
+        let entry_hashes: Entries<u64> = map.entries.iter().map(stable_hash64).collect();
+        let mut digest = 0u64;
+        let mut before = START;
+        for &hash in &entry_hashes {
+            digest = digest.wrapping_add(link(before, hash));
+            before = hash;
+        }
+        digest = digest.wrapping_add(link(before, END));
+        Self {
+            map,
+            entry_hashes,
+            digest,
+        }
+    }
+}
+
+impl<K, V> Debug for DigestSeqMap<K, V>
+where
+    K: Eq + Hash + Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "DigestSeqMap({:016x}, {:?})", self.digest, self.map)
+    }
+}
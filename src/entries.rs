@@ -0,0 +1,240 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    collections::{vec_deque, VecDeque},
+    mem,
+    ops::{Deref, DerefMut, RangeBounds},
+};
+
+/// The entries of a [`SeqMap`](crate::SeqMap), in order.
+///
+/// A `VecDeque` that always holds its items in one contiguous run of its buffer, so they can be
+/// borrowed as a slice like those of a `Vec`. Removing an item only moves the items on the
+/// shorter side of it, so removing the first item moves none, where a `Vec` would move all.
+///
+/// When a push runs past the end of the buffer, or an insert past its start, the run is moved
+/// into a buffer of twice the length, a quarter of the length from its start. It then takes at
+/// least a quarter as many pushes or inserts at the front as there are items before the run has
+/// to move again.
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct Entries<T> {
+    deque: VecDeque<T>,
+}
+
+impl<T> Entries<T> {
+    /// Creates an empty list.
+    pub(crate) const fn new() -> Self {
+        Self {
+            deque: VecDeque::new(),
+        }
+    }
+
+    /// Creates an empty list with room for `capacity` items.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            deque: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of items.
+    pub(crate) fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    /// Returns the items as a slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the items wrapped around the end of the buffer, which no method lets happen.
+    pub(crate) fn as_slice(&self) -> &[T] {
+        let (run, wrapped) = self.deque.as_slices();
+        assert!(wrapped.is_empty(), "entries are kept contiguous");
+        run
+    }
+
+    /// Returns the items as a mutable slice, first moving them into one run if they wrapped
+    /// around the end of the buffer.
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [T] {
+        self.deque.make_contiguous()
+    }
+
+    /// Returns the number of items the buffer can hold without reallocating.
+    pub(crate) fn capacity(&self) -> usize {
+        self.deque.capacity()
+    }
+
+    /// Makes room for `additional` more items.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.deque.reserve(additional);
+        self.straighten();
+    }
+
+    /// Frees the capacity that is not needed.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        let mut items = Vec::from(mem::take(&mut self.deque));
+        items.shrink_to_fit();
+        self.deque = VecDeque::from(items);
+    }
+
+    /// Appends `value` after all other items.
+    pub(crate) fn push(&mut self, value: T) {
+        self.deque.push_back(value);
+        self.straighten();
+    }
+
+    /// Removes and returns the last item, if any.
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        self.deque.pop_back()
+    }
+
+    /// Inserts `value` at `index`, moving the items on the shorter side of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of items.
+    pub(crate) fn insert_at(&mut self, index: usize, value: T) {
+        self.deque.insert(index, value);
+        self.straighten();
+    }
+
+    /// Removes and returns the item at `index`, moving the items on the shorter side of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub(crate) fn remove(&mut self, index: usize) -> T {
+        let len = self.deque.len();
+        self.deque
+            .remove(index)
+            .unwrap_or_else(|| panic!("removal index {index} is out of bounds for {len} entries"))
+    }
+
+    /// Removes all items.
+    pub(crate) fn clear(&mut self) {
+        self.deque.clear();
+    }
+
+    /// Removes the items in `range`, returning them as an iterator.
+    pub(crate) fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> vec_deque::Drain<'_, T> {
+        self.deque.drain(range)
+    }
+
+    /// Keeps only the items for which `keep` returns `true`, in order.
+    pub(crate) fn retain(&mut self, keep: impl FnMut(&T) -> bool) {
+        self.deque.retain(keep);
+    }
+
+    /// Replaces the items in `range` with `replace_with`, returning the removed items.
+    pub(crate) fn splice<R, I>(&mut self, range: R, replace_with: I) -> Vec<T>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let mut items = Vec::from(mem::take(&mut self.deque));
+        let removed = items.splice(range, replace_with).collect();
+        self.deque = VecDeque::from(items);
+        removed
+    }
+
+    /// Converts the list into a `Vec`, moving the items only if the run does not start at the
+    /// beginning of the buffer.
+    pub(crate) fn into_vec(self) -> Vec<T> {
+        Vec::from(self.deque)
+    }
+
+    /// Moves a run that wrapped around the end of the buffer into a buffer of twice its
+    /// length, starting a quarter of its length in.
+    fn straighten(&mut self) {
+        if self.deque.as_slices().1.is_empty() {
+            return;
+        }
+        let mut items = Vec::from(mem::take(&mut self.deque));
+        let front = items.len() / 4;
+        let mut deque = VecDeque::with_capacity(2 * items.len());
+        // Pushed rotated, so rotating back moves the last `front` items past the others and
+        // leaves the run starting `front` places into the buffer
+        let last = items.split_off(items.len() - front);
+        deque.extend(last);
+        deque.extend(items);
+        deque.rotate_left(front);
+        if !deque.as_slices().1.is_empty() {
+            deque.make_contiguous();
+        }
+        self.deque = deque;
+    }
+}
+
+impl<T> Default for Entries<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for Entries<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for Entries<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T> From<Vec<T>> for Entries<T> {
+    fn from(items: Vec<T>) -> Self {
+        Self {
+            deque: VecDeque::from(items),
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Entries<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            deque: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Extend<T> for Entries<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T> IntoIterator for Entries<T> {
+    type Item = T;
+    type IntoIter = vec_deque::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.deque.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Entries<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Entries<T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_mut_slice().iter_mut()
+    }
+}
@@ -0,0 +1,303 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    ops::RangeBounds,
+};
+
+use crate::{map_index::Generation, SeqMapError, SeqMapIndex};
+
+/// An insertion ordered map with room for at most `N` entries, which never allocates.
+///
+/// All entries live in an array inside the map itself, and keys are found by comparing them
+/// along the entries, so keys only need to be [`Eq`]. Lookups are linear, which is intended for
+/// small `N`. Once the map is full, [`insert`](Self::insert) fails with
+/// `SeqMapError::CapacityExceeded` instead of growing.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::{FixedSeqMap, SeqMapError};
+/// let mut map: FixedSeqMap<u8, &str, 2> = FixedSeqMap::new();
+/// map.insert(2, "two").unwrap();
+/// map.insert(1, "one").unwrap();
+/// assert!(matches!(
+///     map.insert(3, "three"),
+///     Err(SeqMapError::CapacityExceeded { capacity: 2 })
+/// ));
+/// assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![2, 1]);
+/// ```
+#[derive(Clone)]
+pub struct FixedSeqMap<K, V, const N: usize> {
+    // The first `len` slots are occupied, in insertion order
+    entries: [Option<(K, V)>; N],
+    len: usize,
+    // Changed whenever entries are removed, to catch stale `SeqMapIndex` values in debug builds
+    generation: Generation,
+}
+
+impl<K, V, const N: usize> FixedSeqMap<K, V, N>
+where
+    K: Eq,
+{
+    /// Creates a new, empty `FixedSeqMap`.
+    pub fn new() -> Self {
+        Self {
+            entries: std::array::from_fn(|_| None),
+            len: 0,
+            generation: Generation::new(),
+        }
+    }
+
+    /// Returns the maximum number of entries, `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if no more entries can be inserted.
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Inserts a key-value pair at the end of the insertion order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present, and
+    /// `SeqMapError::CapacityExceeded` if the map already holds `N` entries. In both cases the
+    /// map is left unchanged.
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), SeqMapError<K>> {
+        self.insert_full(key, value).map(|_| ())
+    }
+
+    /// Inserts a key-value pair like [`insert`](Self::insert), returning the position of the
+    /// new entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present, and
+    /// `SeqMapError::CapacityExceeded` if the map already holds `N` entries. In both cases the
+    /// map is left unchanged.
+    pub fn insert_full(&mut self, key: K, value: V) -> Result<SeqMapIndex<K, V>, SeqMapError<K>> {
+        if self.contains_key(&key) {
+            return Err(SeqMapError::KeyAlreadyExists(key));
+        }
+        if self.is_full() {
+            return Err(SeqMapError::CapacityExceeded { capacity: N });
+        }
+        let index = self.len;
+        self.entries[index] = Some((key, value));
+        self.len += 1;
+        self.generation.draw_once();
+        Ok(self.generation.index(index))
+    }
+
+    /// Removes a key from the map, returning the value if it existed.
+    ///
+    /// The order of the remaining entries is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::FixedSeqMap;
+    /// let mut map: FixedSeqMap<&str, i32, 4> = FixedSeqMap::new();
+    /// map.insert("a", 1).unwrap();
+    /// map.insert("b", 2).unwrap();
+    /// map.insert("c", 3).unwrap();
+    /// assert_eq!(map.remove(&"a"), Some(1));
+    /// assert_eq!(map.get_index(&"c").map(usize::from), Some(1));
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let index = self.position_of(key)?;
+        self.generation.bump();
+        let (_, value) = self.entries[index].take()?;
+        self.entries[index..self.len].rotate_left(1);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Removes all elements from the map.
+    pub fn clear(&mut self) {
+        self.generation.bump();
+        for slot in &mut self.entries[..self.len] {
+            *slot = None;
+        }
+        self.len = 0;
+    }
+
+    /// Retrieves a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.iter()
+            .find(|(k, _)| (*k).borrow() == key)
+            .map(|(_, v)| v)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.iter_mut()
+            .find(|(k, _)| (*k).borrow() == key)
+            .map(|(_, v)| v)
+    }
+
+    /// Returns the insertion index of the key.
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.position_of(key)
+            .map(|index| self.generation.index(index))
+    }
+
+    /// Returns the key and value at `index`, or `None` if it is out of bounds.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `index` was taken before entries were removed, or was taken
+    /// from another map.
+    pub fn get_at(&self, index: SeqMapIndex<K, V>) -> Option<(&K, &V)> {
+        self.iter().nth(self.generation.position(index))
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.position_of(key).is_some()
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns an iterator over the entries in insertion order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> {
+        self.iter_range(..)
+    }
+
+    /// Returns an iterator over the entries in insertion order, with mutable values.
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (&K, &mut V)> {
+        self.entries[..self.len]
+            .iter_mut()
+            .flatten()
+            .map(|(k, v)| (&*k, v))
+    }
+
+    /// Returns an iterator over the entries in the positional `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn iter_range<R>(&self, range: R) -> impl DoubleEndedIterator<Item = (&K, &V)>
+    where
+        R: RangeBounds<usize>,
+    {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        self.entries[..self.len][bounds]
+            .iter()
+            .flatten()
+            .map(|(k, v)| (k, v))
+    }
+
+    /// Returns an iterator over the keys in insertion order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+
+    /// Returns an iterator over the values in insertion order.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+
+    /// Returns an iterator over mutable references to the values in insertion order.
+    pub fn values_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut V> {
+        self.iter_mut().map(|(_, v)| v)
+    }
+
+    /// Returns the index, key and value of the first entry, in insertion order, that matches
+    /// `pred`.
+    pub fn find<F>(&self, mut pred: F) -> Option<(SeqMapIndex<K, V>, &K, &V)>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.iter()
+            .enumerate()
+            .find(|(_, (key, value))| pred(key, value))
+            .map(|(index, (key, value))| (self.generation.index(index), key, value))
+    }
+
+    fn position_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.keys().position(|k| k.borrow() == key)
+    }
+}
+
+impl<K, V, const N: usize> Default for FixedSeqMap<K, V, N>
+where
+    K: Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, const N: usize> PartialEq for FixedSeqMap<K, V, N>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.entries[..self.len] == other.entries[..other.len]
+    }
+}
+
+impl<K, V, const N: usize> Eq for FixedSeqMap<K, V, N>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V, const N: usize> Debug for FixedSeqMap<K, V, N>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "FixedSeqMap ")?;
+        f.debug_map()
+            .entries(
+                self.entries[..self.len]
+                    .iter()
+                    .flatten()
+                    .map(|(k, v)| (k, v)),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,242 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    hash::{BuildHasher, Hash},
+    ops::Index,
+};
+
+use hashbrown::HashTable;
+
+use crate::{
+    key_index::{from_slot, KeyIndex, Slot},
+    map_index::Generation,
+    DefaultHashBuilder, SeqMap, SeqMapIndex,
+};
+
+/// An insertion ordered map that can no longer change, made by [`SeqMap::shrink_to_frozen`].
+///
+/// The entries are a boxed slice and the index is sized for exactly the entries there are,
+/// so nothing is reserved for growth. The cached key hashes a `SeqMap` keeps for rebuilding
+/// its index are dropped as well. Use it for maps that are built once, during loading, and
+/// only read afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::SeqMap;
+/// let mut map = SeqMap::new();
+/// map.insert("grass", 1).unwrap();
+/// map.insert("stone", 2).unwrap();
+/// let frozen = map.shrink_to_frozen();
+/// assert_eq!(frozen.get("stone"), Some(&2));
+/// assert_eq!(frozen.as_entries(), &[("grass", 1), ("stone", 2)]);
+/// ```
+#[derive(Clone)]
+pub struct FrozenSeqMap<K, V, S = DefaultHashBuilder> {
+    // Positions in `entries`, hashed by the key at that position. Empty for small maps
+    key_to_index: HashTable<Slot>,
+    hash_builder: S,
+    entries: Box<[(K, V)]>,
+    // The generation of the map it was made from, as entries never move afterwards
+    generation: Generation,
+}
+
+impl<K, V, S> FrozenSeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Retrieves a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Returns the insertion index of the key.
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| self.generation.index(index))
+    }
+
+    /// Returns the key and value at `index`, or `None` if it is out of bounds.
+    ///
+    /// Indexes taken from the `SeqMap` it was made from stay valid.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `index` was taken from another map.
+    pub fn get_at(&self, index: SeqMapIndex<K, V>) -> Option<(&K, &V)> {
+        self.entries
+            .get(self.generation.position(index))
+            .map(|(k, v)| (k, v))
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).is_some()
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entries in insertion order.
+    pub fn as_entries(&self) -> &[(K, V)] {
+        &self.entries
+    }
+
+    /// Returns an iterator over the entries in insertion order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Returns an iterator over the keys in insertion order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Returns an iterator over the values in insertion order.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    /// Turns the map back into a [`SeqMap`] that can change, hashing every key once to cache
+    /// its hash again. The index is kept as it is.
+    pub fn into_seq_map(self) -> SeqMap<K, V, S> {
+        let mut map = SeqMap::from_entries(
+            self.hash_builder,
+            self.entries.into_vec().into(),
+            self.generation,
+        );
+        if !self.key_to_index.is_empty() {
+            let hash_builder = &map.hash_builder;
+            map.key_index = KeyIndex::from_parts(
+                self.key_to_index,
+                map.entries
+                    .iter()
+                    .map(|(key, _)| hash_builder.hash_one(key)),
+            );
+        }
+        map.check_invariants();
+        map
+    }
+
+    fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        if self.key_to_index.is_empty() {
+            return self.entries.iter().position(|(k, _)| k.borrow() == key);
+        }
+        let hash = self.hash_builder.hash_one(key);
+        self.key_to_index
+            .find(hash, |&slot| {
+                self.entries[from_slot(slot)].0.borrow() == key
+            })
+            .map(|&slot| from_slot(slot))
+    }
+}
+
+impl<K, V, S> SeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Consumes the map and returns a [`FrozenSeqMap`] with the same entries, which holds no
+    /// spare capacity and can no longer change.
+    ///
+    /// No key is hashed.
+    pub fn shrink_to_frozen(mut self) -> FrozenSeqMap<K, V, S> {
+        self.shrink_to_fit();
+        FrozenSeqMap {
+            key_to_index: self.key_index.into_slots(),
+            hash_builder: self.hash_builder,
+            entries: self.entries.into_vec().into_boxed_slice(),
+            generation: self.generation,
+        }
+    }
+}
+
+impl<K, V, S> From<SeqMap<K, V, S>> for FrozenSeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn from(map: SeqMap<K, V, S>) -> Self {
+        map.shrink_to_frozen()
+    }
+}
+
+impl<K, V, S, Q> Index<&Q> for FrozenSeqMap<K, V, S>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    /// # Panics
+    ///
+    /// Panics if the key is not present.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("key not found in FrozenSeqMap")
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a FrozenSeqMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = std::iter::Map<std::slice::Iter<'a, (K, V)>, fn(&'a (K, V)) -> (&'a K, &'a V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+}
+
+impl<K, V, S> PartialEq for FrozenSeqMap<K, V, S>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<K, V, S> Eq for FrozenSeqMap<K, V, S>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V, S> Debug for FrozenSeqMap<K, V, S>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "FrozenSeqMap ")?;
+        f.debug_map().entries(self).finish()
+    }
+}
@@ -0,0 +1,58 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use crate::SeqMap;
+
+impl<K, V> SeqMap<K, V>
+where
+    K: AsRef<str>,
+{
+    /// Returns up to `limit` keys within `max_distance` edits of `query`, closest first.
+    ///
+    /// The distance is the Levenshtein distance over `char`s. Keys at the same distance are
+    /// returned in insertion order, so the result is deterministic. Meant for "did you mean"
+    /// style diagnostics, it scans every key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("foo_bar", 1), ("foo_baz", 2), ("unrelated", 3)]);
+    /// assert_eq!(map.closest_keys("foo_bat", 1, 5), vec![&"foo_bar", &"foo_baz"]);
+    /// assert!(map.closest_keys("xyz", 1, 5).is_empty());
+    /// ```
+    pub fn closest_keys(&self, query: &str, max_distance: usize, limit: usize) -> Vec<&K> {
+        let query: Vec<char> = query.chars().collect();
+        let mut candidates: Vec<(usize, &K)> = self
+            .entries
+            .iter()
+            .filter_map(|(key, _)| {
+                let distance = edit_distance(&query, key.as_ref());
+                (distance <= max_distance).then_some((distance, key))
+            })
+            .collect();
+        // Stable sort keeps insertion order among equal distances
+        candidates.sort_by_key(|(distance, _)| *distance);
+        candidates
+            .into_iter()
+            .take(limit)
+            .map(|(_, key)| key)
+            .collect()
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, using a single row of the distance matrix.
+fn edit_distance(a: &[char], b: &str) -> usize {
+    let mut row: Vec<usize> = (0..=a.len()).collect();
+    for (j, b_char) in b.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = j + 1;
+        for (i, &a_char) in a.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != b_char);
+            diagonal = row[i + 1];
+            row[i + 1] = substitution.min(row[i] + 1).min(diagonal + 1);
+        }
+    }
+    row[a.len()]
+}
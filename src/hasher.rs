@@ -0,0 +1,79 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// The multiplier of the Fx hash, as used by the Rust compiler.
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// A fast, non-cryptographic hasher in the style of the Fx hash used by the Rust compiler.
+///
+/// It always starts from the same state, so a key hashes to the same value in every run and
+/// insert and lookup costs are reproducible. It does not protect against keys chosen to
+/// collide, so only use it for keys that do not come from untrusted input.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::{FxBuildHasher, SeqMap};
+/// let mut map = SeqMap::with_hasher(FxBuildHasher::default());
+/// map.insert("symbol", 7).unwrap();
+/// assert_eq!(map["symbol"], 7);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+impl FxHasher {
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            self.add_to_hash(u64::from_le_bytes(word));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add_to_hash(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        // The multiply leaves the trailing zero bits of a word in place, and the hash table
+        // picks buckets by the low bits, so move the well mixed high bits down
+        self.hash.rotate_left(26)
+    }
+}
+
+/// Builds [`FxHasher`]s, all starting from the same fixed state.
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
@@ -0,0 +1,602 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    any::Any,
+    borrow::Borrow,
+    collections::{btree_map, BTreeMap, HashMap},
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+    iter::FusedIterator,
+};
+
+use crate::{entries::Entries, SeqMap, SeqMapError};
+
+/// Keys ordered by the stamp their entry got when it was inserted.
+///
+/// Stamps only grow, so this is the insertion order of the map, and unlike positions they do
+/// not change when an earlier entry is removed. Adding or removing a key is O(log n).
+type StampedKeys<K> = BTreeMap<u64, K>;
+
+/// A secondary index with its field type erased, so indexes over fields of different types
+/// can be kept side by side.
+trait ErasedIndex<K, V> {
+    /// Adds the entry with `stamp` that was just appended to the map.
+    fn insert(&mut self, stamp: u64, key: &K, value: &V);
+    fn remove(&mut self, stamp: u64, value: &V);
+    /// Takes out the entry with `stamp`, the newest one, without looking at its value.
+    fn remove_newest(&mut self, stamp: u64);
+    fn clear(&mut self);
+    /// Returns the keys having `field`, or `None` if `field` is not of the field type.
+    fn lookup(&self, field: &dyn Any) -> Option<MatchingKeys<'_, K>>;
+    /// Remembers the field of `value` before it is changed by [`IndexedSeqMap::update`].
+    fn begin_update(&mut self, value: &V);
+    /// Moves the entry with `stamp` to the bucket of its changed `value`, if the field
+    /// changed.
+    fn finish_update(&mut self, stamp: u64, value: &V);
+}
+
+/// A named secondary index, mapping an extracted field to the keys having it.
+struct SecondaryIndex<K, V, I> {
+    extract: Box<dyn Fn(&V) -> I>,
+    buckets: HashMap<I, StampedKeys<K>>,
+    /// The field of the value being updated, from before the update.
+    before_update: Option<I>,
+}
+
+impl<K, V, I> SecondaryIndex<K, V, I>
+where
+    I: Eq + Hash,
+{
+    /// Takes the entry with `stamp` out of the bucket for `field`, dropping the bucket once
+    /// it is empty.
+    fn take(&mut self, field: &I, stamp: u64) -> Option<K> {
+        let bucket = self.buckets.get_mut(field)?;
+        let key = bucket.remove(&stamp);
+        if bucket.is_empty() {
+            self.buckets.remove(field);
+        }
+        key
+    }
+}
+
+impl<K, V, I> ErasedIndex<K, V> for SecondaryIndex<K, V, I>
+where
+    K: Eq + Hash + Clone,
+    I: Eq + Hash + 'static,
+{
+    fn insert(&mut self, stamp: u64, key: &K, value: &V) {
+        self.buckets
+            .entry((self.extract)(value))
+            .or_default()
+            .insert(stamp, key.clone());
+    }
+
+    fn remove(&mut self, stamp: u64, value: &V) {
+        let field = (self.extract)(value);
+        self.take(&field, stamp);
+    }
+
+    fn remove_newest(&mut self, stamp: u64) {
+        // The newest entry is last in its bucket, whichever bucket that is
+        self.buckets.retain(|_, bucket| {
+            if bucket
+                .last_key_value()
+                .is_some_and(|(&last, _)| last == stamp)
+            {
+                bucket.pop_last();
+            }
+            !bucket.is_empty()
+        });
+    }
+
+    fn clear(&mut self) {
+        self.buckets.clear();
+        self.before_update = None;
+    }
+
+    fn lookup(&self, field: &dyn Any) -> Option<MatchingKeys<'_, K>> {
+        let field = field.downcast_ref::<I>()?;
+        Some(MatchingKeys::new(self.buckets.get(field)))
+    }
+
+    fn begin_update(&mut self, value: &V) {
+        self.before_update = Some((self.extract)(value));
+    }
+
+    fn finish_update(&mut self, stamp: u64, value: &V) {
+        let Some(old_field) = self.before_update.take() else {
+            return;
+        };
+        let new_field = (self.extract)(value);
+        if new_field == old_field {
+            return;
+        }
+        if let Some(key) = self.take(&old_field, stamp) {
+            self.buckets
+                .entry(new_field)
+                .or_default()
+                .insert(stamp, key);
+        }
+    }
+}
+
+/// An iterator over the keys of an index bucket, in insertion order.
+///
+/// Created by [`IndexedSeqMap::lookup_index`] and [`IndexedSeqMap::query`].
+#[derive(Clone, Debug)]
+pub struct MatchingKeys<'a, K> {
+    inner: Option<btree_map::Values<'a, u64, K>>,
+}
+
+impl<'a, K> MatchingKeys<'a, K> {
+    fn new(keys: Option<&'a StampedKeys<K>>) -> Self {
+        Self {
+            inner: keys.map(BTreeMap::values),
+        }
+    }
+}
+
+impl<'a, K> Iterator for MatchingKeys<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut()?.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner
+            .as_ref()
+            .map_or((0, Some(0)), Iterator::size_hint)
+    }
+}
+
+impl<K> DoubleEndedIterator for MatchingKeys<'_, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut()?.next_back()
+    }
+}
+
+impl<K> ExactSizeIterator for MatchingKeys<'_, K> {}
+
+impl<K> FusedIterator for MatchingKeys<'_, K> {}
+
+/// A named predicate together with the keys of the values currently matching it.
+struct CachedQuery<K, V> {
+    predicate: Box<dyn Fn(&V) -> bool>,
+    matches: StampedKeys<K>,
+    /// Whether the value being updated matched, from before the update.
+    before_update: Option<bool>,
+}
+
+impl<K, V> CachedQuery<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    fn begin_update(&mut self, value: &V) {
+        self.before_update = Some((self.predicate)(value));
+    }
+
+    fn finish_update(&mut self, stamp: u64, key: &K, value: &V) {
+        let Some(was_matching) = self.before_update.take() else {
+            return;
+        };
+        match ((self.predicate)(value), was_matching) {
+            (true, false) => {
+                self.matches.insert(stamp, key.clone());
+            }
+            (false, true) => {
+                self.matches.remove(&stamp);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Takes the entry that [`IndexedSeqMap::insert`] appended out of the map and of the first
+/// `indexed` indexes and `queried` queries again when dropped before the insert finished.
+///
+/// No extractor or predicate is called, since a second panic while unwinding would abort.
+struct InsertGuard<'a, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    map: &'a mut SeqMap<K, V>,
+    indexes: &'a mut SeqMap<&'static str, Box<dyn ErasedIndex<K, V>>>,
+    queries: &'a mut SeqMap<&'static str, CachedQuery<K, V>>,
+    stamp: u64,
+    indexed: usize,
+    queried: usize,
+    finished: bool,
+}
+
+impl<K, V> Drop for InsertGuard<'_, K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        for index in self.indexes.values_mut().take(self.indexed) {
+            index.remove_newest(self.stamp);
+        }
+        for query in self.queries.values_mut().take(self.queried) {
+            query.matches.remove(&self.stamp);
+        }
+        self.map.pop_last().expect("inserted entry is last");
+    }
+}
+
+/// A [`SeqMap`] with named secondary indexes over fields of its values.
+///
+/// Every index is declared with a function that extracts a field from a value, and every index
+/// can extract a field of its own type. The indexes are kept in sync on every mutation, which
+/// is why values can only be changed through [`update`](Self::update) and not through a plain
+/// mutable reference.
+///
+/// Predicates registered with [`add_query`](Self::add_query) are maintained the same way, so
+/// [`query`](Self::query) returns the matching keys without scanning the values.
+///
+/// Every index bucket and query keeps its keys ordered by when they were inserted, so
+/// inserting, removing and moving a key between buckets or in and out of a query is O(log n)
+/// no matter how many keys share a bucket or match a query.
+pub struct IndexedSeqMap<K, V> {
+    map: SeqMap<K, V>,
+    // The insertion stamp of the entry at every position of `map`
+    stamps: Entries<u64>,
+    next_stamp: u64,
+    indexes: SeqMap<&'static str, Box<dyn ErasedIndex<K, V>>>,
+    queries: SeqMap<&'static str, CachedQuery<K, V>>,
+    // The position of an entry whose update panicked, to be brought up to date in the indexes
+    // and queries by the next mutation
+    pending_update: Option<usize>,
+}
+
+impl<K, V> IndexedSeqMap<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Creates a new, empty `IndexedSeqMap` without any indexes.
+    pub fn new() -> Self {
+        Self::from(SeqMap::new())
+    }
+
+    /// Brings the indexes and queries up to date with an entry whose update panicked.
+    fn finish_pending_update(&mut self) {
+        let Some(position) = self.pending_update.take() else {
+            return;
+        };
+        let stamp = self.stamps[position];
+        let (key, value) = &self.map.entries[position];
+        for index in self.indexes.values_mut() {
+            index.finish_update(stamp, value);
+        }
+        for query in self.queries.values_mut() {
+            query.finish_update(stamp, key, value);
+        }
+    }
+
+    /// Declares a new index called `name`, built right away from the existing entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` with the name if an index with that name
+    /// already exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::IndexedSeqMap;
+    /// let mut map = IndexedSeqMap::new();
+    /// map.insert("x", ("int", 1)).unwrap();
+    /// map.insert("y", ("bool", 2)).unwrap();
+    /// map.insert("z", ("int", 3)).unwrap();
+    /// map.add_index("by_type", |(ty, _)| *ty).unwrap();
+    /// map.add_index("by_size", |(_, size)| *size > 1).unwrap();
+    /// assert!(map.lookup_index("by_type", &"int").unwrap().eq(["x", "z"].iter()));
+    /// assert!(map.lookup_index("by_size", &true).unwrap().eq(["y", "z"].iter()));
+    /// ```
+    pub fn add_index<I, F>(
+        &mut self,
+        name: &'static str,
+        extract: F,
+    ) -> Result<(), SeqMapError<&'static str>>
+    where
+        K: 'static,
+        V: 'static,
+        I: Eq + Hash + 'static,
+        F: Fn(&V) -> I + 'static,
+    {
+        if self.indexes.contains_key(&name) {
+            return Err(SeqMapError::KeyAlreadyExists(name));
+        }
+        self.finish_pending_update();
+        let mut index = SecondaryIndex {
+            extract: Box::new(extract),
+            buckets: HashMap::new(),
+            before_update: None,
+        };
+        for ((key, value), &stamp) in self.map.iter().zip(self.stamps.iter()) {
+            index.insert(stamp, key, value);
+        }
+        self.indexes.insert(name, Box::new(index))
+    }
+
+    /// Removes the index called `name`, returning `true` if it existed.
+    pub fn remove_index(&mut self, name: &'static str) -> bool {
+        self.indexes.remove(&name).is_some()
+    }
+
+    /// Returns the keys whose value has `field` in the index called `name`, in insertion order.
+    ///
+    /// Returns `None` if there is no index with that name, or if its fields are not of type
+    /// `I`.
+    pub fn lookup_index<I>(&self, name: &'static str, field: &I) -> Option<MatchingKeys<'_, K>>
+    where
+        I: Eq + Hash + 'static,
+    {
+        self.indexes.get(&name)?.lookup(field)
+    }
+
+    /// Registers a predicate called `name` whose matching keys are cached and kept up to date
+    /// as the map is mutated.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` with the name if a query with that name already
+    /// exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::IndexedSeqMap;
+    /// let mut map = IndexedSeqMap::new();
+    /// map.insert("a", (false, 1)).unwrap();
+    /// map.insert("b", (true, 2)).unwrap();
+    /// map.add_query("dirty", |(dirty, _)| *dirty).unwrap();
+    /// map.insert("c", (true, 3)).unwrap();
+    /// map.update(&"b", |(dirty, _)| *dirty = false);
+    /// assert!(map.query("dirty").unwrap().eq(["c"].iter()));
+    /// ```
+    pub fn add_query<F>(
+        &mut self,
+        name: &'static str,
+        predicate: F,
+    ) -> Result<(), SeqMapError<&'static str>>
+    where
+        F: Fn(&V) -> bool + 'static,
+    {
+        if self.queries.contains_key(&name) {
+            return Err(SeqMapError::KeyAlreadyExists(name));
+        }
+        self.finish_pending_update();
+        let matches = self
+            .map
+            .iter()
+            .zip(self.stamps.iter())
+            .filter(|((_, value), _)| predicate(value))
+            .map(|((key, _), &stamp)| (stamp, key.clone()))
+            .collect();
+        self.queries.insert(
+            name,
+            CachedQuery {
+                predicate: Box::new(predicate),
+                matches,
+                before_update: None,
+            },
+        )
+    }
+
+    /// Removes the query called `name`, returning `true` if it existed.
+    pub fn remove_query(&mut self, name: &'static str) -> bool {
+        self.queries.remove(&name).is_some()
+    }
+
+    /// Returns the keys whose value currently matches the query called `name`, in insertion
+    /// order.
+    ///
+    /// Returns `None` if there is no query with that name.
+    pub fn query(&self, name: &'static str) -> Option<MatchingKeys<'_, K>> {
+        self.queries
+            .get(&name)
+            .map(|query| MatchingKeys::new(Some(&query.matches)))
+    }
+
+    /// Inserts a key-value pair and adds it to all indexes and matching queries.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    ///
+    /// If an index or a query panics, the entry is taken out of the map and of the indexes
+    /// and queries it was already added to, so the map is left as it was.
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), SeqMapError<K>> {
+        self.finish_pending_update();
+        self.map.insert(key, value)?;
+        let stamp = self.next_stamp;
+        let mut guard = InsertGuard {
+            map: &mut self.map,
+            indexes: &mut self.indexes,
+            queries: &mut self.queries,
+            stamp,
+            indexed: 0,
+            queried: 0,
+            finished: false,
+        };
+        let (key, value) = guard.map.entries.last().expect("just inserted");
+        for index in guard.indexes.values_mut() {
+            index.insert(stamp, key, value);
+            guard.indexed += 1;
+        }
+        for query in guard.queries.values_mut() {
+            if (query.predicate)(value) {
+                query.matches.insert(stamp, key.clone());
+            }
+            guard.queried += 1;
+        }
+        guard.finished = true;
+        self.stamps.push(stamp);
+        self.next_stamp += 1;
+        Ok(())
+    }
+
+    /// Removes a key from the map, all indexes and all queries, returning the value if it
+    /// existed.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.finish_pending_update();
+        let (position, _, value) = self.map.remove_full(key)?;
+        let stamp = self.stamps.remove(position);
+        for index in self.indexes.values_mut() {
+            index.remove(stamp, &value);
+        }
+        for query in self.queries.values_mut() {
+            query.matches.remove(&stamp);
+        }
+        Some(value)
+    }
+
+    /// Modifies the value for `key` with `f` and moves it between index buckets and query
+    /// results if an indexed field or a predicate outcome changed. Returns `true` if the key was
+    /// present.
+    ///
+    /// If `f` panics, no extractor or predicate is called while unwinding, since a second
+    /// panic would abort. The indexes and queries keep the entry where it was until the next
+    /// call that changes the map brings them up to date with whatever `f` left in the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::IndexedSeqMap;
+    /// let mut map = IndexedSeqMap::new();
+    /// map.add_index("dirty", |dirty: &bool| *dirty).unwrap();
+    /// map.insert("a", false).unwrap();
+    /// map.insert("b", false).unwrap();
+    /// map.update(&"b", |dirty| *dirty = true);
+    /// assert!(map.lookup_index("dirty", &true).unwrap().eq(["b"].iter()));
+    /// ```
+    pub fn update<Q, F>(&mut self, key: &Q, f: F) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        F: FnOnce(&mut V),
+    {
+        self.finish_pending_update();
+        let Some(position) = self.map.index_of(key) else {
+            return false;
+        };
+        let value = &self.map.entries[position].1;
+        for index in self.indexes.values_mut() {
+            index.begin_update(value);
+        }
+        for query in self.queries.values_mut() {
+            query.begin_update(value);
+        }
+        // Stays set if `f` panics, for the next mutation to pick up
+        self.pending_update = Some(position);
+        f(&mut self.map.entries[position].1);
+        self.finish_pending_update();
+        true
+    }
+
+    /// Removes all elements from the map and empties all indexes and queries. The indexes and
+    /// queries stay registered.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.stamps.clear();
+        self.pending_update = None;
+        for index in self.indexes.values_mut() {
+            index.clear();
+        }
+        for query in self.queries.values_mut() {
+            query.matches.clear();
+            query.before_update = None;
+        }
+    }
+
+    /// Retrieves a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get(key)
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator over the entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.map.iter()
+    }
+
+    /// Returns the underlying map.
+    pub fn as_seq_map(&self) -> &SeqMap<K, V> {
+        &self.map
+    }
+
+    /// Consumes the wrapper, dropping the indexes, and returns the underlying map.
+    pub fn into_seq_map(self) -> SeqMap<K, V> {
+        self.map
+    }
+}
+
+impl<K, V> Default for IndexedSeqMap<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> From<SeqMap<K, V>> for IndexedSeqMap<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Wraps an existing map. Indexes can be added afterwards with
+    /// [`add_index`](IndexedSeqMap::add_index).
+    fn from(map: SeqMap<K, V>) -> Self {
+        let len = map.len() as u64;
+        Self {
+            map,
+            stamps: (0..len).collect(),
+            next_stamp: len,
+            indexes: SeqMap::new(),
+            queries: SeqMap::new(),
+            pending_update: None,
+        }
+    }
+}
+
+impl<K, V> Debug for IndexedSeqMap<K, V>
+where
+    K: Eq + Hash + Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Indexed{:?}", self.map)
+    }
+}
@@ -0,0 +1,156 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::HashSet,
+    fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::Arc,
+};
+
+/// A string key handed out by an [`Interner`], sharing its storage with every other
+/// `Interned` of the same string.
+///
+/// Cloning only bumps a reference count, so many maps keyed by the same identifiers hold the
+/// text once. `Interned` compares, hashes and orders like the string it holds, and borrows as
+/// a `str`, so maps keyed by it can be indexed with plain `&str`.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::{Interner, SeqMap};
+/// let mut interner = Interner::new();
+/// let mut map = SeqMap::new();
+/// map.insert(interner.intern("position"), 1).unwrap();
+/// assert_eq!(map["position"], 1);
+/// ```
+#[derive(Clone)]
+pub struct Interned(Arc<str>);
+
+impl Interned {
+    /// Returns the interned string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns `true` if both keys share the same storage, which is the case for keys from the
+    /// same [`Interner`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl PartialEq for Interned {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.0 == other.0
+    }
+}
+
+impl Eq for Interned {}
+
+impl Hash for Interned {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Must hash like `str`, since it borrows as one
+        self.as_str().hash(state);
+    }
+}
+
+impl PartialOrd for Interned {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interned {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Borrow<str> for Interned {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Interned {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Deref for Interned {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Debug for Interned {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for Interned {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+/// Hands out [`Interned`] keys, storing every distinct string once.
+///
+/// Interning the same string again returns a key sharing the storage of the first one. The
+/// strings stay alive as long as the interner or any key handed out for them does.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::Interner;
+/// let mut interner = Interner::new();
+/// let a = interner.intern("health");
+/// let b = interner.intern(&String::from("health"));
+/// assert!(a.ptr_eq(&b));
+/// assert_eq!(interner.len(), 1);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+    strings: HashSet<Interned>,
+}
+
+impl Interner {
+    /// Creates a new, empty `Interner`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the key for `string`, storing the string if it has not been interned yet.
+    pub fn intern(&mut self, string: &str) -> Interned {
+        if let Some(interned) = self.strings.get(string) {
+            return interned.clone();
+        }
+        let interned = Interned(Arc::from(string));
+        self.strings.insert(interned.clone());
+        interned
+    }
+
+    /// Returns the key for `string` if it has been interned, without storing it otherwise.
+    pub fn get(&self, string: &str) -> Option<Interned> {
+        self.strings.get(string).cloned()
+    }
+
+    /// Returns the number of distinct strings interned.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns `true` if no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
@@ -0,0 +1,700 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#[cfg(not(feature = "alloc_map"))]
+use std::marker::PhantomData;
+use std::{iter, mem, ops::Range};
+
+#[cfg(feature = "alloc_map")]
+use allocator_api2::{
+    alloc::{Allocator, Global},
+    vec::Vec,
+};
+use hashbrown::{hash_table, HashTable};
+
+#[cfg(not(feature = "alloc_map"))]
+use self::global::{Allocator, Global};
+
+/// Without the `alloc_map` feature the index is always in the global allocator, and these
+/// stand in for the allocator API, so the index types keep their allocator parameter.
+#[cfg(not(feature = "alloc_map"))]
+mod global {
+    pub(crate) trait Allocator {}
+
+    #[derive(Clone, Copy)]
+    pub(crate) struct Global;
+
+    impl Allocator for Global {}
+}
+
+/// The position of an entry, as stored in the index.
+#[cfg(not(feature = "compact_index"))]
+pub(crate) type Slot = usize;
+/// The position of an entry, as stored in the index. Half the size of a `usize` on 64-bit
+/// targets, which makes the index table smaller.
+#[cfg(feature = "compact_index")]
+pub(crate) type Slot = u32;
+
+#[cfg(not(feature = "compact_index"))]
+pub(crate) const fn to_slot(index: usize) -> Slot {
+    index
+}
+
+/// # Panics
+///
+/// Panics if `index` does not fit in a `u32`.
+#[cfg(feature = "compact_index")]
+pub(crate) fn to_slot(index: usize) -> Slot {
+    Slot::try_from(index).expect("SeqMap with compact_index can hold at most u32::MAX entries")
+}
+
+#[cfg(not(feature = "compact_index"))]
+pub(crate) const fn from_slot(slot: Slot) -> usize {
+    slot
+}
+
+#[cfg(feature = "compact_index")]
+pub(crate) const fn from_slot(slot: Slot) -> usize {
+    slot as usize
+}
+
+/// Translates a slot in the index to the position of its entry, skipping the positions of
+/// the removed entries in `removed_slots` that come before it.
+pub(crate) fn position(removed_slots: &[Slot], slot: Slot) -> usize {
+    from_slot(slot) - removed_slots.partition_point(|&removed| removed < slot)
+}
+
+/// The key index shared by the maps of this crate: a hash table of the positions of the
+/// entries, and the hash of the key at every position.
+///
+/// The index does not hold the keys, so the entries can be stored however the map likes.
+/// Methods that compare keys take an `eq` closure, which is given the position of an entry
+/// whose cached hash matches.
+///
+/// Removing an entry does not renumber the slots of the later entries. The removed slot is
+/// recorded in `removed_slots` instead, and all slots are renumbered at once when removals
+/// add up to a fraction of the entries. The hash of a removed entry in the first half is
+/// dropped by moving the hashes before it one place up and starting the hashes one place
+/// later, so removing the first entry moves no hash.
+///
+/// Inserting an entry moves the slots of the entries on the shorter side of it one slot along.
+/// The later entries move up to the next slot after all others. The earlier entries move down
+/// to the nearest removed slot below them, which takes no moves when the entry is put back in
+/// the place of a pending removal. If there is no removed slot below them, a batch of removed
+/// slots is first made below all others.
+#[derive(Clone)]
+pub(crate) struct KeyIndex<A: Allocator + Clone = Global> {
+    // Positions of the entries, hashed by the key at that position
+    #[cfg(feature = "alloc_map")]
+    slots: HashTable<Slot, A>,
+    #[cfg(not(feature = "alloc_map"))]
+    slots: HashTable<Slot>,
+    // The hash of the key at every position, from `start` on
+    #[cfg(feature = "alloc_map")]
+    hashes: Vec<u64, A>,
+    #[cfg(not(feature = "alloc_map"))]
+    hashes: Vec<u64>,
+    // The number of hashes at the front of `hashes` that belong to removed entries
+    start: usize,
+    // Sorted slots of removed entries that the slots in `slots` are not renumbered for yet
+    #[cfg(feature = "alloc_map")]
+    removed_slots: Vec<Slot, A>,
+    #[cfg(not(feature = "alloc_map"))]
+    removed_slots: Vec<Slot>,
+    #[cfg(not(feature = "alloc_map"))]
+    alloc: PhantomData<A>,
+}
+
+/// The result of [`KeyIndex::entry`].
+pub(crate) enum IndexEntry<'a, A: Allocator + Clone> {
+    /// The key is at this position.
+    Occupied(usize),
+    /// The key is missing.
+    Vacant(VacantSlot<'a, A>),
+}
+
+/// The place of a missing key in the index.
+pub(crate) struct VacantSlot<'a, A: Allocator + Clone> {
+    #[cfg(feature = "alloc_map")]
+    entry: hash_table::VacantEntry<'a, Slot, A>,
+    #[cfg(not(feature = "alloc_map"))]
+    entry: hash_table::VacantEntry<'a, Slot>,
+    #[cfg(feature = "alloc_map")]
+    hashes: &'a mut Vec<u64, A>,
+    #[cfg(not(feature = "alloc_map"))]
+    hashes: &'a mut Vec<u64>,
+    hash: u64,
+    slot: Slot,
+    #[cfg(not(feature = "alloc_map"))]
+    alloc: PhantomData<A>,
+}
+
+impl<A: Allocator + Clone> VacantSlot<'_, A> {
+    /// Adds the slot of the entry that was just appended for the missing key.
+    pub(crate) fn insert(self) {
+        self.hashes.push(self.hash);
+        self.entry.insert(self.slot);
+    }
+}
+
+impl KeyIndex {
+    /// Creates an empty index.
+    pub(crate) const fn new() -> Self {
+        Self {
+            slots: HashTable::new(),
+            hashes: Vec::new(),
+            start: 0,
+            removed_slots: Vec::new(),
+            #[cfg(not(feature = "alloc_map"))]
+            alloc: PhantomData,
+        }
+    }
+
+    /// Creates an empty index with room for `capacity` entries.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+
+    /// Creates an index from the slots of a complete table and the hashes of all keys.
+    pub(crate) fn from_parts(
+        slots: HashTable<Slot>,
+        hashes: impl IntoIterator<Item = u64>,
+    ) -> Self {
+        let mut index = Self::new();
+        index.hashes.extend(hashes);
+        index.slots = slots;
+        index
+    }
+
+    /// Renumbers all slots and returns the table, for [`FrozenSeqMap`](crate::FrozenSeqMap).
+    pub(crate) fn into_slots(mut self) -> HashTable<Slot> {
+        self.renumber();
+        self.slots
+    }
+}
+
+impl<A: Allocator + Clone> KeyIndex<A> {
+    /// Creates an empty index in `alloc` with room for `capacity` entries.
+    #[cfg(feature = "alloc_map")]
+    pub(crate) fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            slots: HashTable::with_capacity_in(capacity, alloc.clone()),
+            hashes: Vec::with_capacity_in(capacity, alloc.clone()),
+            start: 0,
+            removed_slots: Vec::new_in(alloc),
+        }
+    }
+
+    /// Creates an empty index with room for `capacity` entries.
+    #[cfg(not(feature = "alloc_map"))]
+    pub(crate) fn with_capacity_in(capacity: usize, _alloc: A) -> Self {
+        Self {
+            slots: HashTable::with_capacity(capacity),
+            hashes: Vec::with_capacity(capacity),
+            start: 0,
+            removed_slots: Vec::new(),
+            alloc: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the index holds no slots.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Returns the number of slots.
+    pub(crate) fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the hash of the key at every position.
+    pub(crate) fn hashes(&self) -> &[u64] {
+        &self.hashes[self.start..]
+    }
+
+    /// Returns the position of every slot, in table order.
+    pub(crate) fn positions(&self) -> impl Iterator<Item = usize> + '_ {
+        self.slots
+            .iter()
+            .map(|&slot| position(&self.removed_slots, slot))
+    }
+
+    /// Returns the heap bytes held by the table, the hashes and the pending removals.
+    pub(crate) fn allocation_size(&self) -> usize {
+        self.slots.allocation_size()
+            + self.hashes.capacity() * size_of::<u64>()
+            + self.removed_slots.capacity() * size_of::<Slot>()
+    }
+
+    /// Finds the position of the key with `hash` for which `eq` returns `true`.
+    pub(crate) fn find(&self, hash: u64, mut eq: impl FnMut(usize) -> bool) -> Option<usize> {
+        self.slots
+            .find(hash, |&slot| {
+                let index = position(&self.removed_slots, slot);
+                self.hashes[self.start + index] == hash && eq(index)
+            })
+            .map(|&slot| position(&self.removed_slots, slot))
+    }
+
+    /// Finds the position of the key with `hash` for which `eq` returns `true`, or the place
+    /// for it if it is missing.
+    ///
+    /// The entry for a missing key must be appended before [`VacantSlot::insert`] is called.
+    pub(crate) fn entry(
+        &mut self,
+        hash: u64,
+        mut eq: impl FnMut(usize) -> bool,
+    ) -> IndexEntry<'_, A> {
+        let slot = self.next_slot();
+        let Self {
+            slots,
+            hashes,
+            start,
+            removed_slots,
+            ..
+        } = self;
+        let entry = slots.entry(
+            hash,
+            |&slot| {
+                let index = position(removed_slots, slot);
+                hashes[*start + index] == hash && eq(index)
+            },
+            |&slot| hashes[*start + position(removed_slots, slot)],
+        );
+        match entry {
+            hash_table::Entry::Occupied(entry) => {
+                IndexEntry::Occupied(position(removed_slots, *entry.get()))
+            }
+            hash_table::Entry::Vacant(entry) => IndexEntry::Vacant(VacantSlot {
+                entry,
+                hashes,
+                hash,
+                slot,
+                #[cfg(not(feature = "alloc_map"))]
+                alloc: PhantomData,
+            }),
+        }
+    }
+
+    /// Adds the slot of an entry with `hash` appended after all others.
+    pub(crate) fn push(&mut self, hash: u64) {
+        let slot = self.next_slot();
+        self.hashes.push(hash);
+        self.insert_slot(slot);
+    }
+
+    /// Adds the slot of an entry with `hash` inserted at position `index`, moving the slots
+    /// and hashes of the entries on the shorter side of it. The entry itself is inserted by the
+    /// caller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of entries.
+    pub(crate) fn insert_at(&mut self, index: usize, hash: u64) {
+        let len = self.hashes.len() - self.start;
+        assert!(
+            index <= len,
+            "insertion index {index} is out of bounds for {len} entries"
+        );
+        let slot = if index < len / 2 {
+            let mut next = self.slot_at(index);
+            if self.start == 0 || self.removed_slots.first().is_none_or(|&slot| slot > next) {
+                self.make_front_room();
+                next = self.slot_at(index);
+            }
+            // The entries between the nearest removed slot and the slot of `index` move down
+            let below = self.removed_slots.partition_point(|&slot| slot < next);
+            let mut free = self.removed_slots[below - 1];
+            let first = from_slot(free) - (below - 1);
+            for at in first..index {
+                free = self.replace_slot(at, free);
+            }
+            self.removed_slots.remove(below - 1);
+            self.hashes
+                .copy_within(self.start..self.start + index, self.start - 1);
+            self.start -= 1;
+            self.hashes[self.start + index] = hash;
+            free
+        } else {
+            let mut free = self.next_slot();
+            for at in (index..len).rev() {
+                free = self.replace_slot(at, free);
+            }
+            self.hashes.insert(self.start + index, hash);
+            free
+        };
+        self.insert_slot(slot);
+    }
+
+    /// Removes the slot of the key with `hash` for which `eq` returns `true`, returning the
+    /// position of its entry. The entry itself is removed by the caller.
+    pub(crate) fn remove(&mut self, hash: u64, mut eq: impl FnMut(usize) -> bool) -> Option<usize> {
+        let Self {
+            slots,
+            hashes,
+            start,
+            removed_slots,
+            ..
+        } = self;
+        let entry = slots
+            .find_entry(hash, |&slot| {
+                let index = position(removed_slots, slot);
+                hashes[*start + index] == hash && eq(index)
+            })
+            .ok()?;
+        let (slot, _) = entry.remove();
+        let index = position(removed_slots, slot);
+        let at = removed_slots.partition_point(|&removed| removed < slot);
+        removed_slots.insert(at, slot);
+        let len = hashes.len() - *start;
+        if index < len / 2 {
+            hashes.copy_within(*start..*start + index, *start + 1);
+            *start += 1;
+        } else {
+            hashes.remove(*start + index);
+        }
+        if removed_slots.len() > len / 8 || slots.is_empty() {
+            self.renumber();
+        }
+        Some(index)
+    }
+
+    /// Removes the slot of the last entry, without hashing its key. The entry itself is
+    /// removed by the caller.
+    pub(crate) fn pop(&mut self) {
+        let Some(&hash) = self.hashes.get(self.start..).and_then(<[u64]>::last) else {
+            return;
+        };
+        let last = self.hashes.len() - self.start - 1;
+        let removed_slots = &self.removed_slots;
+        let entry = self
+            .slots
+            .find_entry(hash, |&slot| position(removed_slots, slot) == last)
+            .expect("every position has a slot");
+        let (slot, _) = entry.remove();
+        // No other slot comes after the last one, so the removed slots after it offset nothing
+        let offsetting = self
+            .removed_slots
+            .partition_point(|&removed| removed < slot);
+        self.removed_slots.truncate(offsetting);
+        self.hashes.pop();
+    }
+
+    /// Removes all slots and hashes.
+    pub(crate) fn clear(&mut self) {
+        self.slots.clear();
+        self.hashes.clear();
+        self.start = 0;
+        self.removed_slots.clear();
+    }
+
+    /// Makes room in the table for `additional` more entries.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        let Self {
+            slots,
+            hashes,
+            start,
+            removed_slots,
+            ..
+        } = self;
+        slots.reserve(additional, |&slot| {
+            hashes[*start + position(removed_slots, slot)]
+        });
+    }
+
+    /// Renumbers all slots and frees the capacity that is not needed.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.renumber();
+        self.removed_slots.shrink_to_fit();
+        self.hashes.shrink_to_fit();
+        let Self { slots, hashes, .. } = self;
+        slots.shrink_to_fit(|&slot| hashes[from_slot(slot)]);
+    }
+
+    /// Replaces the index with a complete one for entries with `hashes`.
+    pub(crate) fn build(&mut self, hashes: impl IntoIterator<Item = u64>) {
+        self.clear();
+        self.hashes.extend(hashes);
+        self.reindex_from(0);
+    }
+
+    /// Replaces the hashes in `range` with `hashes`. The slots are rebuilt by a following
+    /// [`reindex_from`](Self::reindex_from).
+    pub(crate) fn splice_hashes(
+        &mut self,
+        range: Range<usize>,
+        hashes: impl IntoIterator<Item = u64>,
+    ) {
+        let start = self.start;
+        self.hashes
+            .splice(start + range.start..start + range.end, hashes);
+    }
+
+    /// Rebuilds the slots of every position from `start` on, from the hashes already there.
+    pub(crate) fn reindex_from(&mut self, start: usize) {
+        self.renumber();
+        self.slots.retain(|&mut slot| from_slot(slot) < start);
+        self.reserve(self.hashes.len() - start);
+        for index in start..self.hashes.len() {
+            self.insert_slot(to_slot(index));
+        }
+    }
+
+    /// Drops the hashes and slots of the positions marked in `removed`, and moves the
+    /// remaining slots down to where their entries end up once the removed entries are gone.
+    /// The entries themselves are compacted by the caller.
+    ///
+    /// No key is hashed, since a slot stays in the same bucket when only its position changes.
+    pub(crate) fn compact(&mut self, removed: &[bool]) {
+        if self.slots.is_empty() {
+            return;
+        }
+        self.renumber();
+        let mut position = 0;
+        self.hashes.retain(|_| {
+            position += 1;
+            !removed[position - 1]
+        });
+        let mut new_positions = std::vec::Vec::with_capacity(removed.len());
+        let mut next = 0;
+        for &is_removed in removed {
+            new_positions.push(next);
+            if !is_removed {
+                next += 1;
+            }
+        }
+        self.slots.retain(|slot| {
+            let old = from_slot(*slot);
+            *slot = to_slot(new_positions[old]);
+            !removed[old]
+        });
+    }
+
+    /// Moves the hash at position `order[i]` to position `i`, and renumbers the slots to
+    /// match, for an `order` that is known to be a permutation of all positions.
+    pub(crate) fn permute(&mut self, order: &[usize]) {
+        if self.slots.is_empty() {
+            return;
+        }
+        self.renumber();
+        #[cfg(feature = "alloc_map")]
+        let mut hashes = Vec::with_capacity_in(self.hashes.len(), self.hashes.allocator().clone());
+        #[cfg(not(feature = "alloc_map"))]
+        let mut hashes = Vec::with_capacity(self.hashes.len());
+        hashes.extend(order.iter().map(|&index| self.hashes[index]));
+        self.hashes = hashes;
+        let mut new_positions = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            new_positions[old] = new;
+        }
+        for slot in self.slots.iter_mut() {
+            *slot = to_slot(new_positions[from_slot(*slot)]);
+        }
+    }
+
+    /// Returns the slot of the entry at position `index`.
+    fn slot_at(&self, index: usize) -> Slot {
+        *self
+            .slots
+            .find(self.hashes[self.start + index], |&slot| {
+                position(&self.removed_slots, slot) == index
+            })
+            .expect("every position has a slot")
+    }
+
+    /// Gives the entry at position `index` the slot `slot`, which must not be in the table,
+    /// and returns the slot it had.
+    fn replace_slot(&mut self, index: usize, slot: Slot) -> Slot {
+        let Self {
+            slots,
+            hashes,
+            start,
+            removed_slots,
+            ..
+        } = self;
+        let old = slots
+            .find_mut(hashes[*start + index], |&old| {
+                position(removed_slots, old) == index
+            })
+            .expect("every position has a slot");
+        mem::replace(old, slot)
+    }
+
+    /// Moves all slots up and records the slots below them as removed, and makes the same room
+    /// before the hashes, so a batch of entries can be inserted at the front.
+    fn make_front_room(&mut self) {
+        let room = (self.hashes.len() - self.start) / 16 + 1;
+        for slot in self.slots.iter_mut() {
+            *slot = to_slot(from_slot(*slot) + room);
+        }
+        let removed_slots = (0..room)
+            .map(to_slot)
+            .chain(
+                self.removed_slots
+                    .iter()
+                    .map(|&slot| to_slot(from_slot(slot) + room)),
+            )
+            .collect::<std::vec::Vec<_>>();
+        self.removed_slots.clear();
+        self.removed_slots.extend(removed_slots);
+        self.hashes.splice(0..0, iter::repeat_n(0, room));
+        self.start += room;
+    }
+
+    /// Adds `slot` to the table. The hash of its position must already be in `hashes`.
+    fn insert_slot(&mut self, slot: Slot) {
+        let Self {
+            slots,
+            hashes,
+            start,
+            removed_slots,
+            ..
+        } = self;
+        slots.insert_unique(
+            hashes[*start + position(removed_slots, slot)],
+            slot,
+            |&slot| hashes[*start + position(removed_slots, slot)],
+        );
+    }
+
+    /// Returns the slot for a new entry appended after all others.
+    fn next_slot(&self) -> Slot {
+        // Removed slots are still counted until the slots are renumbered
+        to_slot(self.hashes.len() - self.start + self.removed_slots.len())
+    }
+
+    /// Applies the pending removals in `removed_slots` to all slots, and drops the hashes
+    /// before `start`.
+    fn renumber(&mut self) {
+        self.hashes.drain(..self.start);
+        self.start = 0;
+        if self.removed_slots.is_empty() {
+            return;
+        }
+        let Self {
+            slots,
+            removed_slots,
+            ..
+        } = self;
+        for slot in slots.iter_mut() {
+            *slot = to_slot(position(removed_slots, *slot));
+        }
+        removed_slots.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::KeyIndex;
+
+    fn hash(key: u64) -> u64 {
+        key.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
+
+    #[test]
+    fn removing_the_oldest_slot_renumbers_in_batches() {
+        let len = 10_000;
+        let ticks = 5 * len;
+        let mut keys: VecDeque<u64> = (0..len).collect();
+        let mut index = KeyIndex::new();
+        index.build(keys.iter().map(|&key| hash(key)));
+
+        let mut renumbered = 0;
+        for tick in 0..ticks {
+            let oldest = keys[0];
+            let removed = index.remove(hash(oldest), |position| keys[position] == oldest);
+            assert_eq!(removed, Some(0));
+            keys.pop_front();
+            if index.removed_slots.is_empty() {
+                renumbered += 1;
+            } else {
+                // The hash was dropped by starting one later, not by shifting the others
+                assert_eq!(index.start, index.removed_slots.len());
+            }
+            assert!(index.removed_slots.len() <= (keys.len() + 1) / 8);
+
+            keys.push_back(len + tick);
+            index.push(hash(len + tick));
+            assert_eq!(index.hashes().len(), keys.len());
+        }
+        // One renumbering per eighth of the entries removed, instead of one per removal
+        assert!(
+            renumbered <= ticks / (len / 8) + 1,
+            "renumbered {renumbered} times"
+        );
+        for (position, &key) in keys.iter().enumerate() {
+            assert_eq!(index.find(hash(key), |at| keys[at] == key), Some(position));
+        }
+    }
+
+    #[test]
+    fn inserting_moves_the_shorter_side() {
+        let mut keys: VecDeque<u64> = (0..1000).collect();
+        let mut index = KeyIndex::new();
+        index.build(keys.iter().map(|&key| hash(key)));
+        let check = |index: &KeyIndex, keys: &VecDeque<u64>| {
+            assert_eq!(index.hashes().len(), keys.len());
+            assert_eq!(index.len(), keys.len());
+            for (position, &key) in keys.iter().enumerate() {
+                assert_eq!(index.find(hash(key), |at| keys[at] == key), Some(position));
+            }
+        };
+
+        // Put back the oldest entries, as a rollback does, while their slots are pending
+        let removed: Vec<u64> = keys.drain(..20).collect();
+        for &key in &removed {
+            index.remove(hash(key), |at| at == 0);
+        }
+        for &key in removed.iter().rev() {
+            let pending = index.removed_slots.len();
+            index.insert_at(0, hash(key));
+            keys.push_front(key);
+            assert_eq!(index.removed_slots.len(), pending - 1);
+        }
+        check(&index, &keys);
+
+        assert!(index.removed_slots.is_empty());
+
+        // With no removed slot below them, inserts near the front first make room in a batch
+        let mut next = 1000;
+        for at in [0, 3, 1, 0, 100, 499, 500, 900, 1008, 1009] {
+            index.insert_at(at, hash(next));
+            keys.insert(at, next);
+            next += 1;
+            check(&index, &keys);
+        }
+        let key = keys[10];
+        index.remove(hash(key), |at| keys[at] == key);
+        keys.remove(10);
+        index.insert_at(5, hash(next));
+        keys.insert(5, next);
+        check(&index, &keys);
+    }
+
+    #[test]
+    fn popping_after_removals_reuses_the_last_slot() {
+        let mut keys: Vec<u64> = (0..64).collect();
+        let mut index = KeyIndex::new();
+        index.build(keys.iter().map(|&key| hash(key)));
+        // Remove the last few, so their slots are pending after the one to be popped
+        for _ in 0..4 {
+            let key = keys.pop().expect("keys left");
+            index.remove(hash(key), |at| at == keys.len());
+        }
+        let key = keys[10];
+        index.remove(hash(key), |at| keys[at] == key);
+        keys.remove(10);
+
+        keys.pop();
+        index.pop();
+        keys.push(100);
+        index.push(hash(100));
+        assert_eq!(index.hashes().len(), keys.len());
+        for (position, &key) in keys.iter().enumerate() {
+            assert_eq!(index.find(hash(key), |at| keys[at] == key), Some(position));
+        }
+    }
+}
@@ -2,25 +2,324 @@
  * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
  * Licensed under the MIT License. See LICENSE in the project root for license information.
  */
+#![debugger_visualizer(natvis_file = "../debug_metadata/seq_map.natvis")]
+#![debugger_visualizer(gdb_script_file = "../debug_metadata/seq_map_gdb.py")]
+
 use std::{
-    collections::HashMap,
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
     error::Error,
-    fmt::{self, Debug, Display, Formatter},
-    hash::{Hash, Hasher},
-    ops::Index,
+    fmt::{self, Debug, Display, Formatter, Write as _},
+    hash::{BuildHasher, Hash, Hasher},
+    iter::FusedIterator,
+    ops::{Bound, Index, IndexMut, Range, RangeBounds},
 };
 
+#[cfg(feature = "alloc_map")]
+mod alloc;
+mod any;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impl;
+mod canonical;
+mod cell;
+mod checksum;
+mod counter;
+#[cfg(feature = "defmt")]
+mod defmt_impl;
+mod digest;
+mod entries;
+mod fixed;
+mod frozen;
+#[cfg(feature = "fuzzy")]
+mod fuzzy;
+mod hasher;
+mod indexed;
+mod intern;
+mod key_index;
+mod linear;
+mod map_index;
+mod memory;
+#[cfg(feature = "minicbor")]
+mod minicbor_impl;
+#[cfg(feature = "proptest")]
+mod proptest_impl;
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impl;
+#[cfg(feature = "rayon")]
+mod rayon_impl;
+mod registry;
+mod rng;
+mod rollback;
+#[cfg(feature = "schemars")]
+mod schemars_impl;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "serde_json")]
+mod serde_json_impl;
+#[cfg(feature = "arc-swap")]
+mod shared;
+#[cfg(feature = "smallvec")]
+mod small;
+mod soa;
+mod sorted;
+mod table;
+#[cfg(feature = "tracing")]
+mod tracing_impl;
+mod validate;
+mod view;
+
+#[cfg(feature = "alloc_map")]
+pub use alloc::AllocSeqMap;
+pub use any::{AnySeqMap, AnyValue};
+pub use canonical::ParseCanonicalError;
+pub use cell::SeqMapCell;
+pub use checksum::{StableEncode, StableWrite};
+pub use counter::SeqCounter;
+pub use digest::DigestSeqMap;
+pub use fixed::FixedSeqMap;
+pub use frozen::FrozenSeqMap;
+pub use hasher::{FxBuildHasher, FxHasher};
+pub use indexed::{IndexedSeqMap, MatchingKeys};
+pub use intern::{Interned, Interner};
+pub use linear::LinearSeqMap;
+pub use map_index::SeqMapIndex;
+pub use memory::MemoryReport;
+#[cfg(feature = "proptest")]
+pub use proptest_impl::seq_map;
+pub use registry::{Id, Registry};
+pub use rollback::RollbackSeqMap;
+#[cfg(feature = "serde")]
+pub use serde_impl::{duplicates, serialize_sorted, CanonicalSeqMap};
+#[cfg(feature = "serde_json")]
+pub use serde_json_impl::NotAnObjectError;
+#[cfg(feature = "arc-swap")]
+pub use shared::SharedSeqMap;
+#[cfg(feature = "smallvec")]
+pub use small::SmallSeqMap;
+pub use soa::SoaSeqMap;
+pub use sorted::SortedSeqMap;
+pub use table::TableDisplay;
+pub use validate::InvariantError;
+pub use view::SeqMapView;
+
+use entries::Entries;
+use hashbrown::{hash_table, HashTable};
+use key_index::{IndexEntry, KeyIndex};
+use map_index::Generation;
+
+/// The hasher a [`SeqMap`] uses unless another one is given.
+///
+/// This is [`RandomState`](std::collections::hash_map::RandomState), seeded randomly per map,
+/// unless the `fast_hash` feature is enabled, which makes it [`FxBuildHasher`].
+#[cfg(not(feature = "fast_hash"))]
+pub type DefaultHashBuilder = std::collections::hash_map::RandomState;
+/// The hasher a [`SeqMap`] uses unless another one is given.
+///
+/// This is [`FxBuildHasher`], since the `fast_hash` feature is enabled.
+#[cfg(feature = "fast_hash")]
+pub type DefaultHashBuilder = FxBuildHasher;
+
+/// Maps with at most this many entries find keys by scanning `entries` and have no index.
+const SMALL_MAP_LEN: usize = 8;
+
 /// A deterministic map that preserves insertion order.
 ///
-/// Internally, it uses a [`HashMap`] for quick key lookups and a [`Vec`] to maintain the order
-/// of inserted key-value pairs.
+/// Internally, it uses a hash table for quick key lookups and a list of the key-value pairs in
+/// insertion order. The hash table only stores positions into the list, so every key is stored
+/// once and keys do not have to be [`Clone`]. Small maps skip the hash table and compare keys
+/// along the list instead, which for a handful of entries is faster than hashing. The hash
+/// table is only allocated once the map grows past a few entries, and is kept from then on.
+///
+/// Once the hash table exists, the hash of every key is kept next to the entries. Growing the
+/// table, reordering entries and splicing never hash a key again, and lookups only compare
+/// keys whose full hash matches.
+///
+/// The list is a [`VecDeque`](std::collections::VecDeque) that is kept in one piece, so the
+/// entries can still be borrowed as a slice. [`remove`](Self::remove) keeps the order and only
+/// moves the entries on the shorter side of the removed one, so removing the first or the last
+/// entry moves none. The positions stored in the hash table are renumbered in batches, once
+/// removals add up to a fraction of the map, so removing the oldest entry is amortized O(1) no
+/// matter how large the map is. Removing an entry in the middle moves up to half of them.
+///
+/// Methods that take a closure leave the map valid if the closure panics, so a map can still
+/// be used after the panic has been caught. [`retain`](Self::retain) and its relatives keep
+/// the entries they had not visited yet, dropping the one being visited, and the sorts leave
+/// the entries in an unspecified order.
+///
+/// With the `compact_index` feature, the hash table stores positions as `u32` instead of
+/// `usize`, which on 64-bit targets shrinks the index for small keys. Such a map panics when
+/// it grows past `u32::MAX` entries.
+///
+/// Keys are hashed with `S`, which defaults to [`DefaultHashBuilder`]. Use
+/// [`with_hasher`](Self::with_hasher) to pick another hasher.
 #[derive(Clone)]
-pub struct SeqMap<K, V> {
-    key_to_index: HashMap<K, usize>, // Maps keys to their index in `entries`
-    entries: Vec<(K, V)>,            // Stores key-value pairs in insertion order
+pub struct SeqMap<K, V, S = DefaultHashBuilder> {
+    // Positions in `entries` and the hashes of their keys. Either empty, for small maps, or
+    // complete
+    key_index: KeyIndex,
+    hash_builder: S,
+    entries: Entries<(K, V)>, // Stores key-value pairs in insertion order
+    #[cfg(feature = "tracing")]
+    trace: Option<tracing_impl::TraceLabel<K>>,
+    // Changed whenever entries move or are removed, to catch stale or foreign `SeqMapIndex`
+    // values in debug builds
+    generation: Generation,
+}
+
+impl<K, V, S> SeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Finds the position of `key`, through the index or, for small maps, by scanning.
+    fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        if self.key_index.is_empty() {
+            self.entries.iter().position(|(k, _)| k.borrow() == key)
+        } else {
+            self.find_hashed(self.hash_builder.hash_one(key), key)
+        }
+    }
+
+    /// Finds the position of `key` through the index, which must not be empty, given the
+    /// hash of the key.
+    fn find_hashed<Q>(&self, hash: u64, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.key_index
+            .find(hash, |index| self.entries[index].0.borrow() == key)
+    }
+
+    /// Removes the slot of `key` from the index, returning the position of its entry.
+    ///
+    /// The slots of the later entries are not renumbered here, but in batches once enough
+    /// removals have added up.
+    fn unindex<Q>(&mut self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        self.key_index
+            .remove(hash, |index| self.entries[index].0.borrow() == key)
+    }
+}
+
+/// Converts any kind of positional range into a `Range` that lies within `bounds`, where an
+/// unbounded side takes the matching side of `bounds`.
+///
+/// # Panics
+///
+/// Panics if the range does not lie within `bounds`.
+fn resolve_range<R: RangeBounds<usize>>(range: R, bounds: Range<usize>) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start + 1,
+        Bound::Unbounded => bounds.start,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end + 1,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => bounds.end,
+    };
+    assert!(
+        bounds.start <= start && start <= end && end <= bounds.end,
+        "range {start}..{end} out of bounds for {}..{}",
+        bounds.start,
+        bounds.end
+    );
+    start..end
+}
+
+impl<K, V, S> SeqMap<K, V, S> {
+    /// Makes every [`SeqMapIndex`] taken so far stale. Only tracked in debug builds.
+    fn invalidate_positions(&mut self) {
+        self.generation.bump();
+    }
+
+    /// Wraps `index` in a [`SeqMapIndex`] that, in debug builds, remembers the current
+    /// generation.
+    fn typed_index(&self, index: usize) -> SeqMapIndex<K, V> {
+        self.generation.index(index)
+    }
+
+    /// Returns the raw position of `index`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `index` was taken before entries were moved or removed, or
+    /// was taken from another map.
+    fn checked_position(&self, index: SeqMapIndex<K, V>) -> usize {
+        self.generation.position(index)
+    }
+
+    /// Creates a map of `entries`, which take the places of the entries of this map one for
+    /// one, reusing its key index and hasher. The tracing label and the generation carry over.
+    fn with_entries<W>(self, entries: Entries<(K, W)>) -> SeqMap<K, W, S> {
+        SeqMap {
+            key_index: self.key_index,
+            hash_builder: self.hash_builder,
+            entries,
+            #[cfg(feature = "tracing")]
+            trace: self.trace,
+            generation: self.generation,
+        }
+    }
+
+    /// Creates a map of `entries` without an index, for the other maps of this crate to
+    /// convert into. The caller builds the index if the map is not small.
+    ///
+    /// The positions are those of the map converted from, so its `generation` carries over and
+    /// indexes taken from it stay valid.
+    fn from_entries(hash_builder: S, entries: Entries<(K, V)>, generation: Generation) -> Self {
+        Self {
+            key_index: KeyIndex::new(),
+            hash_builder,
+            entries,
+            #[cfg(feature = "tracing")]
+            trace: None,
+            generation,
+        }
+    }
+
+    /// Clones everything but the entries, for [`with_entries`](Self::with_entries) to fill in.
+    fn clone_without_entries(&self) -> Self
+    where
+        S: Clone,
+    {
+        Self {
+            key_index: self.key_index.clone(),
+            hash_builder: self.hash_builder.clone(),
+            entries: Entries::new(),
+            #[cfg(feature = "tracing")]
+            trace: self.trace,
+            generation: self.generation,
+        }
+    }
+
+    /// Moves the entry at position `order[i]` to position `i`, for an `order` that is known to
+    /// be a permutation of all positions.
+    ///
+    /// The hashes move along and the slots are renumbered in place, so no key is hashed.
+    fn permute(&mut self, order: &[usize]) {
+        self.invalidate_positions();
+        let mut old: Vec<Option<(K, V)>> = self.entries.drain(..).map(Some).collect();
+        self.entries.extend(
+            order
+                .iter()
+                .map(|&index| old[index].take().expect("validated permutation")),
+        );
+        self.key_index.permute(order);
+    }
 }
 
-impl<K, V> Hash for SeqMap<K, V>
+impl<K, V, S> Hash for SeqMap<K, V, S>
 where
     K: Hash,
     V: Hash,
@@ -33,7 +332,7 @@ where
     }
 }
 
-impl<K, V> PartialEq for SeqMap<K, V>
+impl<K, V, S> PartialEq for SeqMap<K, V, S>
 where
     K: Eq,
     V: Eq,
@@ -49,66 +348,398 @@ where
     }
 }
 
-impl<K, V> Eq for SeqMap<K, V>
+impl<K, V, S> Eq for SeqMap<K, V, S>
 where
     K: Eq,
     V: Eq,
 {
 }
 
-impl<K, V> Display for SeqMap<K, V>
+/// Compares the contents with a [`HashMap`]. The insertion order is not taken into account.
+impl<K, V, S, S2> PartialEq<HashMap<K, V, S2>> for SeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    V: PartialEq,
+    S2: BuildHasher,
+{
+    fn eq(&self, other: &HashMap<K, V, S2>) -> bool {
+        self.entries.len() == other.len()
+            && self
+                .entries
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+/// Compares the contents with a [`BTreeMap`]. The insertion order is not taken into account.
+impl<K, V, S> PartialEq<BTreeMap<K, V>> for SeqMap<K, V, S>
+where
+    K: Ord,
+    V: PartialEq,
+{
+    fn eq(&self, other: &BTreeMap<K, V>) -> bool {
+        self.entries.len() == other.len()
+            && self
+                .entries
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+/// Compares the entries with a slice of pairs. The insertion order must match the slice order.
+impl<K, V, S> PartialEq<&[(K, V)]> for SeqMap<K, V, S>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &&[(K, V)]) -> bool {
+        self.entries.as_slice() == *other
+    }
+}
+
+/// Compares maps lexicographically over their entries in insertion order.
+impl<K, V, S> PartialOrd for SeqMap<K, V, S>
+where
+    K: Ord,
+    V: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares maps lexicographically over their entries in insertion order.
+///
+/// Entries are compared pairwise, first by key and then by value. If one map is a prefix of
+/// the other, the shorter map is considered smaller.
+impl<K, V, S> Ord for SeqMap<K, V, S>
+where
+    K: Ord,
+    V: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.entries.cmp(&other.entries)
+    }
+}
+
+impl<K, V, S> Display for SeqMap<K, V, S>
 where
     K: Eq + Hash + Display,
     V: Display,
 {
+    /// Formats as a `SeqMap(len)` header followed by one `key: value` line per entry.
+    ///
+    /// A width (`{:12}`) pads every key to that many characters, and the alternate form
+    /// (`{:#}`) pads them to the longest key so the values line up. The alternate flag is
+    /// passed on to the values. Values that span several lines, such as nested maps, are
+    /// indented one level deeper than their key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut config = SeqMap::new();
+    /// config.insert("name", SeqMap::from([("first", 1)])).unwrap();
+    /// config.insert("render", SeqMap::from([("vsync", 0), ("fps", 60)])).unwrap();
+    /// assert_eq!(
+    ///     format!("{config:#}"),
+    ///     "SeqMap(2)\nname  : SeqMap(1)\n  first: 1\nrender: SeqMap(2)\n  vsync: 0\n  fps  : 60"
+    /// );
+    /// ```
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "SeqMap({})", self.entries.len())?;
-        for (key, value) in &self.entries {
-            write!(f, "\n{key}: {value}")?;
+        let keys: Vec<String> = self.entries.iter().map(|(k, _)| k.to_string()).collect();
+        let width = if f.alternate() {
+            keys.iter().map(|k| k.chars().count()).max().unwrap_or(0)
+        } else {
+            f.width().unwrap_or(0)
+        };
+        let alternate = f.alternate();
+        for (key, (_, value)) in keys.iter().zip(&self.entries) {
+            write!(f, "\n{key:<width$}: ")?;
+            let mut nested = Indented { f: &mut *f };
+            if alternate {
+                write!(nested, "{value:#}")?;
+            } else {
+                write!(nested, "{value}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes through to a formatter, indenting every line after the first by one level.
+struct Indented<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+}
+
+impl fmt::Write for Indented<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut lines = s.split('\n');
+        if let Some(first) = lines.next() {
+            self.f.write_str(first)?;
+        }
+        for line in lines {
+            self.f.write_str("\n  ")?;
+            self.f.write_str(line)?;
         }
         Ok(())
     }
 }
 
-impl<K, V> Debug for SeqMap<K, V>
+impl<K, V, S> Debug for SeqMap<K, V, S>
 where
     K: Eq + Hash + Debug,
     V: Debug,
 {
+    /// Formats as `SeqMap(key: value, ...)` on a single line.
+    ///
+    /// The alternate form (`{:#?}`) prints one entry per line, indented like the standard maps,
+    /// and nested values are indented along with it:
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2)]);
+    /// assert_eq!(format!("{map:?}"), r#"SeqMap("a": 1, "b": 2)"#);
+    /// assert_eq!(format!("{map:#?}"), "SeqMap {\n    \"a\": 1,\n    \"b\": 2,\n}");
+    /// ```
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "SeqMap(")?;
-        let mut first = true;
-        for (key, value) in &self.entries {
-            if !first {
-                write!(f, ", ")?;
-            }
-            first = false;
-            write!(f, "{key:?}: {value:?}")?;
+        debug_entries(f, &self.entries)
+    }
+}
+
+/// Formats `entries` the way `SeqMap` implements [`Debug`].
+fn debug_entries<K: Debug, V: Debug>(f: &mut Formatter<'_>, entries: &[(K, V)]) -> fmt::Result {
+    if f.alternate() {
+        write!(f, "SeqMap ")?;
+        return f
+            .debug_map()
+            .entries(entries.iter().map(|(k, v)| (k, v)))
+            .finish();
+    }
+    write!(f, "SeqMap(")?;
+    let mut first = true;
+    for (key, value) in entries {
+        if !first {
+            write!(f, ", ")?;
         }
-        write!(f, ")")
+        first = false;
+        write!(f, "{key:?}: {value:?}")?;
     }
+    write!(f, ")")
 }
 
 /// Errors that can occur when manipulating a `SeqMap`.
-#[derive(Debug)]
-pub enum SeqMapError {
+///
+/// The variants that concern a specific key carry that key, so it can be reported or
+/// recovered. The key is included in the [`Display`] output. [`Debug`] is implemented for any
+/// key type, so `unwrap()` keeps working for keys that are not `Debug`, and hence leaves the
+/// key out.
+#[derive(Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SeqMapError<K> {
     /// Occurs when attempting to insert a key that already exists in the map.
-    KeyAlreadyExists,
+    KeyAlreadyExists(K),
+    /// Occurs when a key that is required to be present is missing from the map.
+    KeyNotFound(K),
+    /// Occurs when a position is outside of the entries of the map.
+    IndexOutOfRange {
+        /// The requested position.
+        index: usize,
+        /// Number of entries in the map.
+        len: usize,
+    },
+    /// Occurs when inserting into a map that can not grow any further.
+    CapacityExceeded {
+        /// The maximum number of entries.
+        capacity: usize,
+    },
+    /// Occurs when a list of positions is not a reordering of all entries of the map.
+    InvalidPermutation {
+        /// Number of entries in the map.
+        len: usize,
+    },
+}
+
+impl<K> SeqMapError<K> {
+    /// Returns the key the error is about, if any.
+    pub fn key(&self) -> Option<&K> {
+        match self {
+            Self::KeyAlreadyExists(key) | Self::KeyNotFound(key) => Some(key),
+            Self::IndexOutOfRange { .. }
+            | Self::CapacityExceeded { .. }
+            | Self::InvalidPermutation { .. } => None,
+        }
+    }
+}
+
+impl<K> Debug for SeqMapError<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SeqMapError::KeyAlreadyExists(_) => write!(f, "KeyAlreadyExists(..)"),
+            SeqMapError::KeyNotFound(_) => write!(f, "KeyNotFound(..)"),
+            SeqMapError::IndexOutOfRange { index, len } => f
+                .debug_struct("IndexOutOfRange")
+                .field("index", index)
+                .field("len", len)
+                .finish(),
+            SeqMapError::CapacityExceeded { capacity } => f
+                .debug_struct("CapacityExceeded")
+                .field("capacity", capacity)
+                .finish(),
+            SeqMapError::InvalidPermutation { len } => f
+                .debug_struct("InvalidPermutation")
+                .field("len", len)
+                .finish(),
+        }
+    }
+}
+
+impl<K: Debug> Display for SeqMapError<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SeqMapError::KeyAlreadyExists(key) => {
+                write!(f, "The key {key:?} already exists in the SeqMap.")
+            }
+            SeqMapError::KeyNotFound(key) => {
+                write!(f, "The key {key:?} was not found in the SeqMap.")
+            }
+            SeqMapError::IndexOutOfRange { index, len } => write!(
+                f,
+                "The index {index} is out of range for a SeqMap with {len} entries."
+            ),
+            SeqMapError::CapacityExceeded { capacity } => write!(
+                f,
+                "The SeqMap is full, it can not hold more than {capacity} entries."
+            ),
+            SeqMapError::InvalidPermutation { len } => write!(
+                f,
+                "The positions are not a permutation of the {len} entries of the SeqMap."
+            ),
+        }
+    }
+}
+
+impl<K: Debug> Error for SeqMapError<K> {}
+
+/// Error returned by [`SeqMap::try_from_iter`] when a key occurs more than once.
+///
+/// Holds on to the rejected pair so that nothing is lost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateKeyError<K, V> {
+    /// Position of the rejected pair in the source iterator.
+    pub index: usize,
+    /// The duplicated key.
+    pub key: K,
+    /// The value that was paired with the duplicated key.
+    pub value: V,
+}
+
+impl<K: Debug, V> Display for DuplicateKeyError<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The key {:?} at index {} already exists in the SeqMap.",
+            self.key, self.index
+        )
+    }
+}
+
+impl<K: Debug, V: Debug> Error for DuplicateKeyError<K, V> {}
+
+/// Error returned by [`SeqMap::try_get`] when the key is not present.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyNotFoundError<K> {
+    /// The key that was looked up.
+    pub key: K,
+    /// Number of entries in the map at the time of the lookup.
+    pub map_len: usize,
+}
+
+impl<K: Debug> Display for KeyNotFoundError<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The key {:?} was not found in the SeqMap ({} entries).",
+            self.key, self.map_len
+        )
+    }
+}
+
+impl<K: Debug> Error for KeyNotFoundError<K> {}
+
+/// Decides what happens when a key that is already present is encountered while building a map.
+///
+/// Regardless of the policy, a key keeps the position of its first occurrence.
+pub enum DuplicatePolicy<V> {
+    /// Keep the first value and ignore later ones. This is what [`FromIterator`] does.
+    FirstWins,
+    /// Replace the value with the later one.
+    LastWins,
+    /// Stop and report the duplicate as a [`DuplicateKeyError`].
+    Error,
+    /// Combine the later value into the existing one.
+    Merge(fn(&mut V, V)),
+}
+
+impl<V> Clone for DuplicatePolicy<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
 }
 
-impl Display for SeqMapError {
+impl<V> Copy for DuplicatePolicy<V> {}
+
+impl<V> Debug for DuplicatePolicy<V> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            SeqMapError::KeyAlreadyExists => write!(f, "The key already exists in the SeqMap."),
+            Self::FirstWins => write!(f, "FirstWins"),
+            Self::LastWins => write!(f, "LastWins"),
+            Self::Error => write!(f, "Error"),
+            Self::Merge(_) => write!(f, "Merge"),
         }
     }
 }
 
-impl Error for SeqMapError {}
+/// Outcome of a [`SeqMap::insert_many`] call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsertReport<K, V> {
+    /// Indices in the map of the newly inserted entries.
+    pub inserted: Vec<SeqMapIndex<K, V>>,
+    /// Pairs that were not inserted because their key was already present, in encounter order.
+    pub skipped: Vec<(K, V)>,
+}
+
+/// Error returned by [`SeqMap::try_map_values`] when the transform fails for an entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapValuesError<K, E> {
+    /// Index of the entry that failed.
+    pub index: usize,
+    /// Key of the entry that failed.
+    pub key: K,
+    /// The error returned by the transform.
+    pub error: E,
+}
+
+impl<K: Debug, E: Display> Display for MapValuesError<K, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to map the value for key {:?} at index {}: {}",
+            self.key, self.index, self.error
+        )
+    }
+}
+
+impl<K: Debug, E: Error + 'static> Error for MapValuesError<K, E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
 
 impl<K, V> SeqMap<K, V>
 where
-    K: Eq + Hash + Clone, // Clone is because we add it to two containers
+    K: Eq + Hash,
 {
     /// Creates a new, empty `SeqMap`.
     ///
@@ -118,200 +749,2300 @@ where
     /// use seq_map::SeqMap;
     /// let map: SeqMap<String, i32> = SeqMap::new();
     /// ```
+    #[cfg(not(feature = "fast_hash"))]
     pub fn new() -> Self {
-        Self {
-            key_to_index: HashMap::new(),
-            entries: Vec::new(),
-        }
+        Self::with_hasher(DefaultHashBuilder::default())
     }
 
-    /// Inserts a key-value pair into the map.
+    /// Creates a new, empty `SeqMap`.
     ///
-    /// Returns an error if the key already exists.
+    /// With the `fast_hash` feature the default hasher needs no random seed, so this is a
+    /// `const fn` and the map can be created in a `static`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Mutex;
+    /// use seq_map::SeqMap;
+    /// static REGISTRY: Mutex<SeqMap<&str, u32>> = Mutex::new(SeqMap::new());
+    /// REGISTRY.lock().unwrap().insert("player", 1).unwrap();
+    /// ```
+    #[cfg(feature = "fast_hash")]
+    pub const fn new() -> Self {
+        Self::with_hasher(FxBuildHasher::new())
+    }
+
+    /// Creates a `SeqMap` from a vector of key-value pairs, rejecting duplicate keys.
+    ///
+    /// This is the strict counterpart to `SeqMap::from(vec)`, which silently keeps the first
+    /// occurrence of a duplicated key.
     ///
     /// # Errors
     ///
-    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    /// Returns `SeqMapError::KeyAlreadyExists`, holding the duplicated key, if a key occurs
+    /// more than once.
     ///
     /// # Examples
     ///
     /// ```
     /// use seq_map::SeqMap;
-    /// let mut map = SeqMap::new();
-    /// map.insert("key".to_string(), 42).unwrap();
-    /// assert!(map.insert("key".to_string(), 43).is_err());
+    /// let map = SeqMap::try_from_vec(vec![("a", 1), ("b", 2)]).unwrap();
+    /// assert_eq!(map.len(), 2);
+    /// assert!(SeqMap::try_from_vec(vec![("a", 1), ("a", 2)]).is_err());
     /// ```
-    pub fn insert(&mut self, key: K, value: V) -> Result<(), SeqMapError> {
-        #[allow(clippy::map_entry)]
-        if self.key_to_index.contains_key(&key) {
-            Err(SeqMapError::KeyAlreadyExists)
-        } else {
-            self.entries.push((key.clone(), value));
-            self.key_to_index.insert(key, self.entries.len() - 1);
-            Ok(())
+    pub fn try_from_vec(pairs: Vec<(K, V)>) -> Result<Self, SeqMapError<K>> {
+        let mut map = Self::new();
+        map.entries.reserve(pairs.len());
+        map.key_index.reserve(pairs.len());
+        for (key, value) in pairs {
+            map.insert(key, value)?;
         }
+        Ok(map)
     }
 
-    /// Checks if the map contains a key.
-    pub fn contains_key(&self, key: &K) -> bool {
-        self.key_to_index.contains_key(key)
-    }
-
-    /// Returns a mutable reference to the value corresponding to the key.
+    /// Creates a `SeqMap` from an iterator of key-value pairs, rejecting duplicate keys.
     ///
-    /// Returns `None` if the key does not exist.
+    /// This is the strict counterpart to [`FromIterator`], which silently keeps the first
+    /// occurrence of a duplicated key.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DuplicateKeyError`] with the position, key and value of the first pair
+    /// whose key has already been seen.
     ///
     /// # Examples
     ///
     /// ```
     /// use seq_map::SeqMap;
-    /// let mut map = SeqMap::new();
-    /// map.insert("key".to_string(), 42).unwrap();
-    /// if let Some(value) = map.get_mut(&"key".to_string()) {
-    ///     *value = 100;
-    /// }
-    /// assert_eq!(map[&"key".to_string()], 100);
+    /// let err = SeqMap::try_from_iter([("a", 1), ("b", 2), ("a", 3)]).unwrap_err();
+    /// assert_eq!(err.index, 2);
+    /// assert_eq!(err.key, "a");
+    /// assert_eq!(err.value, 3);
     /// ```
-    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
-        self.key_to_index
-            .get(key)
-            .map(|&index| &mut self.entries[index].1)
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, DuplicateKeyError<K, V>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        Self::from_iter_with(iter, DuplicatePolicy::Error)
     }
 
-    /// Returns the number of key-value pairs in the map.
+    /// Creates a `SeqMap` from an iterator of key-value pairs, resolving duplicate keys
+    /// according to `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DuplicateKeyError`] for the first duplicate if `policy` is
+    /// [`DuplicatePolicy::Error`]. The other policies never fail.
     ///
     /// # Examples
     ///
     /// ```
-    /// use seq_map::SeqMap;
-    /// let mut map = SeqMap::new();
-    /// assert_eq!(map.len(), 0);
-    /// map.insert("key", 42).unwrap();
-    /// assert_eq!(map.len(), 1);
+    /// use seq_map::{DuplicatePolicy, SeqMap};
+    /// let pairs = [("a", 1), ("b", 2), ("a", 3)];
+    ///
+    /// let map = SeqMap::from_iter_with(pairs, DuplicatePolicy::LastWins).unwrap();
+    /// assert_eq!(map.into_vec(), vec![("a", 3), ("b", 2)]);
+    ///
+    /// let map = SeqMap::from_iter_with(pairs, DuplicatePolicy::Merge(|a, b| *a += b)).unwrap();
+    /// assert_eq!(map.into_vec(), vec![("a", 4), ("b", 2)]);
     /// ```
-    pub fn len(&self) -> usize {
-        self.entries.len()
+    pub fn from_iter_with<I>(
+        iter: I,
+        policy: DuplicatePolicy<V>,
+    ) -> Result<Self, DuplicateKeyError<K, V>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let iter = iter.into_iter();
+        let mut map = Self::new();
+        map.entries.reserve(iter.size_hint().0);
+        for (index, (key, value)) in iter.enumerate() {
+            map.insert_with_policy(index, key, value, policy)?;
+        }
+        Ok(map)
     }
 
-    /// Returns `true` if the map contains no elements.
+    /// Creates a `SeqMap` with the keys of `keys`, in order, and the value `f` makes for each.
+    ///
+    /// Only the first occurrence of a duplicated key is kept, and `f` is called once for every
+    /// distinct key, in the order of the keys.
     ///
     /// # Examples
     ///
     /// ```
     /// use seq_map::SeqMap;
-    /// let map: SeqMap<String, i32> = SeqMap::new();
-    /// assert!(map.is_empty());
+    /// let scores = SeqMap::from_keys(["red", "blue", "red"], |team| team.len());
+    /// assert_eq!(scores.into_vec(), vec![("red", 3), ("blue", 4)]);
     /// ```
-    pub fn is_empty(&self) -> bool {
-        self.entries.is_empty()
+    pub fn from_keys<I, F>(keys: I, mut f: F) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        F: FnMut(&K) -> V,
+    {
+        let keys = keys.into_iter();
+        let mut map = Self::new();
+        map.entries.reserve(keys.size_hint().0);
+        for key in keys {
+            if map.key_index.is_empty() {
+                if map.index_of(&key).is_none() {
+                    let value = f(&key);
+                    map.push_hashed_entry(None, key, value);
+                }
+            } else {
+                let hash = map.hash_builder.hash_one(&key);
+                if map.find_hashed(hash, &key).is_none() {
+                    let value = f(&key);
+                    map.push_hashed_entry(Some(hash), key, value);
+                }
+            }
+        }
+        map
     }
 
-    /// Returns an iterator over the keys of the map in insertion order.
+    /// Creates a `SeqMap` with the entries of all `maps`, one map after the other.
+    ///
+    /// Capacity is reserved once, for all entries. If a key occurs in more than one map, the
+    /// entry from the first map is kept and the later ones are dropped, the same as
+    /// [`DuplicatePolicy::FirstWins`]. Use [`concat_with`](Self::concat_with) for another
+    /// policy.
     ///
     /// # Examples
     ///
     /// ```
     /// use seq_map::SeqMap;
-    /// let mut map = SeqMap::new();
-    /// map.insert("a".to_string(), 1).unwrap();
-    /// map.insert("b".to_string(), 2).unwrap();
-    /// let keys: Vec<_> = map.keys().cloned().collect();
-    /// assert_eq!(keys, vec!["a", "b"]);
+    /// let audio = SeqMap::from([("volume", 8), ("mute", 0)]);
+    /// let video = SeqMap::from([("gamma", 2), ("volume", 5)]);
+    /// let settings = SeqMap::concat([audio, video]);
+    /// assert_eq!(settings.into_vec(), vec![("volume", 8), ("mute", 0), ("gamma", 2)]);
     /// ```
-    pub fn keys(&self) -> impl Iterator<Item = &K> {
-        self.entries.iter().map(|(k, _)| k)
+    pub fn concat<I, S2>(maps: I) -> Self
+    where
+        I: IntoIterator<Item = SeqMap<K, V, S2>>,
+    {
+        let mut map = Self::new();
+        map.extend(maps);
+        map
     }
 
-    /// Returns an iterator over the values of the map in insertion order.
+    /// Creates a `SeqMap` with the entries of all `maps`, one map after the other, resolving
+    /// keys that occur in more than one map according to `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DuplicateKeyError`] for the first duplicate if `policy` is
+    /// [`DuplicatePolicy::Error`]. Its index counts the entries of all maps, in order.
     ///
     /// # Examples
     ///
     /// ```
-    /// use seq_map::SeqMap;
-    /// let mut map = SeqMap::new();
-    /// map.insert("a".to_string(), 1).unwrap();
-    /// map.insert("b".to_string(), 2).unwrap();
-    /// let values: Vec<_> = map.values().cloned().collect();
-    /// assert_eq!(values, vec![1, 2]);
+    /// use seq_map::{DuplicatePolicy, SeqMap};
+    /// let base = SeqMap::from([("hp", 10), ("mp", 5)]);
+    /// let bonus = SeqMap::from([("hp", 2)]);
+    /// let total = SeqMap::concat_with([base, bonus], DuplicatePolicy::Merge(|a, b| *a += b));
+    /// assert_eq!(total.unwrap().into_vec(), vec![("hp", 12), ("mp", 5)]);
     /// ```
-    pub fn values(&self) -> impl Iterator<Item = &V> {
-        self.entries.iter().map(|(_, v)| v)
-    }
-
-    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
-        self.entries.iter_mut().map(|(_, v)| v)
-    }
-
-    pub fn get_index(&self, key: &K) -> Option<usize> {
-        self.key_to_index.get(key).copied()
+    pub fn concat_with<I, S2>(
+        maps: I,
+        policy: DuplicatePolicy<V>,
+    ) -> Result<Self, DuplicateKeyError<K, V>>
+    where
+        I: IntoIterator<Item = SeqMap<K, V, S2>>,
+    {
+        let maps: Vec<_> = maps.into_iter().collect();
+        let mut map = Self::new();
+        map.entries
+            .reserve(maps.iter().map(|m| m.entries.len()).sum());
+        for (index, (key, value)) in maps.into_iter().flat_map(|map| map.entries).enumerate() {
+            map.insert_with_policy(index, key, value, policy)?;
+        }
+        Ok(map)
     }
+}
 
-    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
-        self.entries.iter().map(|(k, v)| (k, v))
-    }
+impl<K, V, S> SeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Creates a new, empty `SeqMap` that hashes keys with `hash_builder`.
+    ///
+    /// Nothing is allocated until the first insert, and this is a `const fn`. With a hasher
+    /// that can be created in a constant, such as [`FxBuildHasher`], a `SeqMap` can be
+    /// created in a `static` without lazy initialization.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::with_hasher(RandomState::new());
+    /// map.insert("a", 1).unwrap();
+    /// assert_eq!(map["a"], 1);
+    /// ```
+    ///
+    /// ```
+    /// use std::sync::Mutex;
+    /// use seq_map::{FxBuildHasher, SeqMap};
+    /// static REGISTRY: Mutex<SeqMap<&str, u32, FxBuildHasher>> =
+    ///     Mutex::new(SeqMap::with_hasher(FxBuildHasher::new()));
+    /// REGISTRY.lock().unwrap().insert("player", 1).unwrap();
+    /// assert_eq!(REGISTRY.lock().unwrap()["player"], 1);
+    /// ```
+    pub const fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            key_index: KeyIndex::new(),
+            hash_builder,
+            entries: Entries::new(),
+            #[cfg(feature = "tracing")]
+            trace: None,
+            generation: Generation::new(),
+        }
+    }
+
+    /// Creates a new, empty `SeqMap` with room for `capacity` entries, that hashes keys with
+    /// `hash_builder`.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            key_index: KeyIndex::new(),
+            hash_builder,
+            entries: Entries::with_capacity(capacity),
+            #[cfg(feature = "tracing")]
+            trace: None,
+            generation: Generation::new(),
+        }
+    }
+
+    /// Returns the hasher the map hashes keys with.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Inserts a pair, resolving an existing key according to `policy`.
+    ///
+    /// `source_index` is only used for reporting the position of a rejected pair.
+    fn insert_with_policy(
+        &mut self,
+        source_index: usize,
+        key: K,
+        value: V,
+        policy: DuplicatePolicy<V>,
+    ) -> Result<(), DuplicateKeyError<K, V>> {
+        let Some(index) = self.index_of(&key) else {
+            self.push_entry(key, value);
+            return Ok(());
+        };
+        match policy {
+            DuplicatePolicy::FirstWins => {}
+            DuplicatePolicy::LastWins => self.entries[index].1 = value,
+            DuplicatePolicy::Error => {
+                return Err(DuplicateKeyError {
+                    index: source_index,
+                    key,
+                    value,
+                })
+            }
+            DuplicatePolicy::Merge(merge) => merge(&mut self.entries[index].1, value),
+        }
+        Ok(())
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// Returns an error if the key already exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists`, holding the rejected key, if the key is already
+    /// present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::{SeqMap, SeqMapError};
+    /// let mut map = SeqMap::new();
+    /// map.insert("key".to_string(), 42).unwrap();
+    /// let err = map.insert("key".to_string(), 43).unwrap_err();
+    /// assert_eq!(err, SeqMapError::KeyAlreadyExists("key".to_string()));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), SeqMapError<K>> {
+        if self.contains_key(&key) {
+            Err(SeqMapError::KeyAlreadyExists(key))
+        } else {
+            self.push_entry(key, value);
+            Ok(())
+        }
+    }
+
+    /// Inserts a key-value pair like [`insert`](Self::insert), returning the position of the
+    /// new entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists`, holding the rejected key, if the key is already
+    /// present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::new();
+    /// map.insert("a", 1).unwrap();
+    /// let index = map.insert_full("b", 2).unwrap();
+    /// assert_eq!(index.get(), 1);
+    /// assert_eq!(map.get_at(index), Some((&"b", &2)));
+    /// ```
+    pub fn insert_full(&mut self, key: K, value: V) -> Result<SeqMapIndex<K, V>, SeqMapError<K>> {
+        if self.contains_key(&key) {
+            Err(SeqMapError::KeyAlreadyExists(key))
+        } else {
+            let index = self.push_entry(key, value);
+            Ok(self.typed_index(index))
+        }
+    }
+
+    /// Returns the position and value for `key`.
+    pub fn get_full<Q>(&self, key: &Q) -> Option<(SeqMapIndex<K, V>, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key)
+            .map(|index| (self.typed_index(index), &self.entries[index].1))
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).is_some()
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// Returns `None` if the key does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::new();
+    /// map.insert("key".to_string(), 42).unwrap();
+    /// if let Some(value) = map.get_mut(&"key".to_string()) {
+    ///     *value = 100;
+    /// }
+    /// assert_eq!(map[&"key".to_string()], 100);
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| &mut self.entries[index].1)
+    }
+
+    /// Modifies the value for `key` in place with `f`, returning `true` if the key was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("hits", 1)]);
+    /// assert!(map.update(&"hits", |v| *v += 1));
+    /// assert!(!map.update(&"misses", |v| *v += 1));
+    /// assert_eq!(map[&"hits"], 2);
+    /// ```
+    pub fn update<Q, F>(&mut self, key: &Q, f: F) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        F: FnOnce(&mut V),
+    {
+        self.get_mut(key).map(f).is_some()
+    }
+
+    /// Modifies the value for `key` in place with `f`, treating a missing key as an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyNotFound`, holding a clone of the key, if the key is not
+    /// present. `f` is not called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("hits", 1)]);
+    /// map.try_update(&"hits", |v| *v += 1).unwrap();
+    /// assert!(map.try_update(&"misses", |v| *v += 1).is_err());
+    /// ```
+    pub fn try_update<Q, F>(&mut self, key: &Q, f: F) -> Result<(), SeqMapError<K>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = K> + ?Sized,
+        F: FnOnce(&mut V),
+    {
+        self.get_mut(key)
+            .map(f)
+            .ok_or_else(|| SeqMapError::KeyNotFound(key.to_owned()))
+    }
+
+    /// Updates the value for `key` with `update` if it is present, or appends a new entry with
+    /// the value from `insert` if it is not. Returns `true` if a new entry was inserted.
+    ///
+    /// The key is only hashed once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut counts = SeqMap::new();
+    /// for word in ["a", "b", "a"] {
+    ///     counts.upsert_with(word, || 1, |count| *count += 1);
+    /// }
+    /// assert_eq!(counts.into_vec(), vec![("a", 2), ("b", 1)]);
+    /// ```
+    pub fn upsert_with<I, U>(&mut self, key: K, insert: I, update: U) -> bool
+    where
+        I: FnOnce() -> V,
+        U: FnOnce(&mut V),
+    {
+        if self.key_index.is_empty() {
+            return match self.index_of(&key) {
+                Some(index) => {
+                    update(&mut self.entries[index].1);
+                    false
+                }
+                None => {
+                    self.push_entry(key, insert());
+                    true
+                }
+            };
+        }
+        let hash = self.hash_builder.hash_one(&key);
+        match self
+            .key_index
+            .entry(hash, |index| self.entries[index].0 == key)
+        {
+            IndexEntry::Occupied(index) => {
+                update(&mut self.entries[index].1);
+                false
+            }
+            IndexEntry::Vacant(slot) => {
+                #[cfg(feature = "tracing")]
+                let index = self.entries.len();
+                self.entries.push((key, insert()));
+                slot.insert();
+                self.generation.draw_once();
+                #[cfg(feature = "tracing")]
+                self.trace_insert(index);
+                self.check_invariants();
+                true
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value for `key`, appending a new entry with the value
+    /// from `insert` first if the key is missing.
+    ///
+    /// The key is only hashed once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::new();
+    /// *map.get_mut_or_insert_with("a", || 10) += 1;
+    /// *map.get_mut_or_insert_with("a", || 10) += 1;
+    /// assert_eq!(map[&"a"], 12);
+    /// ```
+    pub fn get_mut_or_insert_with<F>(&mut self, key: K, insert: F) -> &mut V
+    where
+        F: FnOnce() -> V,
+    {
+        if self.key_index.is_empty() {
+            let index = match self.index_of(&key) {
+                Some(index) => index,
+                None => self.push_entry(key, insert()),
+            };
+            return &mut self.entries[index].1;
+        }
+        let hash = self.hash_builder.hash_one(&key);
+        let index = match self
+            .key_index
+            .entry(hash, |index| self.entries[index].0 == key)
+        {
+            IndexEntry::Occupied(index) => index,
+            IndexEntry::Vacant(slot) => {
+                let index = self.entries.len();
+                self.entries.push((key, insert()));
+                slot.insert();
+                self.generation.draw_once();
+                #[cfg(feature = "tracing")]
+                self.trace_insert(index);
+                index
+            }
+        };
+        self.check_invariants();
+        &mut self.entries[index].1
+    }
+
+    /// Returns a mutable reference to the value for `key`, appending a new entry with
+    /// `V::default()` first if the key is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut groups: SeqMap<&str, Vec<i32>> = SeqMap::new();
+    /// for (group, item) in [("odd", 1), ("even", 2), ("odd", 3)] {
+    ///     groups.get_mut_or_default(group).push(item);
+    /// }
+    /// assert_eq!(groups.into_vec(), vec![("odd", vec![1, 3]), ("even", vec![2])]);
+    /// ```
+    pub fn get_mut_or_default(&mut self, key: K) -> &mut V
+    where
+        V: Default,
+    {
+        self.get_mut_or_insert_with(key, V::default)
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::new();
+    /// assert_eq!(map.len(), 0);
+    /// map.insert("key", 42).unwrap();
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map: SeqMap<String, i32> = SeqMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the keys of the map in insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::new();
+    /// map.insert("a".to_string(), 1).unwrap();
+    /// map.insert("b".to_string(), 2).unwrap();
+    /// let keys: Vec<_> = map.keys().cloned().collect();
+    /// assert_eq!(keys, vec!["a", "b"]);
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Returns an iterator over the values of the map in insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::new();
+    /// map.insert("a".to_string(), 1).unwrap();
+    /// map.insert("b".to_string(), 2).unwrap();
+    /// let values: Vec<_> = map.values().cloned().collect();
+    /// assert_eq!(values, vec![1, 2]);
+    /// ```
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+
+    /// Returns the key-value pairs in insertion order as a slice.
+    ///
+    /// Only shared access is given, since changing a key through the slice would desync it
+    /// from the key index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let entries = map.as_entries();
+    /// assert_eq!(entries[1], ("b", 2));
+    /// assert_eq!(entries.windows(2).count(), 2);
+    /// ```
+    pub fn as_entries(&self) -> &[(K, V)] {
+        &self.entries
+    }
+
+    /// Returns the key and value at `index`, or `None` if it is out of bounds.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `index` came from the map, for example from
+    /// [`get_index`](Self::get_index), and entries were moved or removed since.
+    pub fn get_at(&self, index: SeqMapIndex<K, V>) -> Option<(&K, &V)> {
+        let index = self.checked_position(index);
+        self.entries.get(index).map(|(k, v)| (k, v))
+    }
+
+    /// Returns the key and a mutable reference to the value at `index`, or `None` if it is
+    /// out of bounds.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `index` came from the map, for example from
+    /// [`get_index`](Self::get_index), and entries were moved or removed since.
+    pub fn get_at_mut(&mut self, index: SeqMapIndex<K, V>) -> Option<(&K, &mut V)> {
+        let index = self.checked_position(index);
+        self.entries.get_mut(index).map(|(k, v)| (&*k, v))
+    }
+
+    /// Returns the insertion index of the key, typed for this map so that it can be handed
+    /// to [`get_at`](Self::get_at) but not to a map of other types.
+    ///
+    /// Use [`SeqMapIndex::get`] or `usize::from` for the raw position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2)]);
+    /// let b = map.get_index(&"b").unwrap();
+    /// assert_eq!(b.get(), 1);
+    /// assert_eq!(map.get_at(b), Some((&"b", &2)));
+    /// ```
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| self.typed_index(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    /// Returns an iterator over the entries in insertion order, each with its position.
+    ///
+    /// The positions stay correct when iterating from the back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// assert_eq!(map.indexed_iter().next_back(), Some((2, &"c", &3)));
+    /// let evens: Vec<_> = map.indexed_iter().rev().filter(|(i, _, _)| i % 2 == 0).collect();
+    /// assert_eq!(evens, vec![(2, &"c", &3), (0, &"a", &1)]);
+    /// ```
+    pub fn indexed_iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (usize, &K, &V)> + ExactSizeIterator {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(index, (k, v))| (index, k, v))
+    }
+
+    /// Returns an iterator over the entries in insertion order, each with its position, with
+    /// mutable references to the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 0), ("b", 0)]);
+    /// for (index, _, value) in map.indexed_iter_mut().rev() {
+    ///     *value = index * 10;
+    /// }
+    /// assert_eq!(map.into_vec(), vec![("a", 0), ("b", 10)]);
+    /// ```
+    pub fn indexed_iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (usize, &K, &mut V)> + ExactSizeIterator {
+        self.entries
+            .iter_mut()
+            .enumerate()
+            .map(|(index, (k, v))| (index, &*k, v))
+    }
+
+    /// Creates a new map with every value transformed by `f`, keeping keys and order.
+    ///
+    /// The key lookup table is cloned rather than rebuilt, so no key is hashed again.
+    /// Use [`into_map_values`](Self::into_map_values) to avoid cloning the keys as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2)]);
+    /// let doubled = map.map_values(|v| v * 2);
+    /// assert_eq!(doubled.into_vec(), vec![("a", 2), ("b", 4)]);
+    /// ```
+    pub fn map_values<W, F>(&self, mut f: F) -> SeqMap<K, W, S>
+    where
+        K: Clone,
+        S: Clone,
+        F: FnMut(&V) -> W,
+    {
+        let entries = self
+            .entries
+            .iter()
+            .map(|(key, value)| (key.clone(), f(value)))
+            .collect();
+        self.clone_without_entries().with_entries(entries)
+    }
+
+    /// Creates a new map with every value transformed by the fallible `f`, keeping keys and
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first entry for which `f` fails and returns a [`MapValuesError`] holding
+    /// that entry's index, a clone of its key, and the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", "1"), ("b", "x"), ("c", "3")]);
+    /// let err = map.try_map_values(|_, v| v.parse::<i32>()).unwrap_err();
+    /// assert_eq!(err.key, "b");
+    /// assert_eq!(err.index, 1);
+    /// ```
+    pub fn try_map_values<W, E, F>(&self, mut f: F) -> Result<SeqMap<K, W, S>, MapValuesError<K, E>>
+    where
+        K: Clone,
+        S: Clone,
+        F: FnMut(&K, &V) -> Result<W, E>,
+    {
+        let mut entries = Entries::with_capacity(self.entries.len());
+        for (index, (key, value)) in self.entries.iter().enumerate() {
+            match f(key, value) {
+                Ok(mapped) => entries.push((key.clone(), mapped)),
+                Err(error) => {
+                    return Err(MapValuesError {
+                        index,
+                        key: key.clone(),
+                        error,
+                    })
+                }
+            }
+        }
+        Ok(self.clone_without_entries().with_entries(entries))
+    }
+
+    /// Returns an iterator over the keys present in both maps, together with both values.
+    ///
+    /// This is an inner join on the keys, yielded in the insertion order of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let names = SeqMap::from([("x", "int"), ("y", "bool"), ("z", "str")]);
+    /// let sizes = SeqMap::from([("z", 16), ("x", 4)]);
+    /// let joined: Vec<_> = names.zip(&sizes).collect();
+    /// assert_eq!(joined, vec![(&"x", &"int", &4), (&"z", &"str", &16)]);
+    /// ```
+    pub fn zip<'a, V2, S2>(
+        &'a self,
+        other: &'a SeqMap<K, V2, S2>,
+    ) -> impl Iterator<Item = (&'a K, &'a V, &'a V2)>
+    where
+        S2: BuildHasher,
+    {
+        self.entries
+            .iter()
+            .filter_map(|(key, value)| other.get(key).map(|other_value| (key, value, other_value)))
+    }
+
+    /// Returns an iterator over all entries of `self`, together with the value for the same key
+    /// in `other`, if any.
+    ///
+    /// This is a left join on the keys, yielded in the insertion order of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let desired = SeqMap::from([("a", 1), ("b", 2)]);
+    /// let current = SeqMap::from([("b", 20), ("c", 30)]);
+    /// let joined: Vec<_> = desired.left_join(&current).collect();
+    /// assert_eq!(joined, vec![(&"a", &1, None), (&"b", &2, Some(&20))]);
+    /// ```
+    pub fn left_join<'a, V2, S2>(
+        &'a self,
+        other: &'a SeqMap<K, V2, S2>,
+    ) -> impl Iterator<Item = (&'a K, &'a V, Option<&'a V2>)>
+    where
+        S2: BuildHasher,
+    {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key, value, other.get(key)))
+    }
+
+    /// Returns an iterator over the keys present in either map, together with the value from
+    /// each map, if any.
+    ///
+    /// This is a full outer join on the keys. The keys of `self` come first, in insertion order,
+    /// followed by the keys only present in `other`, in the insertion order of `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let desired = SeqMap::from([("a", 1), ("b", 2)]);
+    /// let current = SeqMap::from([("c", 30), ("b", 20)]);
+    /// let joined: Vec<_> = desired.outer_join(&current).collect();
+    /// assert_eq!(
+    ///     joined,
+    ///     vec![
+    ///         (&"a", Some(&1), None),
+    ///         (&"b", Some(&2), Some(&20)),
+    ///         (&"c", None, Some(&30)),
+    ///     ]
+    /// );
+    /// ```
+    pub fn outer_join<'a, V2, S2>(
+        &'a self,
+        other: &'a SeqMap<K, V2, S2>,
+    ) -> impl Iterator<Item = (&'a K, Option<&'a V>, Option<&'a V2>)>
+    where
+        S2: BuildHasher,
+    {
+        let left = self
+            .entries
+            .iter()
+            .map(|(key, value)| (key, Some(value), other.get(key)));
+        let right = other
+            .entries
+            .iter()
+            .filter(|(key, _)| !self.contains_key(key))
+            .map(|(key, value)| (key, None, Some(value)));
+        left.chain(right)
+    }
+
+    /// Returns an iterator over the entries in the positional `range`, in insertion order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let since_last: Vec<_> = map.iter_range(1..).collect();
+    /// assert_eq!(since_last, vec![(&"b", &2), (&"c", &3)]);
+    /// ```
+    pub fn iter_range<R>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = self.resolve_range(range);
+        self.entries[range].iter().map(|(k, v)| (k, v))
+    }
+
+    /// Returns an iterator over the entries in the positional `range`, with mutable references
+    /// to the values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// for (_, v) in map.iter_range_mut(..2) {
+    ///     *v *= 10;
+    /// }
+    /// assert_eq!(map.into_vec(), vec![("a", 10), ("b", 20), ("c", 3)]);
+    /// ```
+    pub fn iter_range_mut<R>(
+        &mut self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&K, &mut V)> + ExactSizeIterator
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = self.resolve_range(range);
+        self.entries[range].iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    /// Returns an iterator that starts at the entry for `key`, inclusive, and runs to the end
+    /// in insertion order.
+    ///
+    /// If `key` is not present, the iterator is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let keys: Vec<_> = map.iter_from(&"b").map(|(k, _)| *k).collect();
+    /// assert_eq!(keys, vec!["b", "c"]);
+    /// ```
+    pub fn iter_from<Q>(
+        &self,
+        key: &Q,
+    ) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let start = self.index_of(key).unwrap_or(self.entries.len());
+        self.iter_range(start..)
+    }
+
+    /// Returns an iterator that starts right after the entry for `key`, exclusive, and runs to
+    /// the end in insertion order.
+    ///
+    /// If `key` is not present, the iterator is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let keys: Vec<_> = map.iter_after(&"a").map(|(k, _)| *k).collect();
+    /// assert_eq!(keys, vec!["b", "c"]);
+    /// ```
+    pub fn iter_after<Q>(
+        &self,
+        key: &Q,
+    ) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let start = self
+            .index_of(key)
+            .map_or(self.entries.len(), |index| index + 1);
+        self.iter_range(start..)
+    }
+
+    /// Returns the entry right before the entry for `key`, in insertion order.
+    ///
+    /// Returns `None` if `key` is not present or is the first entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2)]);
+    /// assert_eq!(map.entry_before(&"b"), Some((&"a", &1)));
+    /// assert_eq!(map.entry_before(&"a"), None);
+    /// ```
+    pub fn entry_before<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = self.index_of(key)?.checked_sub(1)?;
+        self.entries.get(index).map(|(k, v)| (k, v))
+    }
+
+    /// Returns the entry right after the entry for `key`, in insertion order.
+    ///
+    /// Returns `None` if `key` is not present or is the last entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2)]);
+    /// assert_eq!(map.entry_after(&"a"), Some((&"b", &2)));
+    /// assert_eq!(map.entry_after(&"b"), None);
+    /// ```
+    pub fn entry_after<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = self.index_of(key)? + 1;
+        self.entries.get(index).map(|(k, v)| (k, v))
+    }
+
+    /// Binary searches the entries for `key`, assuming they are sorted by key.
+    ///
+    /// Returns `Ok(index)` if the key is found, or `Err(index)` with the position where it
+    /// would have to be inserted to keep the entries sorted. If the entries are not sorted by
+    /// key, the result is unspecified, the same as for [`slice::binary_search`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([(10, "a"), (20, "b"), (30, "c")]);
+    /// assert_eq!(map.binary_search_keys(&20), Ok(1));
+    /// assert_eq!(map.binary_search_keys(&25), Err(2));
+    /// ```
+    pub fn binary_search_keys<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entries.binary_search_by(|(k, _)| k.borrow().cmp(key))
+    }
+
+    /// Binary searches the entries with a comparator function, assuming they are sorted
+    /// consistently with it.
+    ///
+    /// `compare` should return how an entry is ordered relative to the target. See
+    /// [`slice::binary_search_by`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 5), ("c", 9)]);
+    /// assert_eq!(map.binary_search_by(|_, v| v.cmp(&5)), Ok(1));
+    /// ```
+    pub fn binary_search_by<F>(&self, mut compare: F) -> Result<usize, usize>
+    where
+        F: FnMut(&K, &V) -> Ordering,
+    {
+        self.entries.binary_search_by(|(k, v)| compare(k, v))
+    }
+
+    /// Returns `true` if the keys are in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// assert!(SeqMap::from([(1, "a"), (2, "b")]).is_sorted());
+    /// assert!(!SeqMap::from([(2, "b"), (1, "a")]).is_sorted());
+    /// ```
+    pub fn is_sorted(&self) -> bool
+    where
+        K: Ord,
+    {
+        self.keys().is_sorted()
+    }
+
+    /// Returns `true` if the entries are in ascending order of the key computed by `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("b", 1), ("a", 2)]);
+    /// assert!(map.is_sorted_by_key(|_, v| *v));
+    /// assert!(!map.is_sorted_by_key(|k, _| *k));
+    /// ```
+    pub fn is_sorted_by_key<B, F>(&self, mut f: F) -> bool
+    where
+        B: PartialOrd,
+        F: FnMut(&K, &V) -> B,
+    {
+        self.entries.iter().map(|(k, v)| f(k, v)).is_sorted()
+    }
+
+    /// Sorts the entries by key, so that insertion order becomes ascending key order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("c", 3), ("a", 1), ("b", 2)]);
+    /// map.sort_keys();
+    /// assert_eq!(map.binary_search_keys(&"b"), Ok(1));
+    /// assert_eq!(map.get_index(&"c").map(usize::from), Some(2));
+    /// ```
+    pub fn sort_keys(&mut self)
+    where
+        K: Ord,
+    {
+        self.sort_entries_by(|(a, _), (b, _)| a.cmp(b));
+        #[cfg(feature = "tracing")]
+        self.trace_reorder("sort_keys");
+    }
+
+    /// Sorts the entries by their values with `compare`.
+    ///
+    /// The sort is stable, so entries with equal values keep their current relative order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut scores = SeqMap::from([("ann", 10), ("bob", 30), ("cid", 10), ("dan", 30)]);
+    /// scores.sort_values_by(|a, b| b.cmp(a));
+    /// assert_eq!(
+    ///     scores.keys().copied().collect::<Vec<_>>(),
+    ///     vec!["bob", "dan", "ann", "cid"]
+    /// );
+    /// ```
+    pub fn sort_values_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&V, &V) -> Ordering,
+    {
+        self.sort_entries_by(|(_, a), (_, b)| compare(a, b));
+        #[cfg(feature = "tracing")]
+        self.trace_reorder("sort_values_by");
+    }
+
+    /// Reorders the entries so that the entry at position `order[i]` ends up at position `i`.
+    ///
+    /// The cached hashes move along with their entries and the key index is renumbered in
+    /// place, so no key is hashed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::IndexOutOfRange` if a position is not within the map, and
+    /// `SeqMapError::InvalidPermutation` if `order` does not contain every position exactly
+    /// once. The map is left untouched on error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// map.apply_permutation(&[2, 0, 1]).unwrap();
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec!["c", "a", "b"]);
+    /// assert_eq!(map.get_index(&"a").map(usize::from), Some(1));
+    /// assert!(map.apply_permutation(&[0, 0, 1]).is_err());
+    /// ```
+    pub fn apply_permutation(&mut self, order: &[usize]) -> Result<(), SeqMapError<K>> {
+        let len = self.entries.len();
+        if order.len() != len {
+            return Err(SeqMapError::InvalidPermutation { len });
+        }
+        let mut seen = vec![false; len];
+        for &index in order {
+            if index >= len {
+                return Err(SeqMapError::IndexOutOfRange { index, len });
+            }
+            if std::mem::replace(&mut seen[index], true) {
+                return Err(SeqMapError::InvalidPermutation { len });
+            }
+        }
+        self.permute(order);
+        self.check_invariants();
+        #[cfg(feature = "tracing")]
+        self.trace_reorder("apply_permutation");
+        Ok(())
+    }
+
+    /// Rotates the entry order so that the entry at position `mid` becomes the first one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is greater than the length of the map, like [`slice::rotate_left`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// map.rotate_left(1);
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec!["b", "c", "a"]);
+    /// assert_eq!(map.get_index(&"a").map(usize::from), Some(2));
+    /// ```
+    pub fn rotate_left(&mut self, mid: usize) {
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        order.rotate_left(mid);
+        self.permute(&order);
+        self.check_invariants();
+        #[cfg(feature = "tracing")]
+        self.trace_reorder("rotate_left");
+    }
+
+    /// Rotates the entry order so that the last `k` entries come first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is greater than the length of the map, like [`slice::rotate_right`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// map.rotate_right(1);
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec!["c", "a", "b"]);
+    /// assert_eq!(map.get_index(&"c").map(usize::from), Some(0));
+    /// ```
+    pub fn rotate_right(&mut self, k: usize) {
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        order.rotate_right(k);
+        self.permute(&order);
+        self.check_invariants();
+        #[cfg(feature = "tracing")]
+        self.trace_reorder("rotate_right");
+    }
+
+    /// Shuffles the entry order with a Fisher-Yates shuffle driven by a crate internal
+    /// generator seeded with `seed`.
+    ///
+    /// The same seed always gives the same order for the same map, on every platform and
+    /// across releases of this crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut a = SeqMap::from([("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+    /// let mut b = a.clone();
+    /// a.shuffle_seeded(42);
+    /// b.shuffle_seeded(42);
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn shuffle_seeded(&mut self, seed: u64) {
+        let mut rng = rng::SplitMix64::new(seed);
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        for i in (1..order.len()).rev() {
+            let j = rng.below(i as u64 + 1) as usize;
+            order.swap(i, j);
+        }
+        self.permute(&order);
+        self.check_invariants();
+        #[cfg(feature = "tracing")]
+        self.trace_reorder("shuffle_seeded");
+    }
+
+    /// Returns an iterator over consecutive slices of at most `chunk_size` entries, in
+    /// insertion order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let chunks: Vec<_> = map.chunks(2).collect();
+    /// assert_eq!(chunks, vec![&[("a", 1), ("b", 2)][..], &[("c", 3)][..]]);
+    /// ```
+    pub fn chunks(&self, chunk_size: usize) -> impl Iterator<Item = &[(K, V)]> {
+        self.entries.chunks(chunk_size)
+    }
+
+    /// Retrieves a reference to the value corresponding to the key.
+    ///
+    /// This method performs a faster lookup using the internal hash table.
+    ///
+    /// For a slower but simpler lookup, see [`slow_get`](Self::slow_get).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::new();
+    /// map.insert("key".to_string(), 42).unwrap();
+    /// assert_eq!(map.get(&"key".to_string()), Some(&42));
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Retrieves a reference to the value corresponding to the key, treating a missing key as
+    /// an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`KeyNotFoundError`] holding a clone of the key if it is not present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1)]);
+    /// assert_eq!(map.try_get(&"a"), Ok(&1));
+    /// let err = map.try_get(&"b").unwrap_err();
+    /// assert_eq!(err.key, "b");
+    /// assert_eq!(err.to_string(), "The key \"b\" was not found in the SeqMap (1 entries).");
+    /// ```
+    pub fn try_get<Q>(&self, key: &Q) -> Result<&V, KeyNotFoundError<K>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = K> + ?Sized,
+    {
+        self.get(key).ok_or_else(|| KeyNotFoundError {
+            key: key.to_owned(),
+            map_len: self.entries.len(),
+        })
+    }
+
+    /// Returns `true` if any entry has a value equal to `value`.
+    ///
+    /// This is a linear scan over the entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2)]);
+    /// assert!(map.contains_value(&2));
+    /// assert!(!map.contains_value(&3));
+    /// ```
+    pub fn contains_value(&self, value: &V) -> bool
+    where
+        V: PartialEq,
+    {
+        self.values().any(|v| v == value)
+    }
+
+    /// Returns the index, key and value of the first entry, in insertion order, that matches
+    /// `pred`.
+    ///
+    /// This is a linear scan over the entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 20), ("c", 30)]);
+    /// let (index, key, value) = map.find(|_, v| *v > 10).unwrap();
+    /// assert_eq!((usize::from(index), key, value), (1, &"b", &20));
+    /// ```
+    pub fn find<F>(&self, mut pred: F) -> Option<(SeqMapIndex<K, V>, &K, &V)>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.entries
+            .iter()
+            .enumerate()
+            .find(|(_, (key, value))| pred(key, value))
+            .map(|(index, (key, value))| (self.typed_index(index), key, value))
+    }
+
+    /// Returns the index of the first entry, in insertion order, that matches `pred`.
+    ///
+    /// This is a linear scan over the entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 20), ("c", 30)]);
+    /// assert_eq!(map.position_by(|_, v| *v == 30).map(usize::from), Some(2));
+    /// assert_eq!(map.position_by(|k, _| *k == "x"), None);
+    /// ```
+    pub fn position_by<F>(&self, mut pred: F) -> Option<SeqMapIndex<K, V>>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.entries
+            .iter()
+            .position(|(key, value)| pred(key, value))
+            .map(|index| self.typed_index(index))
+    }
+
+    /// Returns the index, key and value of the smallest entry according to `compare`.
+    ///
+    /// If several entries are equally small, the first one in insertion order is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 3), ("b", 1), ("c", 1)]);
+    /// let (index, key, _) = map.min_by(|(_, a), (_, b)| a.cmp(b)).unwrap();
+    /// assert_eq!((usize::from(index), key), (1, &"b"));
+    /// ```
+    pub fn min_by<F>(&self, mut compare: F) -> Option<(SeqMapIndex<K, V>, &K, &V)>
+    where
+        F: FnMut((&K, &V), (&K, &V)) -> Ordering,
+    {
+        self.select_first_by(|candidate, best| compare(candidate, best) == Ordering::Less)
+    }
 
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
-        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    /// Returns the index, key and value of the largest entry according to `compare`.
+    ///
+    /// If several entries are equally large, the first one in insertion order is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 3), ("b", 5), ("c", 5)]);
+    /// let (index, key, _) = map.max_by(|(_, a), (_, b)| a.cmp(b)).unwrap();
+    /// assert_eq!((usize::from(index), key), (1, &"b"));
+    /// ```
+    pub fn max_by<F>(&self, mut compare: F) -> Option<(SeqMapIndex<K, V>, &K, &V)>
+    where
+        F: FnMut((&K, &V), (&K, &V)) -> Ordering,
+    {
+        self.select_first_by(|candidate, best| compare(candidate, best) == Ordering::Greater)
     }
 
-    /// Retrieves a reference to the value corresponding to the key.
+    /// Returns the index, key and value of the entry with the smallest key computed by `f`.
     ///
-    /// This method performs a faster lookup using the internal `HashMap`.
+    /// If several entries are equally small, the first one in insertion order is returned.
     ///
-    /// For a slower but simpler lookup, see [`slow_get`](Self::slow_get).
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("long", 1), ("ab", 2), ("cd", 3)]);
+    /// let (index, key, _) = map.min_by_key(|k, _| k.len()).unwrap();
+    /// assert_eq!((usize::from(index), key), (1, &"ab"));
+    /// ```
+    pub fn min_by_key<B, F>(&self, f: F) -> Option<(SeqMapIndex<K, V>, &K, &V)>
+    where
+        B: Ord,
+        F: FnMut(&K, &V) -> B,
+    {
+        self.select_first_by_key(f, Ordering::Less)
+    }
+
+    /// Returns the index, key and value of the entry with the largest key computed by `f`.
+    ///
+    /// If several entries are equally large, the first one in insertion order is returned.
     ///
     /// # Examples
     ///
     /// ```
     /// use seq_map::SeqMap;
-    /// let mut map = SeqMap::new();
-    /// map.insert("key".to_string(), 42).unwrap();
-    /// assert_eq!(map.get(&"key".to_string()), Some(&42));
+    /// let map = SeqMap::from([("a", 1), ("bb", 2), ("cc", 3)]);
+    /// let (index, key, _) = map.max_by_key(|k, _| k.len()).unwrap();
+    /// assert_eq!((usize::from(index), key), (1, &"bb"));
+    /// ```
+    pub fn max_by_key<B, F>(&self, f: F) -> Option<(SeqMapIndex<K, V>, &K, &V)>
+    where
+        B: Ord,
+        F: FnMut(&K, &V) -> B,
+    {
+        self.select_first_by_key(f, Ordering::Greater)
+    }
+
+    /// Returns the first entry that no later entry `is_better` than.
+    fn select_first_by<F>(&self, mut is_better: F) -> Option<(SeqMapIndex<K, V>, &K, &V)>
+    where
+        F: FnMut((&K, &V), (&K, &V)) -> bool,
+    {
+        let mut best: Option<(usize, &K, &V)> = None;
+        for (index, (key, value)) in self.entries.iter().enumerate() {
+            match best {
+                Some((_, best_key, best_value))
+                    if !is_better((key, value), (best_key, best_value)) => {}
+                _ => best = Some((index, key, value)),
+            }
+        }
+        best.map(|(index, key, value)| (self.typed_index(index), key, value))
+    }
+
+    /// Returns the first entry whose computed key no later entry beats in direction `wanted`.
+    fn select_first_by_key<B, F>(
+        &self,
+        mut f: F,
+        wanted: Ordering,
+    ) -> Option<(SeqMapIndex<K, V>, &K, &V)>
+    where
+        B: Ord,
+        F: FnMut(&K, &V) -> B,
+    {
+        let mut best: Option<(B, usize)> = None;
+        for (index, (key, value)) in self.entries.iter().enumerate() {
+            let candidate = f(key, value);
+            match &best {
+                Some((best_key, _)) if candidate.cmp(best_key) != wanted => {}
+                _ => best = Some((candidate, index)),
+            }
+        }
+        best.map(|(_, index)| {
+            let (key, value) = &self.entries[index];
+            (self.typed_index(index), key, value)
+        })
+    }
+
+    /// Shrinks the entries and the index to fit the current number of entries.
+    ///
+    /// Positions stay the same and no key is hashed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map: SeqMap<_, _> = (0..100).map(|i| (i, i)).collect();
+    /// map.retain(|k, _| *k < 10);
+    /// map.shrink_to_fit();
+    /// assert_eq!(map.get_index(&9).map(usize::from), Some(9));
     /// ```
-    pub fn get(&self, key: &K) -> Option<&V> {
-        self.key_to_index
-            .get(key)
-            .map(|&index| &self.entries[index].1)
+    pub fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit();
+        self.key_index.shrink_to_fit();
     }
 
     /// Removes all elements from the map
     pub fn clear(&mut self) {
-        self.key_to_index.clear();
+        self.invalidate_positions();
+        self.key_index.clear();
         self.entries.clear();
+        self.check_invariants();
     }
 
     /// Removes a key from the map, returning the value if it existed
-    pub fn remove(&mut self, key: &K) -> Option<V> {
-        if let Some(&index) = self.key_to_index.get(key) {
-            self.key_to_index.remove(key);
-            // Update indices for all elements after the removed one
-            for k in self.entries[index + 1..].iter().map(|(k, _)| k) {
-                if let Some(idx) = self.key_to_index.get_mut(k) {
-                    *idx -= 1;
-                }
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.remove_full(key).map(|(_, _, value)| value)
+    }
+
+    /// Removes a key from the map, returning the stored key and the value if it existed.
+    ///
+    /// The order of the remaining entries is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut pending = SeqMap::from([(String::from("job"), 1)]);
+    /// let mut done = SeqMap::new();
+    /// let (key, value) = pending.remove_entry(&String::from("job")).unwrap();
+    /// done.insert(key, value).unwrap();
+    /// assert!(pending.is_empty());
+    /// ```
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.remove_full(key).map(|(_, key, value)| (key, value))
+    }
+
+    /// Removes a key from the map, returning the position the entry had, the stored key and
+    /// the value if it existed.
+    ///
+    /// The order of the remaining entries is kept, so the entries after the removed one each
+    /// move one position down. In memory only the entries on the shorter side of the removed
+    /// one are moved, so removing the first or the last entry is amortized O(1).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// assert_eq!(map.remove_full(&"b"), Some((1, "b", 2)));
+    /// assert_eq!(map.remove_full(&"b"), None);
+    /// assert_eq!(map.get_index(&"c").map(usize::from), Some(1));
+    /// ```
+    pub fn remove_full<Q>(&mut self, key: &Q) -> Option<(usize, K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = if self.key_index.is_empty() {
+            self.index_of(key)
+        } else {
+            self.unindex(key)
+        }?;
+        self.invalidate_positions();
+        let (key, value) = self.entries.remove(index);
+        #[cfg(feature = "tracing")]
+        self.trace_remove(&key, index);
+        self.check_invariants();
+        Some((index, key, value))
+    }
+
+    /// Removes all the given keys from the map, returning how many entries were removed.
+    ///
+    /// Keys that are not present are ignored. The remaining entries are reindexed once at the
+    /// end, so this is linear in the size of the map rather than in the number of keys times
+    /// the size of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+    /// assert_eq!(map.remove_many(&["a", "c", "x"]), 2);
+    /// assert_eq!(map.into_vec(), vec![("b", 2), ("d", 4)]);
+    /// ```
+    pub fn remove_many<'a, I>(&mut self, keys: I) -> usize
+    where
+        I: IntoIterator<Item = &'a K>,
+        K: 'a,
+    {
+        let removed = self.mark_keys(keys, true);
+        self.remove_marked(&removed)
+    }
+
+    /// Removes all the given keys from the map, returning how many entries were removed.
+    ///
+    /// This is the same as [`remove_many`](Self::remove_many), and the complement of
+    /// [`keep_only`](Self::keep_only).
+    pub fn remove_all_in<'a, I>(&mut self, keys: I) -> usize
+    where
+        I: IntoIterator<Item = &'a K>,
+        K: 'a,
+    {
+        self.remove_many(keys)
+    }
+
+    /// Removes every entry whose key is not among `keys`, returning how many entries were
+    /// removed.
+    ///
+    /// The remaining entries keep their relative order, whatever the order of `keys`. Keys
+    /// that are not present are ignored. Like [`remove_many`](Self::remove_many), the map is
+    /// reindexed once at the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+    /// assert_eq!(map.keep_only(&["d", "b", "x"]), 2);
+    /// assert_eq!(map.into_vec(), vec![("b", 2), ("d", 4)]);
+    /// ```
+    pub fn keep_only<'a, I>(&mut self, keys: I) -> usize
+    where
+        I: IntoIterator<Item = &'a K>,
+        K: 'a,
+    {
+        let removed = self.mark_keys(keys, false);
+        self.remove_marked(&removed)
+    }
+
+    /// Removes every entry whose key is present in `other`, returning how many entries were
+    /// removed.
+    ///
+    /// Only the keys of `other` matter, so its values and hasher may be of any type. The
+    /// remaining entries keep their order and the map is reindexed once. Use
+    /// [`remove_all_in`](Self::remove_all_in) for keys in a set or a list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let seen = SeqMap::from([("c", ()), ("a", ())]);
+    /// assert_eq!(map.subtract(&seen), 2);
+    /// assert_eq!(map.into_vec(), vec![("b", 2)]);
+    /// ```
+    pub fn subtract<V2, S2>(&mut self, other: &SeqMap<K, V2, S2>) -> usize
+    where
+        S2: BuildHasher,
+    {
+        let removed: Vec<bool> = self
+            .entries
+            .iter()
+            .map(|(key, _)| other.contains_key(key))
+            .collect();
+        self.remove_marked(&removed)
+    }
+
+    /// Removes every entry whose key is not present in `other`, returning how many entries
+    /// were removed.
+    ///
+    /// Only the keys of `other` matter, so its values and hasher may be of any type. The
+    /// remaining entries keep their order, not the order of `other`, and the map is reindexed
+    /// once. Use [`keep_only`](Self::keep_only) for keys in a set or a list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let alive = SeqMap::from([("c", true), ("a", true)]);
+    /// assert_eq!(map.intersect_with(&alive), 1);
+    /// assert_eq!(map.into_vec(), vec![("a", 1), ("c", 3)]);
+    /// ```
+    pub fn intersect_with<V2, S2>(&mut self, other: &SeqMap<K, V2, S2>) -> usize
+    where
+        S2: BuildHasher,
+    {
+        let removed: Vec<bool> = self
+            .entries
+            .iter()
+            .map(|(key, _)| !other.contains_key(key))
+            .collect();
+        self.remove_marked(&removed)
+    }
+
+    /// Returns a flag for every position, which is `listed` for the positions of `keys` and
+    /// the opposite for all others.
+    fn mark_keys<'a, I>(&self, keys: I, listed: bool) -> Vec<bool>
+    where
+        I: IntoIterator<Item = &'a K>,
+        K: 'a,
+    {
+        let mut marks = vec![!listed; self.entries.len()];
+        for key in keys {
+            if let Some(index) = self.index_of(key) {
+                marks[index] = listed;
             }
-            Some(self.entries.remove(index).1)
+        }
+        marks
+    }
+
+    /// Inserts an entry whose key is known not to be present at `index`, moving the entries on
+    /// the shorter side of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of entries.
+    fn insert_at(&mut self, index: usize, key: K, value: V) {
+        self.invalidate_positions();
+        if self.key_index.is_empty() {
+            self.entries.insert_at(index, (key, value));
+            // Builds the index once the map is no longer small
+            self.reindex_from(index);
         } else {
+            let hash = self.hash_builder.hash_one(&key);
+            self.key_index.insert_at(index, hash);
+            self.entries.insert_at(index, (key, value));
+        }
+        #[cfg(feature = "tracing")]
+        self.trace_insert(index);
+        self.check_invariants();
+    }
+
+    /// Removes the last entry, if any, without moving or hashing any other.
+    fn pop_last(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.entries.pop()?;
+        self.invalidate_positions();
+        self.key_index.pop();
+        #[cfg(feature = "tracing")]
+        self.trace_remove(&key, self.entries.len());
+        self.check_invariants();
+        Some((key, value))
+    }
+
+    /// Removes the entries at the positions marked in `removed`, returning how many there were.
+    fn remove_marked(&mut self, removed: &[bool]) -> usize {
+        self.invalidate_positions();
+        let len_before = self.entries.len();
+        let mut index = 0;
+        self.entries.retain(|_| {
+            index += 1;
+            !removed[index - 1]
+        });
+        self.key_index.compact(removed);
+        self.check_invariants();
+        len_before - self.entries.len()
+    }
+
+    /// Removes all elements from the map and returns them as an iterator, in insertion order.
+    ///
+    /// The map is emptied right away and keeps its allocated capacity. Entries that have not
+    /// been yielded when the [`Drain`] is dropped are dropped with it, and if the `Drain` is
+    /// leaked the map is still left empty and valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let mut drain = map.drain();
+    /// assert_eq!(drain.next(), Some(("a", 1)));
+    /// drop(drain);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, K, V> {
+        self.invalidate_positions();
+        self.key_index.clear();
+        Drain {
+            inner: self.entries.drain(..),
+        }
+    }
+
+    /// Retains only the entries for which `keep` returns `true`, preserving their order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// map.retain(|_, v| *v != 2);
+    /// assert_eq!(map.into_vec(), vec![("a", 1), ("c", 3)]);
+    /// ```
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.retain_with(|_, key, value| keep(key, value), drop);
+    }
+
+    /// Retains only the entries for which `keep` returns `true`, preserving their order.
+    /// `keep` is also given the position the entry had before any entry was removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", false), ("b", false), ("c", true), ("d", false)]);
+    /// map.retain_with_index(|index, _, pinned| index < 1 || *pinned);
+    /// assert_eq!(map.into_vec(), vec![("a", false), ("c", true)]);
+    /// ```
+    pub fn retain_with_index<F>(&mut self, keep: F)
+    where
+        F: FnMut(usize, &K, &mut V) -> bool,
+    {
+        self.retain_with(keep, drop);
+    }
+
+    /// Retains only the entries for which `keep` returns `true`, and returns the removed
+    /// entries in their original order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut active = SeqMap::from([("a", 1), ("b", 0), ("c", 3), ("d", 0)]);
+    /// let archived = active.retain_drain(|_, v| *v != 0);
+    /// assert_eq!(archived, vec![("b", 0), ("d", 0)]);
+    /// assert_eq!(active.into_vec(), vec![("a", 1), ("c", 3)]);
+    /// ```
+    pub fn retain_drain<F>(&mut self, mut keep: F) -> Vec<(K, V)>
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut removed = Vec::new();
+        self.retain_with(
+            |_, key, value| keep(key, value),
+            |entry| removed.push(entry),
+        );
+        removed
+    }
+
+    /// Shared implementation of the retain family, handing every removed entry to `on_removed`.
+    fn retain_with<F, R>(&mut self, mut keep: F, mut on_removed: R)
+    where
+        F: FnMut(usize, &K, &mut V) -> bool,
+        R: FnMut((K, V)),
+    {
+        self.invalidate_positions();
+        let entries = std::mem::take(&mut self.entries);
+        self.entries.reserve(entries.len());
+        let mut guard = RetainGuard {
+            removed: Vec::with_capacity(entries.len()),
+            rest: entries.into_vec().into_iter(),
+            map: self,
+        };
+        for (key, mut value) in guard.rest.by_ref() {
+            // Counts as removed until it is kept, so an entry lost to a panic leaves the index
+            let index = guard.removed.len();
+            guard.removed.push(true);
+            if keep(index, &key, &mut value) {
+                *guard.removed.last_mut().expect("just pushed") = false;
+                guard.map.entries.push((key, value));
+            } else {
+                on_removed((key, value));
+            }
+        }
+        drop(guard);
+        self.check_invariants();
+    }
+
+    /// Moves all entries of `other` to the end of this map, leaving `other` empty.
+    ///
+    /// The entries keep their relative order. If a key from `other` is already present, the
+    /// existing entry is kept and the one from `other` is dropped, the same as [`Extend`].
+    /// `other` keeps its allocated capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut a = SeqMap::from([("a", 1), ("b", 2)]);
+    /// let mut b = SeqMap::from([("b", 20), ("c", 30)]);
+    /// a.append(&mut b);
+    /// assert!(b.is_empty());
+    /// assert_eq!(a.into_vec(), vec![("a", 1), ("b", 2), ("c", 30)]);
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        self.entries.reserve(other.len());
+        self.key_index.reserve(other.len());
+        for (key, value) in other.drain() {
+            let _ = self.insert(key, value);
+        }
+    }
+
+    /// Appends clones of all entries of `other` to the end of this map, leaving `other` as it
+    /// is.
+    ///
+    /// The entries keep their relative order. If a key from `other` is already present, the
+    /// existing entry is kept, the same as [`Extend`], and nothing is cloned for it. `other`
+    /// may use another hasher.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut a = SeqMap::from([("a", 1), ("b", 2)]);
+    /// let b = SeqMap::from([("b", 20), ("c", 30)]);
+    /// a.extend_from_map(&b);
+    /// assert_eq!(b.len(), 2);
+    /// assert_eq!(a.into_vec(), vec![("a", 1), ("b", 2), ("c", 30)]);
+    /// ```
+    pub fn extend_from_map<S2>(&mut self, other: &SeqMap<K, V, S2>)
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.entries.reserve(other.entries.len());
+        self.key_index.reserve(other.entries.len());
+        self.extend(other);
+    }
+
+    /// Inserts all pairs from `iter`, or none of them if any key collides.
+    ///
+    /// A key collides if it is already present in the map or occurs more than once in `iter`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the colliding keys, in the order they were encountered, and leaves the map
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1)]);
+    /// assert_eq!(map.try_extend([("b", 2), ("a", 3), ("c", 4)]), Err(vec!["a"]));
+    /// assert_eq!(map.len(), 1);
+    ///
+    /// map.try_extend([("b", 2), ("c", 3)]).unwrap();
+    /// assert_eq!(map.len(), 3);
+    /// ```
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), Vec<K>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let pairs: Vec<_> = iter.into_iter().collect();
+        let hashes: Vec<u64> = pairs
+            .iter()
+            .map(|(key, _)| self.hash_builder.hash_one(key))
+            .collect();
+        let collisions: Vec<bool> = {
+            // Positions in `pairs` of the keys seen so far, hashed with the hashes above
+            let mut seen = HashTable::with_capacity(pairs.len());
+            pairs
+                .iter()
+                .zip(&hashes)
+                .enumerate()
+                .map(|(index, ((key, _), &hash))| {
+                    let present = if self.key_index.is_empty() {
+                        self.index_of(key).is_some()
+                    } else {
+                        self.find_hashed(hash, key).is_some()
+                    };
+                    let repeated = match seen.entry(
+                        hash,
+                        |&other: &usize| pairs[other].0 == *key,
+                        |&other| hashes[other],
+                    ) {
+                        hash_table::Entry::Occupied(_) => true,
+                        hash_table::Entry::Vacant(slot) => {
+                            slot.insert(index);
+                            false
+                        }
+                    };
+                    present || repeated
+                })
+                .collect()
+        };
+        if collisions.contains(&true) {
+            return Err(pairs
+                .into_iter()
+                .zip(collisions)
+                .filter_map(|((key, _), collided)| collided.then_some(key))
+                .collect());
+        }
+        self.entries.reserve(pairs.len());
+        self.key_index.reserve(pairs.len());
+        for ((key, value), hash) in pairs.into_iter().zip(hashes) {
+            let hash = (!self.key_index.is_empty()).then_some(hash);
+            self.push_hashed_entry(hash, key, value);
+        }
+        Ok(())
+    }
+
+    /// Inserts all pairs from `iter`, skipping the ones whose key is already present.
+    ///
+    /// Capacity is reserved once up front, based on the size hint of `iter`. The returned
+    /// [`InsertReport`] lists the indices of the inserted entries and hands back the skipped
+    /// pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1)]);
+    /// let report = map.insert_many([("b", 2), ("a", 3), ("c", 4)]);
+    /// let inserted: Vec<usize> = report.inserted.into_iter().map(usize::from).collect();
+    /// assert_eq!(inserted, vec![1, 2]);
+    /// assert_eq!(report.skipped, vec![("a", 3)]);
+    /// ```
+    pub fn insert_many<I>(&mut self, iter: I) -> InsertReport<K, V>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.entries.reserve(lower);
+        self.key_index.reserve(lower);
+
+        let mut report = InsertReport {
+            inserted: Vec::with_capacity(lower),
+            skipped: Vec::new(),
+        };
+        for (key, value) in iter {
+            match self.push_if_absent(key, value) {
+                Ok(index) => report.inserted.push(self.typed_index(index)),
+                Err(pair) => report.skipped.push(pair),
+            }
+        }
+        report
+    }
+
+    /// Replaces the entries in the positional `range` with the pairs from `replace_with`,
+    /// returning the removed entries in order.
+    ///
+    /// The replacement pairs end up where the removed range was, and everything after the
+    /// range is shifted accordingly. Keys inside the removed range may be reused by the
+    /// replacement.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DuplicateKeyError`] for the first replacement pair whose key is still present
+    /// outside of `range`, or occurs more than once in `replace_with`. The map is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, the same as [`Vec::splice`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let removed = map.splice(1..2, [("x", 10), ("y", 20)]).unwrap();
+    /// assert_eq!(removed, vec![("b", 2)]);
+    /// assert_eq!(map.into_vec(), vec![("a", 1), ("x", 10), ("y", 20), ("c", 3)]);
+    /// ```
+    pub fn splice<R, I>(
+        &mut self,
+        range: R,
+        replace_with: I,
+    ) -> Result<Vec<(K, V)>, DuplicateKeyError<K, V>>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let range = self.resolve_range(range);
+        let mut replacement: Vec<_> = replace_with.into_iter().collect();
+
+        let mut seen = HashSet::with_capacity(replacement.len());
+        let duplicate = replacement.iter().position(|(key, _)| {
+            let outside_range = self
+                .index_of(key)
+                .is_some_and(|index| !range.contains(&index));
+            outside_range || !seen.insert(key)
+        });
+        if let Some(index) = duplicate {
+            let (key, value) = replacement.swap_remove(index);
+            return Err(DuplicateKeyError { index, key, value });
+        }
+
+        let start = range.start;
+        if !self.key_index.is_empty() {
+            let hash_builder = &self.hash_builder;
+            self.key_index.splice_hashes(
+                range.clone(),
+                replacement
+                    .iter()
+                    .map(|(key, _)| hash_builder.hash_one(key)),
+            );
+        }
+        self.invalidate_positions();
+        let removed = self.entries.splice(range, replacement);
+        self.reindex_from(start);
+        self.check_invariants();
+        Ok(removed)
+    }
+
+    /// Appends an entry unless its key is already present, hashing the key only once.
+    /// Returns the index of the new entry, or hands the pair back.
+    fn push_if_absent(&mut self, key: K, value: V) -> Result<usize, (K, V)> {
+        let hash = if self.key_index.is_empty() {
+            if self.index_of(&key).is_some() {
+                return Err((key, value));
+            }
             None
+        } else {
+            let hash = self.hash_builder.hash_one(&key);
+            if self.find_hashed(hash, &key).is_some() {
+                return Err((key, value));
+            }
+            Some(hash)
+        };
+        Ok(self.push_hashed_entry(hash, key, value))
+    }
+
+    /// Appends an entry whose key is known not to be present, returning its index.
+    fn push_entry(&mut self, key: K, value: V) -> usize {
+        let hash = (!self.key_index.is_empty()).then(|| self.hash_builder.hash_one(&key));
+        self.push_hashed_entry(hash, key, value)
+    }
+
+    /// Appends an entry whose key is known not to be present, returning its index. `hash` is
+    /// the hash of the key, which must be given exactly when the map has an index.
+    fn push_hashed_entry(&mut self, hash: Option<u64>, key: K, value: V) -> usize {
+        let index = self.entries.len();
+        if let Some(hash) = hash {
+            self.entries.push((key, value));
+            self.key_index.push(hash);
+        } else {
+            self.entries.push((key, value));
+            // Builds the index once the map is no longer small
+            self.reindex_from(index);
+        }
+        self.generation.draw_once();
+        #[cfg(feature = "tracing")]
+        self.trace_insert(index);
+        self.check_invariants();
+        index
+    }
+
+    /// Converts any kind of positional range into a `Range`, panicking if it is out of bounds.
+    fn resolve_range<R: RangeBounds<usize>>(&self, range: R) -> Range<usize> {
+        resolve_range(range, 0..self.entries.len())
+    }
+
+    /// Rebuilds the index for every entry from `start` to the end of `entries`, from the
+    /// hashes already in the index.
+    ///
+    /// Small maps have no index, and get a complete one, hashing every key once, when they
+    /// grow past [`SMALL_MAP_LEN`].
+    fn reindex_from(&mut self, start: usize) {
+        if !self.key_index.is_empty() {
+            self.key_index.reindex_from(start);
+        } else if self.entries.len() > SMALL_MAP_LEN {
+            let hash_builder = &self.hash_builder;
+            self.key_index.build(
+                self.entries
+                    .iter()
+                    .map(|(key, _)| hash_builder.hash_one(key)),
+            );
+        }
+    }
+
+    /// Sorts the entries with `compare`, keeping the hashes and the index in step.
+    fn sort_entries_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&(K, V), &(K, V)) -> Ordering,
+    {
+        self.invalidate_positions();
+        if self.key_index.is_empty() {
+            self.entries.sort_by(compare);
+        } else {
+            let mut order: Vec<usize> = (0..self.entries.len()).collect();
+            order.sort_by(|&a, &b| compare(&self.entries[a], &self.entries[b]));
+            self.permute(&order);
         }
+        self.check_invariants();
+    }
+
+    /// Returns `true` if both maps contain the same key-value pairs, regardless of order.
+    ///
+    /// Unlike `==`, which also requires the insertion order to match, this only compares
+    /// the contents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut a = SeqMap::new();
+    /// a.insert("x", 1).unwrap();
+    /// a.insert("y", 2).unwrap();
+    ///
+    /// let mut b = SeqMap::new();
+    /// b.insert("y", 2).unwrap();
+    /// b.insert("x", 1).unwrap();
+    ///
+    /// assert!(a != b);
+    /// assert!(a.eq_ignore_order(&b));
+    /// ```
+    pub fn eq_ignore_order<S2>(&self, other: &SeqMap<K, V, S2>) -> bool
+    where
+        V: PartialEq,
+        S2: BuildHasher,
+    {
+        self.len() == other.len()
+            && self
+                .entries
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
     }
 
-    /// Removes all elements from the map and returns them as an iterator
-    pub fn drain(&mut self) -> impl Iterator<Item = (K, V)> + '_ {
-        self.key_to_index.clear();
-        self.entries.drain(..)
+    /// Returns `true` if both maps contain the same keys, regardless of order and values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut a = SeqMap::new();
+    /// a.insert("x", 1).unwrap();
+    /// a.insert("y", 2).unwrap();
+    ///
+    /// let mut b = SeqMap::new();
+    /// b.insert("y", 20).unwrap();
+    /// b.insert("x", 10).unwrap();
+    ///
+    /// assert!(a.keys_eq_ignore_order(&b));
+    /// ```
+    pub fn keys_eq_ignore_order<V2, S2>(&self, other: &SeqMap<K, V2, S2>) -> bool
+    where
+        S2: BuildHasher,
+    {
+        self.len() == other.len() && self.keys().all(|key| other.contains_key(key))
     }
 }
 
-impl<K, V> Index<&K> for SeqMap<K, V>
+impl<K, V, Q, S> Index<&Q> for SeqMap<K, V, S>
 where
-    K: Eq + Hash + Clone,
-    V: Clone,
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
 {
     type Output = V;
 
     /// Allows accessing values using the indexing syntax (`map[&key]`).
     ///
+    /// Like `HashMap`, any borrowed form of the key can be used, such as `&str` for `String`
+    /// keys.
+    ///
     /// # Panics
     ///
     /// Panics if the key is not present in the map.
@@ -323,9 +3054,38 @@ where
     /// let mut map = SeqMap::new();
     /// map.insert("key".to_string(), 42).unwrap();
     /// assert_eq!(map[&"key".to_string()], 42);
+    /// assert_eq!(map["key"], 42);
+    /// ```
+    fn index(&self, key: &Q) -> &Self::Output {
+        let index = self.index_of(key).expect("Key not found in SeqMap");
+        &self.entries[index].1
+    }
+}
+
+impl<K, V, Q, S> IndexMut<&Q> for SeqMap<K, V, S>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    /// Allows updating existing values using the indexing syntax (`map[&key] = value`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the map. Use [`SeqMap::insert`] to add new keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::from([("a".to_string(), 1)]);
+    /// map["a"] = 10;
+    /// map[&"a".to_string()] += 1;
+    /// assert_eq!(map["a"], 11);
     /// ```
-    fn index(&self, key: &K) -> &Self::Output {
-        self.get(key).expect("Key not found in SeqMap")
+    fn index_mut(&mut self, key: &Q) -> &mut Self::Output {
+        let index = self.index_of(key).expect("Key not found in SeqMap");
+        &mut self.entries[index].1
     }
 }
 
@@ -360,17 +3120,104 @@ where
     }
 }
 
+impl<K, V> From<Vec<(K, V)>> for SeqMap<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates a `SeqMap` from a vector of key-value pairs, keeping the vector order.
+    ///
+    /// If duplicate keys are present in the vector, the first occurrence is kept, and subsequent
+    /// duplicates are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from(vec![("b", 2), ("a", 1), ("b", 3)]);
+    /// assert_eq!(map.into_vec(), vec![("b", 2), ("a", 1)]);
+    /// ```
+    fn from(pairs: Vec<(K, V)>) -> Self {
+        pairs.into_iter().collect()
+    }
+}
+
+impl<K, V, const N: usize> From<[(K, V); N]> for SeqMap<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates a `SeqMap` from an array of key-value pairs, keeping the array order.
+    ///
+    /// If duplicate keys are present in the array, the first occurrence is kept, and subsequent
+    /// duplicates are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2)]);
+    /// assert_eq!(map[&"b"], 2);
+    /// ```
+    fn from(pairs: [(K, V); N]) -> Self {
+        pairs.into_iter().collect()
+    }
+}
+
+impl<K, V> From<BTreeMap<K, V>> for SeqMap<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates a `SeqMap` from a [`BTreeMap`], ordered by ascending key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// use std::collections::BTreeMap;
+    /// let btree_map = BTreeMap::from([("b", 2), ("a", 1)]);
+    /// let map = SeqMap::from(btree_map);
+    /// assert_eq!(map.into_vec(), vec![("a", 1), ("b", 2)]);
+    /// ```
+    fn from(btree_map: BTreeMap<K, V>) -> Self {
+        btree_map.into_iter().collect()
+    }
+}
+
+impl<K, V, S> From<HashMap<K, V, S>> for SeqMap<K, V>
+where
+    K: Eq + Hash + Ord,
+{
+    /// Creates a `SeqMap` from a [`HashMap`], ordered by ascending key.
+    ///
+    /// The iteration order of a `HashMap` is unspecified, so the entries are sorted by key
+    /// to keep the resulting order deterministic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// use std::collections::HashMap;
+    /// let hash_map = HashMap::from([("c", 3), ("a", 1), ("b", 2)]);
+    /// let map = SeqMap::from(hash_map);
+    /// assert_eq!(map.into_vec(), vec![("a", 1), ("b", 2), ("c", 3)]);
+    /// ```
+    fn from(hash_map: HashMap<K, V, S>) -> Self {
+        let mut pairs: Vec<_> = hash_map.into_iter().collect();
+        pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        pairs.into_iter().collect()
+    }
+}
+
 /// Creates a `SeqMap` from an iterator of key-value pairs.
 ///
 /// If duplicate keys are present in the iterator, the first occurrence is kept,
 /// and subsequent duplicates are silently ignored.
-impl<K: Hash, V> FromIterator<(K, V)> for SeqMap<K, V>
+impl<K: Hash, V, S> FromIterator<(K, V)> for SeqMap<K, V, S>
 where
-    K: Eq + Clone,
-    V: Clone,
+    K: Eq,
+    S: BuildHasher + Default,
 {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
-        let mut map = SeqMap::new();
+        let mut map = Self::default();
         for (k, v) in iter {
             let _ = map.insert(k, v); // Intentionally ignore errors for this trait
         }
@@ -378,10 +3225,7 @@ where
     }
 }
 
-impl<'a, K, V> IntoIterator for &'a SeqMap<K, V>
-where
-    K: Eq + Hash + Clone,
-{
+impl<'a, K, V, S> IntoIterator for &'a SeqMap<K, V, S> {
     type Item = (&'a K, &'a V);
     type IntoIter = std::iter::Map<std::slice::Iter<'a, (K, V)>, fn(&'a (K, V)) -> (&'a K, &'a V)>;
 
@@ -431,7 +3275,7 @@ where
     }
 }
 
-impl<K, V> Default for SeqMap<K, V> {
+impl<K, V, S: Default> Default for SeqMap<K, V, S> {
     /// Creates a new, empty `SeqMap`.
     ///
     /// # Examples
@@ -442,17 +3286,18 @@ impl<K, V> Default for SeqMap<K, V> {
     /// ```
     fn default() -> Self {
         Self {
-            key_to_index: HashMap::default(),
-            entries: Vec::default(),
+            key_index: KeyIndex::new(),
+            hash_builder: S::default(),
+            entries: Entries::default(),
+            #[cfg(feature = "tracing")]
+            trace: None,
+            generation: Generation::new(),
         }
     }
 }
 
 // Mutable reference iterator
-impl<'a, K, V> IntoIterator for &'a mut SeqMap<K, V>
-where
-    K: Eq + Hash + Clone,
-{
+impl<'a, K, V, S> IntoIterator for &'a mut SeqMap<K, V, S> {
     type Item = (&'a K, &'a mut V);
     type IntoIter =
         std::iter::Map<std::slice::IterMut<'a, (K, V)>, fn(&'a mut (K, V)) -> (&'a K, &'a mut V)>;
@@ -463,21 +3308,69 @@ where
 }
 
 // Consuming iterator
-impl<K, V> IntoIterator for SeqMap<K, V>
-where
-    K: Eq + Hash + Clone,
-{
+impl<K, V, S> IntoIterator for SeqMap<K, V, S> {
     type Item = (K, V);
     type IntoIter = std::vec::IntoIter<(K, V)>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.entries.into_iter()
+        self.entries.into_vec().into_iter()
     }
 }
 
-impl<K, V> SeqMap<K, V>
+/// Puts a map back together after the retain family has run, or has panicked half way.
+///
+/// The entries not visited yet are kept, after the ones already kept.
+struct RetainGuard<'a, K, V, S> {
+    map: &'a mut SeqMap<K, V, S>,
+    rest: std::vec::IntoIter<(K, V)>,
+    // For every entry taken out of `rest`, whether it was removed
+    removed: Vec<bool>,
+}
+
+impl<K, V, S> Drop for RetainGuard<'_, K, V, S> {
+    fn drop(&mut self) {
+        for entry in self.rest.by_ref() {
+            self.removed.push(false);
+            self.map.entries.push(entry);
+        }
+        self.map.key_index.compact(&self.removed);
+    }
+}
+
+/// A draining iterator over the entries of a `SeqMap`, in insertion order.
+///
+/// Created by [`SeqMap::drain`].
+#[derive(Debug)]
+pub struct Drain<'a, K, V> {
+    inner: std::collections::vec_deque::Drain<'a, (K, V)>,
+}
+
+impl<K, V> Iterator for Drain<'_, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Drain<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Drain<'_, K, V> {}
+
+impl<K, V> FusedIterator for Drain<'_, K, V> {}
+
+impl<K, V, S> SeqMap<K, V, S>
 where
-    K: Eq + Hash + Clone,
+    K: Eq + Hash,
+    S: BuildHasher,
 {
     pub fn into_keys(self) -> impl Iterator<Item = K> {
         self.entries.into_iter().map(|(k, _)| k)
@@ -486,11 +3379,333 @@ where
     pub fn into_values(self) -> impl Iterator<Item = V> {
         self.entries.into_iter().map(|(_, v)| v)
     }
+
+    /// Consumes the map and returns the key-value pairs in insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::new();
+    /// map.insert("b", 2).unwrap();
+    /// map.insert("a", 1).unwrap();
+    /// assert_eq!(map.into_vec(), vec![("b", 2), ("a", 1)]);
+    /// ```
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.entries.into_vec()
+    }
+
+    /// Consumes the map and returns the key-value pairs in insertion order.
+    ///
+    /// The counterpart of [`as_entries`](Self::as_entries), and the same as
+    /// [`into_vec`](Self::into_vec).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("b", 2), ("a", 1)]);
+    /// assert_eq!(map.into_entries(), vec![("b", 2), ("a", 1)]);
+    /// ```
+    pub fn into_entries(self) -> Vec<(K, V)> {
+        self.entries.into_vec()
+    }
+
+    /// Consumes the map and transforms every value with `f`, keeping keys and order.
+    ///
+    /// The key lookup table is reused as is, so no key is hashed or cloned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2)]);
+    /// let map = map.into_map_values(|v| v.to_string());
+    /// assert_eq!(map[&"b"], "2");
+    /// ```
+    pub fn into_map_values<W, F>(mut self, mut f: F) -> SeqMap<K, W, S>
+    where
+        F: FnMut(V) -> W,
+    {
+        let entries = std::mem::take(&mut self.entries)
+            .into_iter()
+            .map(|(key, value)| (key, f(value)))
+            .collect();
+        self.with_entries(entries)
+    }
+
+    /// Consumes the map and transforms every value with the fallible `f`, keeping keys and
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first entry for which `f` fails and returns a [`MapValuesError`] holding
+    /// that entry's index, key, and the error. The remaining entries are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", "1"), ("b", "2")]);
+    /// let map = map.try_into_map_values(|_, v| v.parse::<i32>()).unwrap();
+    /// assert_eq!(map.into_vec(), vec![("a", 1), ("b", 2)]);
+    /// ```
+    pub fn try_into_map_values<W, E, F>(
+        mut self,
+        mut f: F,
+    ) -> Result<SeqMap<K, W, S>, MapValuesError<K, E>>
+    where
+        F: FnMut(&K, V) -> Result<W, E>,
+    {
+        let mut entries = Entries::with_capacity(self.entries.len());
+        for (index, (key, value)) in std::mem::take(&mut self.entries).into_iter().enumerate() {
+            match f(&key, value) {
+                Ok(mapped) => entries.push((key, mapped)),
+                Err(error) => return Err(MapValuesError { index, key, error }),
+            }
+        }
+        Ok(self.with_entries(entries))
+    }
+
+    /// Consumes the map and transforms every key with `f`, keeping the values and order.
+    ///
+    /// Keys that collapse into the same new key are resolved according to `policy`, in which
+    /// case the entry keeps the position of the first of them.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DuplicateKeyError`] for the first collision if `policy` is
+    /// [`DuplicatePolicy::Error`]. The reported index is the position in this map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::{DuplicatePolicy, SeqMap};
+    /// let map = SeqMap::from([("Foo", 1), ("bar", 2), ("FOO", 3)]);
+    /// let map = map
+    ///     .map_keys(|k| k.to_lowercase(), DuplicatePolicy::Merge(|a, b| *a += b))
+    ///     .unwrap();
+    /// assert_eq!(map.into_vec(), vec![("foo".to_string(), 4), ("bar".to_string(), 2)]);
+    /// ```
+    pub fn map_keys<K2, F>(
+        self,
+        mut f: F,
+        policy: DuplicatePolicy<V>,
+    ) -> Result<SeqMap<K2, V, S>, DuplicateKeyError<K2, V>>
+    where
+        K2: Eq + Hash,
+        F: FnMut(K) -> K2,
+    {
+        let mut map = SeqMap::with_hasher(self.hash_builder);
+        map.entries.reserve(self.entries.len());
+        for (index, (key, value)) in self.entries.into_iter().enumerate() {
+            map.insert_with_policy(index, f(key), value, policy)?;
+        }
+        Ok(map)
+    }
+
+    /// Consumes the map and transforms the values with `f`, dropping the entries for which it
+    /// returns `None`. The kept entries keep their relative order.
+    ///
+    /// The key lookup table is reused without hashing any key again, and no key is cloned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", "1"), ("b", "x"), ("c", "3")]);
+    /// let map = map.filter_map(|_, v| v.parse::<i32>().ok());
+    /// assert_eq!(map.into_vec(), vec![("a", 1), ("c", 3)]);
+    /// ```
+    pub fn filter_map<W, F>(mut self, mut f: F) -> SeqMap<K, W, S>
+    where
+        F: FnMut(&K, V) -> Option<W>,
+    {
+        let mut entries = Entries::with_capacity(self.entries.len());
+        let mut removed = Vec::with_capacity(self.entries.len());
+        for (key, value) in std::mem::take(&mut self.entries) {
+            let mapped = f(&key, value);
+            removed.push(mapped.is_none());
+            if let Some(mapped) = mapped {
+                entries.push((key, mapped));
+            }
+        }
+        let mut map = self.with_entries(entries);
+        map.key_index.compact(&removed);
+        if removed.contains(&true) {
+            map.invalidate_positions();
+        }
+        map
+    }
+
+    /// Consumes both maps and combines the values of the keys present in both with `f`.
+    ///
+    /// This is an inner join on the keys. The result is in the insertion order of `self`;
+    /// keys that are only present in one of the maps are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let prices = SeqMap::from([("apple", 3), ("pear", 4), ("plum", 2)]);
+    /// let counts = SeqMap::from([("plum", 10), ("apple", 5)]);
+    /// let totals = prices.join_with(counts, |price, count| price * count);
+    /// assert_eq!(totals.into_vec(), vec![("apple", 15), ("plum", 20)]);
+    /// ```
+    pub fn join_with<V2, S2, W, F>(self, other: SeqMap<K, V2, S2>, mut f: F) -> SeqMap<K, W, S>
+    where
+        S2: BuildHasher,
+        F: FnMut(V, V2) -> W,
+    {
+        let positions: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(key, _)| other.index_of(key))
+            .collect();
+        let mut other_values: Vec<_> = other.entries.into_iter().map(|(_, v)| Some(v)).collect();
+        let mut joined = SeqMap::with_hasher(self.hash_builder);
+        for ((key, value), position) in self.entries.into_iter().zip(positions) {
+            let other_value = position.and_then(|index| other_values[index].take());
+            if let Some(other_value) = other_value {
+                joined.push_entry(key, f(value, other_value));
+            }
+        }
+        joined
+    }
+
+    /// Consumes the map and splits it into two maps: the entries for which `pred` returns
+    /// `true`, and the rest.
+    ///
+    /// Both maps keep the relative insertion order of their entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+    /// let (even, odd) = map.partition(|_, v| v % 2 == 0);
+    /// assert_eq!(even.into_vec(), vec![("b", 2), ("d", 4)]);
+    /// assert_eq!(odd.into_vec(), vec![("a", 1), ("c", 3)]);
+    /// ```
+    pub fn partition<F>(self, mut pred: F) -> (Self, Self)
+    where
+        S: Clone,
+        F: FnMut(&K, &V) -> bool,
+    {
+        let mut matching = Self::with_hasher(self.hash_builder.clone());
+        let mut rest = Self::with_hasher(self.hash_builder);
+        for (key, value) in self.entries {
+            if pred(&key, &value) {
+                matching.push_entry(key, value);
+            } else {
+                rest.push_entry(key, value);
+            }
+        }
+        (matching, rest)
+    }
+
+    /// Consumes the map and groups its entries by the key `f` gives them.
+    ///
+    /// The groups are in the order their first entry had, and every group keeps the relative
+    /// insertion order of its entries. The group maps use clones of this map's hasher.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("ant", 1), ("bee", 2), ("asp", 3), ("cow", 4)]);
+    /// let groups = map.group_by(|k, _| k.as_bytes()[0]);
+    /// assert_eq!(groups.keys().copied().collect::<Vec<_>>(), vec![b'a', b'b', b'c']);
+    /// assert_eq!(groups[&b'a'].clone().into_vec(), vec![("ant", 1), ("asp", 3)]);
+    /// ```
+    pub fn group_by<G, F>(self, mut f: F) -> SeqMap<G, Self>
+    where
+        S: Clone,
+        G: Eq + Hash,
+        F: FnMut(&K, &V) -> G,
+    {
+        let mut groups = SeqMap::new();
+        for (key, value) in self.entries {
+            let group = f(&key, &value);
+            groups
+                .get_mut_or_insert_with(group, || Self::with_hasher(self.hash_builder.clone()))
+                .push_entry(key, value);
+        }
+        groups
+    }
+
+    /// Groups references to the entries by the key `f` gives them, leaving the map as it is.
+    ///
+    /// Returns an iterator over the groups in the order their first entry had, each with its
+    /// entries in insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let mut groups = map.group_by_ref(|_, v| v % 2);
+    /// assert_eq!(groups.next(), Some((1, vec![(&"a", &1), (&"c", &3)])));
+    /// assert_eq!(groups.next(), Some((0, vec![(&"b", &2)])));
+    /// ```
+    pub fn group_by_ref<G, F>(
+        &self,
+        mut f: F,
+    ) -> impl DoubleEndedIterator<Item = (G, Vec<(&K, &V)>)> + ExactSizeIterator
+    where
+        G: Eq + Hash,
+        F: FnMut(&K, &V) -> G,
+    {
+        let mut groups: SeqMap<G, Vec<(&K, &V)>> = SeqMap::new();
+        for (key, value) in &self.entries {
+            groups
+                .get_mut_or_insert_with(f(key, value), Vec::new)
+                .push((key, value));
+        }
+        groups.entries.into_iter()
+    }
+
+    /// Consumes the map and splits it into consecutive maps of at most `chunk_size` entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let chunks: Vec<_> = map.into_chunks(2).collect();
+    /// assert_eq!(chunks.len(), 2);
+    /// assert_eq!(chunks[1].get_index(&"c").map(usize::from), Some(0));
+    /// ```
+    pub fn into_chunks(self, chunk_size: usize) -> impl Iterator<Item = Self>
+    where
+        S: Clone,
+    {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        let hash_builder = self.hash_builder;
+        let mut entries = self.entries.into_iter();
+        std::iter::from_fn(move || {
+            let mut chunk = Self::with_hasher(hash_builder.clone());
+            chunk.extend(entries.by_ref().take(chunk_size));
+            (!chunk.is_empty()).then_some(chunk)
+        })
+    }
+}
+
+impl<K, V, S> From<SeqMap<K, V, S>> for Vec<(K, V)> {
+    /// Converts the map into its key-value pairs in insertion order.
+    fn from(map: SeqMap<K, V, S>) -> Self {
+        map.entries.into_vec()
+    }
 }
 
-impl<K, V> Extend<(K, V)> for SeqMap<K, V>
+impl<K, V, S> Extend<(K, V)> for SeqMap<K, V, S>
 where
-    K: Eq + Hash + Clone,
+    K: Eq + Hash,
+    S: BuildHasher,
 {
     fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
         for (k, v) in iter {
@@ -499,9 +3714,44 @@ where
     }
 }
 
+impl<K, V, S, S2> Extend<SeqMap<K, V, S2>> for SeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Appends the entries of every map in turn, reserving capacity once for all of them.
+    /// Keys that are already present keep their entry, the same as with pairs.
+    fn extend<T: IntoIterator<Item = SeqMap<K, V, S2>>>(&mut self, iter: T) {
+        let maps: Vec<_> = iter.into_iter().collect();
+        let additional = maps.iter().map(|m| m.entries.len()).sum();
+        self.entries.reserve(additional);
+        self.key_index.reserve(additional);
+        for (k, v) in maps.into_iter().flat_map(|map| map.entries) {
+            let _ = self.insert(k, v);
+        }
+    }
+}
+
+impl<'a, K, V, S> Extend<(&'a K, &'a V)> for SeqMap<K, V, S>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    S: BuildHasher,
+{
+    /// Inserts clones of the pairs, skipping keys that are already present without cloning
+    /// them.
+    fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            if !self.contains_key(k) {
+                self.push_entry(k.clone(), v.clone());
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::SeqMap;
+    use crate::{DefaultHashBuilder, Generation, InvariantError, SeqMap, SMALL_MAP_LEN};
 
     #[test]
     fn test_clear_and_drain() {
@@ -518,7 +3768,22 @@ mod tests {
 
         other_map.clear();
         assert!(other_map.is_empty());
-        assert_eq!(other_map.key_to_index.len(), 0);
+        assert!(other_map.key_index.is_empty());
         assert_eq!(other_map.entries.len(), 0);
     }
+
+    #[test]
+    fn validate_finds_a_large_map_without_index() {
+        let entries = (0..=SMALL_MAP_LEN).map(|i| (i, i)).collect();
+        let map = SeqMap::from_entries(DefaultHashBuilder::default(), entries, Generation::new());
+        assert_eq!(
+            map.validate(),
+            Err(InvariantError::MissingIndex {
+                entries: SMALL_MAP_LEN + 1
+            })
+        );
+
+        let map: SeqMap<_, _> = (0..=SMALL_MAP_LEN).map(|i| (i, i)).collect();
+        assert_eq!(map.validate(), Ok(()));
+    }
 }
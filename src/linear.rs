@@ -0,0 +1,303 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    ops::{Index, IndexMut},
+};
+
+use crate::{map_index::Generation, SeqMapError, SeqMapIndex};
+
+/// An insertion ordered map for keys that are only [`Eq`], not [`Hash`](std::hash::Hash).
+///
+/// Keys are found by comparing them along the entries, so every lookup is linear in the
+/// size of the map. Apart from that it behaves like a [`SeqMap`](crate::SeqMap): iteration
+/// follows insertion order, inserting an existing key fails with
+/// `SeqMapError::KeyAlreadyExists`, and removing an entry keeps the order of the others.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::LinearSeqMap;
+/// #[derive(Debug, PartialEq)]
+/// struct Point(f32, f32);
+/// impl Eq for Point {}
+///
+/// let mut map = LinearSeqMap::new();
+/// map.insert(Point(1.0, 2.0), "a").unwrap();
+/// map.insert(Point(0.5, 0.0), "b").unwrap();
+/// assert!(map.insert(Point(1.0, 2.0), "c").is_err());
+/// assert_eq!(map[&Point(0.5, 0.0)], "b");
+/// ```
+#[derive(Clone)]
+pub struct LinearSeqMap<K, V> {
+    entries: Vec<(K, V)>,
+    // Changed whenever entries are removed, to catch stale `SeqMapIndex` values in debug builds
+    generation: Generation,
+}
+
+impl<K, V> LinearSeqMap<K, V>
+where
+    K: Eq,
+{
+    /// Creates a new, empty `LinearSeqMap`.
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            generation: Generation::new(),
+        }
+    }
+
+    /// Creates a new, empty `LinearSeqMap` with room for `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            generation: Generation::new(),
+        }
+    }
+
+    /// Inserts a key-value pair at the end of the insertion order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists`, holding the rejected key, if the key is already
+    /// present.
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), SeqMapError<K>> {
+        if self.contains_key(&key) {
+            return Err(SeqMapError::KeyAlreadyExists(key));
+        }
+        self.entries.push((key, value));
+        self.generation.draw_once();
+        Ok(())
+    }
+
+    /// Removes a key from the map, returning the value if it existed.
+    ///
+    /// The order of the remaining entries is kept.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let index = self.position_of(key)?;
+        self.generation.bump();
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Removes all elements from the map.
+    pub fn clear(&mut self) {
+        self.generation.bump();
+        self.entries.clear();
+    }
+
+    /// Retrieves a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.position_of(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.position_of(key)
+            .map(|index| &mut self.entries[index].1)
+    }
+
+    /// Returns the insertion index of the key.
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.position_of(key)
+            .map(|index| self.generation.index(index))
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.position_of(key).is_some()
+    }
+
+    /// Returns the key and value at `index` in insertion order.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `index` was taken before entries were removed, or was taken
+    /// from another map.
+    pub fn get_at(&self, index: SeqMapIndex<K, V>) -> Option<(&K, &V)> {
+        self.entries
+            .get(self.generation.position(index))
+            .map(|(k, v)| (k, v))
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the entries in insertion order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Returns an iterator over the entries in insertion order, with mutable values.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (&K, &mut V)> + ExactSizeIterator {
+        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    /// Returns an iterator over the keys in insertion order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Returns an iterator over the values in insertion order.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    /// Returns an iterator over mutable references to the values in insertion order.
+    pub fn values_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut V> + ExactSizeIterator {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+
+    fn position_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.entries.iter().position(|(k, _)| k.borrow() == key)
+    }
+
+    /// Keeps only the entries for which `pred` returns `true`, in their original order.
+    pub fn retain<F>(&mut self, mut pred: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.generation.bump();
+        self.entries.retain_mut(|(k, v)| pred(k, v));
+    }
+
+    /// Consumes the map and returns the key-value pairs in insertion order.
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.entries
+    }
+}
+
+impl<K, V> Default for LinearSeqMap<K, V>
+where
+    K: Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, Q> Index<&Q> for LinearSeqMap<K, V>
+where
+    K: Eq + Borrow<Q>,
+    Q: Eq + ?Sized,
+{
+    type Output = V;
+
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the map.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("Key not found in LinearSeqMap")
+    }
+}
+
+impl<K, V, Q> IndexMut<&Q> for LinearSeqMap<K, V>
+where
+    K: Eq + Borrow<Q>,
+    Q: Eq + ?Sized,
+{
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the map.
+    fn index_mut(&mut self, key: &Q) -> &mut V {
+        self.get_mut(key).expect("Key not found in LinearSeqMap")
+    }
+}
+
+/// Creates a `LinearSeqMap` from key-value pairs, keeping the first occurrence of a duplicated
+/// key.
+impl<K, V> FromIterator<(K, V)> for LinearSeqMap<K, V>
+where
+    K: Eq,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V> Extend<(K, V)> for LinearSeqMap<K, V>
+where
+    K: Eq,
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            let _ = self.insert(k, v);
+        }
+    }
+}
+
+impl<K, V> IntoIterator for LinearSeqMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<K, V> PartialEq for LinearSeqMap<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<K, V> Eq for LinearSeqMap<K, V>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V> Debug for LinearSeqMap<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "LinearSeqMap ")?;
+        f.debug_map()
+            .entries(self.entries.iter().map(|(k, v)| (k, v)))
+            .finish()
+    }
+}
@@ -0,0 +1,197 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    cmp::Ordering,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+#[cfg(debug_assertions)]
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+
+/// The position of an entry in a `SeqMap<K, V>`, typed by the key and value types of the map.
+///
+/// Returned by [`SeqMap::get_index`](crate::SeqMap::get_index),
+/// [`SeqMap::insert_full`](crate::SeqMap::insert_full),
+/// [`SeqMap::get_full`](crate::SeqMap::get_full) and searches like
+/// [`SeqMap::find`](crate::SeqMap::find), and accepted by
+/// [`SeqMap::get_at`](crate::SeqMap::get_at), and the same way by the other maps of this
+/// crate. An index into a map of players does not fit a map of items, so handing it to the
+/// wrong map does not compile. Maps with the same key and value types share the index type.
+///
+/// The position is only valid until entries are removed or the map is reordered. In debug
+/// builds, an index returned by the map remembers the generation of the map, and using it
+/// after such a change panics instead of silently reaching another entry. Using an index with
+/// another map of the same types panics as well, except with a clone of the map it was taken
+/// from: a clone shares the generation of its original until either of them moves or removes
+/// entries, and accepts its indexes until then.
+///
+/// # Examples
+///
+/// ```compile_fail
+/// use seq_map::SeqMap;
+/// let mut players: SeqMap<&str, u32> = SeqMap::new();
+/// let items: SeqMap<&str, String> = SeqMap::new();
+/// let index = players.insert_full("ann", 1).unwrap();
+/// items.get_at(index);
+/// ```
+pub struct SeqMapIndex<K, V> {
+    index: usize,
+    // The generation of the map when the index was taken, if known
+    #[cfg(debug_assertions)]
+    generation: Option<u64>,
+    map: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V> SeqMapIndex<K, V> {
+    /// Wraps a raw position, for example one that was stored as a plain number.
+    ///
+    /// Such an index is never checked for being stale.
+    pub const fn new(index: usize) -> Self {
+        Self {
+            index,
+            #[cfg(debug_assertions)]
+            generation: None,
+            map: PhantomData,
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    pub(crate) const fn with_generation(index: usize, generation: u64) -> Self {
+        Self {
+            index,
+            generation: Some(generation),
+            map: PhantomData,
+        }
+    }
+
+    /// Returns the raw position.
+    pub const fn get(self) -> usize {
+        self.index
+    }
+}
+
+impl<K, V> From<SeqMapIndex<K, V>> for usize {
+    fn from(index: SeqMapIndex<K, V>) -> Self {
+        index.index
+    }
+}
+
+impl<K, V> Clone for SeqMapIndex<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for SeqMapIndex<K, V> {}
+
+impl<K, V> PartialEq for SeqMapIndex<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<K, V> Eq for SeqMapIndex<K, V> {}
+
+impl<K, V> PartialOrd for SeqMapIndex<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K, V> Ord for SeqMapIndex<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl<K, V> Hash for SeqMapIndex<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<K, V> Debug for SeqMapIndex<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "SeqMapIndex({})", self.index)
+    }
+}
+
+/// The generation of a map, which changes whenever its entries move or are removed. Only kept
+/// in debug builds, and empty in release builds.
+///
+/// Generations are drawn from one counter shared by all maps, so no two maps ever hand out
+/// indexes of the same generation. A clone shares the generation of its original until either
+/// of them changes, which is fine since their positions agree until then.
+#[derive(Clone, Copy)]
+pub(crate) struct Generation {
+    #[cfg(debug_assertions)]
+    generation: u64,
+}
+
+impl Generation {
+    /// The generation of a map that never held an entry, so it never handed out an index.
+    #[cfg(debug_assertions)]
+    const UNDRAWN: u64 = 0;
+
+    pub(crate) const fn new() -> Self {
+        Self {
+            #[cfg(debug_assertions)]
+            generation: Self::UNDRAWN,
+        }
+    }
+
+    /// Draws a generation for a map that gets its first entry.
+    pub(crate) fn draw_once(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.generation == Self::UNDRAWN {
+            self.bump();
+        }
+    }
+
+    /// Makes every index taken so far stale.
+    pub(crate) fn bump(&mut self) {
+        #[cfg(debug_assertions)]
+        {
+            static NEXT: AtomicU64 = AtomicU64::new(Generation::UNDRAWN + 1);
+            self.generation = NEXT.fetch_add(1, AtomicOrdering::Relaxed);
+        }
+    }
+
+    /// Wraps `position` in a [`SeqMapIndex`] that, in debug builds, remembers this
+    /// generation.
+    pub(crate) fn index<K, V>(self, position: usize) -> SeqMapIndex<K, V> {
+        #[cfg(debug_assertions)]
+        {
+            debug_assert!(
+                self.generation != Self::UNDRAWN,
+                "a map with entries has a generation"
+            );
+            SeqMapIndex::with_generation(position, self.generation)
+        }
+        #[cfg(not(debug_assertions))]
+        SeqMapIndex::new(position)
+    }
+
+    /// Returns the raw position of `index`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `index` was taken at another generation, so before entries
+    /// were moved or removed, or from another map.
+    pub(crate) fn position<K, V>(self, index: SeqMapIndex<K, V>) -> usize {
+        #[cfg(debug_assertions)]
+        if let Some(generation) = index.generation {
+            assert!(
+                generation == self.generation,
+                "stale SeqMapIndex {}: it is from another map, or entries were moved or removed \
+                 since it was taken",
+                index.index
+            );
+        }
+        index.index
+    }
+}
@@ -0,0 +1,73 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::mem::size_of;
+
+use crate::SeqMap;
+
+/// How many bytes a `SeqMap` uses, as returned by [`SeqMap::memory_usage`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// Size of the `SeqMap` value itself, wherever it is stored.
+    pub inline: usize,
+    /// Heap bytes allocated for the entries, including spare capacity.
+    pub entries: usize,
+    /// The part of `entries` reserved for entries that are not inserted yet.
+    pub entries_spare: usize,
+    /// Heap bytes allocated for the key to index table and the cached key hashes.
+    pub index: usize,
+    /// Heap bytes owned by the keys and values themselves, as told by the callback given to
+    /// [`SeqMap::memory_usage_with`]. Zero for [`SeqMap::memory_usage`].
+    pub deep: usize,
+}
+
+impl MemoryReport {
+    /// Returns the sum of all parts.
+    pub fn total(&self) -> usize {
+        self.inline + self.entries + self.index + self.deep
+    }
+}
+
+impl<K, V, S> SeqMap<K, V, S> {
+    /// Reports the memory used by the map, not counting heap memory owned by keys and values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map = SeqMap::new();
+    /// map.insert(1u64, 2u64).unwrap();
+    /// let report = map.memory_usage();
+    /// assert!(report.entries >= 16);
+    /// assert!(report.total() > report.entries);
+    /// ```
+    pub fn memory_usage(&self) -> MemoryReport {
+        self.memory_usage_with(|_, _| 0)
+    }
+
+    /// Reports the memory used by the map, with `deep_size` giving the heap bytes owned by
+    /// each key and value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a".to_string(), vec![0u8; 100])]);
+    /// let report = map.memory_usage_with(|k, v| k.capacity() + v.capacity());
+    /// assert_eq!(report.deep, 101);
+    /// ```
+    pub fn memory_usage_with<F>(&self, mut deep_size: F) -> MemoryReport
+    where
+        F: FnMut(&K, &V) -> usize,
+    {
+        let entry_size = size_of::<(K, V)>();
+        MemoryReport {
+            inline: size_of::<Self>(),
+            entries: self.entries.capacity() * entry_size,
+            entries_spare: (self.entries.capacity() - self.entries.len()) * entry_size,
+            index: self.key_index.allocation_size(),
+            deep: self.entries.iter().map(|(k, v)| deep_size(k, v)).sum(),
+        }
+    }
+}
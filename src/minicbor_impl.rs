@@ -0,0 +1,71 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::hash::{BuildHasher, Hash};
+
+use minicbor::{
+    decode::{self, Decoder},
+    encode::{self, Encoder, Write},
+    CborLen, Decode, Encode,
+};
+
+use crate::SeqMap;
+
+impl<C, K, V, S> Encode<C> for SeqMap<K, V, S>
+where
+    K: Encode<C>,
+    V: Encode<C>,
+{
+    /// Encodes as a definite-length CBOR map, with the entries in insertion order.
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.map(self.entries.len() as u64)?;
+        for (key, value) in &self.entries {
+            key.encode(e, ctx)?;
+            value.encode(e, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C, K, V, S> CborLen<C> for SeqMap<K, V, S>
+where
+    K: CborLen<C>,
+    V: CborLen<C>,
+{
+    fn cbor_len(&self, ctx: &mut C) -> usize {
+        self.entries.len().cbor_len(ctx)
+            + self
+                .entries
+                .iter()
+                .map(|(key, value)| key.cbor_len(ctx) + value.cbor_len(ctx))
+                .sum::<usize>()
+    }
+}
+
+impl<'b, C, K, V, S> Decode<'b, C> for SeqMap<K, V, S>
+where
+    K: Decode<'b, C> + Eq + Hash,
+    V: Decode<'b, C>,
+    S: BuildHasher + Default,
+{
+    /// Decodes a definite or indefinite-length CBOR map, keeping the order of the input.
+    ///
+    /// A key that occurs more than once is rejected, since maps with duplicate keys are not
+    /// valid CBOR.
+    fn decode(d: &mut Decoder<'b>, ctx: &mut C) -> Result<Self, decode::Error> {
+        let start = d.position();
+        let mut map = SeqMap::default();
+        for entry in d.map_iter_with::<C, K, V>(ctx)? {
+            let (key, value) = entry?;
+            if map.insert(key, value).is_err() {
+                return Err(decode::Error::message("duplicate key in SeqMap").at(start));
+            }
+        }
+        Ok(map)
+    }
+}
@@ -0,0 +1,46 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::hash::Hash;
+
+use proptest::{
+    collection::{vec, SizeRange},
+    strategy::Strategy,
+};
+
+use crate::SeqMap;
+
+/// Creates a proptest strategy generating a `SeqMap` with a length within `size`.
+///
+/// Pairs are generated in order and collected like [`FromIterator`] does, so a key that comes
+/// up again keeps its first value. Shrinking removes and simplifies whole entries, and every
+/// shrunk value is again a valid map. Maps that end up with fewer unique keys than the lower
+/// bound of `size` are rejected, which can make generation slow for small key domains.
+///
+/// # Examples
+///
+/// ```
+/// use proptest::prelude::*;
+/// use seq_map::seq_map;
+///
+/// proptest!(|(map in seq_map(0u8..10, any::<bool>(), 1..5))| {
+///     prop_assert!(!map.is_empty() && map.len() < 5);
+/// });
+/// ```
+pub fn seq_map<K, V>(
+    key: K,
+    value: V,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = SeqMap<K::Value, V::Value>>
+where
+    K: Strategy,
+    K::Value: Eq + Hash + Clone,
+    V: Strategy,
+{
+    let size = size.into();
+    let min = size.start();
+    vec((key, value), size)
+        .prop_map(|pairs| pairs.into_iter().collect::<SeqMap<_, _>>())
+        .prop_filter("too few unique keys", move |map| map.len() >= min)
+}
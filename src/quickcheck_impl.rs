@@ -0,0 +1,32 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::hash::{BuildHasher, Hash};
+
+use quickcheck::{Arbitrary, Gen};
+
+use crate::SeqMap;
+
+/// Generates and shrinks the entries as a list of pairs in insertion order, collected like
+/// [`FromIterator`] does. Every shrunk value is a valid map, with unique keys in the order
+/// they were generated.
+impl<K, V, S> Arbitrary for SeqMap<K, V, S>
+where
+    K: Arbitrary + Eq + Hash,
+    V: Arbitrary,
+    S: BuildHasher + Default + Clone + Send + 'static,
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        Vec::<(K, V)>::arbitrary(g).into_iter().collect()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(
+            self.entries
+                .to_vec()
+                .shrink()
+                .map(|entries| entries.into_iter().collect()),
+        )
+    }
+}
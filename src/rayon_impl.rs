@@ -0,0 +1,133 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    cmp::Ordering,
+    hash::{BuildHasher, Hash},
+};
+
+use rayon::prelude::*;
+
+use crate::SeqMap;
+
+impl<K, V, S> SeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns a parallel iterator over the entries, that yields them in insertion order when
+    /// collected.
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = (&K, &V)>
+    where
+        K: Sync,
+        V: Sync,
+    {
+        self.entries.par_iter().map(|(k, v)| (k, v))
+    }
+
+    /// Returns a parallel iterator over the entries, with mutable references to the values.
+    pub fn par_iter_mut(&mut self) -> impl IndexedParallelIterator<Item = (&K, &mut V)>
+    where
+        K: Send + Sync,
+        V: Send,
+    {
+        self.entries.par_iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    /// Sorts the entries with `compare`, on the rayon thread pool.
+    ///
+    /// The sort is stable, and the result is the same as sorting on one thread. Like the
+    /// other sorts, it moves the cached hashes along and hashes no key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map: SeqMap<_, _> = (0..100).map(|i| (i, i % 7)).collect();
+    /// map.par_sort_by(|_, a, _, b| a.cmp(b));
+    /// assert_eq!(map.iter().next(), Some((&0, &0)));
+    /// assert_eq!(map.get_index(&7).map(usize::from), Some(1));
+    /// ```
+    pub fn par_sort_by<F>(&mut self, compare: F)
+    where
+        K: Send + Sync,
+        V: Send + Sync,
+        F: Fn(&K, &V, &K, &V) -> Ordering + Sync,
+    {
+        self.invalidate_positions();
+        if self.key_index.is_empty() {
+            self.entries
+                .par_sort_by(|(ka, va), (kb, vb)| compare(ka, va, kb, vb));
+        } else {
+            let entries = &self.entries;
+            let mut order: Vec<usize> = (0..entries.len()).collect();
+            order.par_sort_by(|&a, &b| {
+                let ((ka, va), (kb, vb)) = (&entries[a], &entries[b]);
+                compare(ka, va, kb, vb)
+            });
+            self.permute(&order);
+        }
+        self.check_invariants();
+        #[cfg(feature = "tracing")]
+        self.trace_reorder("par_sort_by");
+    }
+
+    /// Sorts the entries by their keys, on the rayon thread pool.
+    pub fn par_sort_keys(&mut self)
+    where
+        K: Ord + Send + Sync,
+        V: Send + Sync,
+    {
+        self.par_sort_by(|a, _, b, _| a.cmp(b));
+    }
+}
+
+impl<K, V, S> ParallelExtend<(K, V)> for SeqMap<K, V, S>
+where
+    K: Eq + Hash + Send,
+    V: Send,
+    S: BuildHasher + Sync,
+{
+    /// Hashes the keys on the rayon thread pool, then appends the pairs in the order of
+    /// `par_iter` on the calling thread, so the result is the same as with [`Extend`]. Pairs
+    /// whose key is already present are skipped.
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = (K, V)>,
+    {
+        let hash_builder = &self.hash_builder;
+        let pairs: Vec<(u64, K, V)> = par_iter
+            .into_par_iter()
+            .map(|(key, value)| (hash_builder.hash_one(&key), key, value))
+            .collect();
+        self.entries.reserve(pairs.len());
+        self.key_index.reserve(pairs.len());
+        for (hash, key, value) in pairs {
+            if self.key_index.is_empty() {
+                if self.index_of(&key).is_none() {
+                    self.push_hashed_entry(None, key, value);
+                }
+            } else if self.find_hashed(hash, &key).is_none() {
+                self.push_hashed_entry(Some(hash), key, value);
+            }
+        }
+    }
+}
+
+impl<K, V, S> FromParallelIterator<(K, V)> for SeqMap<K, V, S>
+where
+    K: Eq + Hash + Send,
+    V: Send,
+    S: BuildHasher + Default + Sync,
+{
+    /// Collects the pairs like [`par_extend`](ParallelExtend::par_extend) into an empty map.
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = (K, V)>,
+    {
+        let mut map = Self::default();
+        map.par_extend(par_iter);
+        map
+    }
+}
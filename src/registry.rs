@@ -0,0 +1,256 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
+
+use crate::{SeqMap, SeqMapError};
+
+#[cfg(debug_assertions)]
+fn next_registry_tag() -> u64 {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A typed handle to a value in a [`Registry`], returned by [`Registry::register`].
+///
+/// An `Id<V>` only fits registries of `V`, so handles to different kinds of values cannot be
+/// mixed up. In debug builds, it also remembers which registry it came from, and using it
+/// with another registry panics.
+pub struct Id<V> {
+    index: usize,
+    #[cfg(debug_assertions)]
+    registry: u64,
+    value: PhantomData<fn() -> V>,
+}
+
+impl<V> Id<V> {
+    /// Returns the registration index this handle refers to.
+    pub const fn index(self) -> usize {
+        self.index
+    }
+}
+
+impl<V> Clone for Id<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for Id<V> {}
+
+impl<V> PartialEq for Id<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<V> Eq for Id<V> {}
+
+impl<V> Hash for Id<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<V> Debug for Id<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Id({})", self.index)
+    }
+}
+
+/// An insertion ordered map that hands out an [`Id`] for every registered value.
+///
+/// Entries are never removed, so an `Id` stays valid for the lifetime of the registry and
+/// looking up a value by it is plain indexing. Keys are resolved to ids with
+/// [`id`](Self::id), typically once at load time.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::Registry;
+/// let mut shaders = Registry::new();
+/// let basic = shaders.register("basic", 1).unwrap();
+/// let shadow = shaders.register("shadow", 2).unwrap();
+/// assert_eq!(shaders[shadow], 2);
+/// assert_eq!(shaders.id(&"basic"), Some(basic));
+/// ```
+pub struct Registry<K, V> {
+    map: SeqMap<K, V>,
+    #[cfg(debug_assertions)]
+    tag: u64,
+}
+
+impl<K, V> Registry<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new, empty `Registry`.
+    pub fn new() -> Self {
+        Self {
+            map: SeqMap::new(),
+            #[cfg(debug_assertions)]
+            tag: next_registry_tag(),
+        }
+    }
+
+    /// Adds a value under `key` and returns its id.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already registered.
+    pub fn register(&mut self, key: K, value: V) -> Result<Id<V>, SeqMapError<K>> {
+        let index = self.map.len();
+        self.map.insert(key, value)?;
+        Ok(self.id_at(index))
+    }
+
+    /// Returns the id of the value registered under `key`.
+    pub fn id<Q>(&self, key: &Q) -> Option<Id<V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.index_of(key).map(|index| self.id_at(index))
+    }
+
+    /// Returns the value registered under `key`.
+    pub fn get_by_key<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get(key)
+    }
+
+    /// Returns the value for `id`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `id` was handed out by another registry.
+    pub fn get(&self, id: Id<V>) -> &V {
+        self.check(id);
+        &self.map.entries[id.index].1
+    }
+
+    /// Returns a mutable reference to the value for `id`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `id` was handed out by another registry.
+    pub fn get_mut(&mut self, id: Id<V>) -> &mut V {
+        self.check(id);
+        &mut self.map.entries[id.index].1
+    }
+
+    /// Returns the key for `id`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `id` was handed out by another registry.
+    pub fn key(&self, id: Id<V>) -> &K {
+        self.check(id);
+        &self.map.entries[id.index].0
+    }
+
+    /// Checks if a value is registered under `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Returns the number of registered values.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if nothing has been registered.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator over the ids, keys and values in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = (Id<V>, &K, &V)> {
+        self.map
+            .iter()
+            .enumerate()
+            .map(|(index, (key, value))| (self.id_at(index), key, value))
+    }
+
+    /// Returns the underlying map, in registration order.
+    pub fn as_seq_map(&self) -> &SeqMap<K, V> {
+        &self.map
+    }
+
+    /// Consumes the registry and returns the underlying map.
+    pub fn into_seq_map(self) -> SeqMap<K, V> {
+        self.map
+    }
+
+    fn id_at(&self, index: usize) -> Id<V> {
+        Id {
+            index,
+            #[cfg(debug_assertions)]
+            registry: self.tag,
+            value: PhantomData,
+        }
+    }
+
+    fn check(&self, _id: Id<V>) {
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            _id.registry, self.tag,
+            "Id used with a Registry it does not belong to"
+        );
+    }
+}
+
+impl<K, V> Default for Registry<K, V>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Index<Id<V>> for Registry<K, V>
+where
+    K: Eq + Hash,
+{
+    type Output = V;
+
+    fn index(&self, id: Id<V>) -> &V {
+        self.get(id)
+    }
+}
+
+impl<K, V> IndexMut<Id<V>> for Registry<K, V>
+where
+    K: Eq + Hash,
+{
+    fn index_mut(&mut self, id: Id<V>) -> &mut V {
+        self.get_mut(id)
+    }
+}
+
+impl<K, V> Debug for Registry<K, V>
+where
+    K: Eq + Hash + Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Registry ")?;
+        f.debug_map().entries(self.map.iter()).finish()
+    }
+}
@@ -0,0 +1,316 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+};
+
+use crate::{SeqMap, SeqMapError, SeqMapIndex};
+
+/// A [`SeqMap`] that can save its state at simulation ticks and roll back to any of them.
+///
+/// Only the changes are kept: every change made after a save logs what it replaced, and
+/// restoring a tick takes the logged changes back, newest first. Saving costs nothing, and a
+/// change costs one clone of the value it changes or removes, however large the map. Nothing
+/// is logged while no state is saved. At most `history` states are kept, dropping the oldest
+/// first, together with the changes only they needed.
+///
+/// [`map_mut`](Self::map_mut) hands out the whole map, so it logs a clone of all of it.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::RollbackSeqMap;
+/// let mut positions = RollbackSeqMap::new(8);
+/// positions.insert("ship", 0).unwrap();
+/// positions.save_state(1);
+/// *positions.get_mut(&"ship").unwrap() = 5;
+/// assert!(positions.restore_state(1));
+/// assert_eq!(positions.get(&"ship"), Some(&0));
+/// ```
+pub struct RollbackSeqMap<K, V> {
+    current: SeqMap<K, V>,
+    // The changes since the oldest saved state, oldest first
+    undo_log: VecDeque<Undo<K, V>>,
+    // The number of changes dropped from the front of `undo_log`
+    dropped: usize,
+    // The saved ticks, with the number of changes logged, dropped ones included, at the save
+    saved: VecDeque<(u64, usize)>,
+    history: usize,
+}
+
+/// A logged change, holding what it replaced.
+#[derive(Clone)]
+enum Undo<K, V> {
+    /// The value at the position was changed
+    Changed(usize, V),
+    /// An entry was appended
+    Inserted,
+    /// The entry was removed from the position
+    Removed(usize, K, V),
+    /// The map was handed out by `map_mut`
+    Replaced(SeqMap<K, V>),
+}
+
+impl<K, V> Undo<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Takes the change back. All changes logged after it must have been taken back first,
+    /// so the positions are the ones at the time of the change.
+    fn take_back(self, map: &mut SeqMap<K, V>) {
+        match self {
+            Self::Changed(index, value) => {
+                let (_, slot) = map
+                    .get_at_mut(SeqMapIndex::new(index))
+                    .expect("changed entry is present");
+                *slot = value;
+            }
+            Self::Inserted => {
+                map.pop_last();
+            }
+            Self::Removed(index, key, value) => map.insert_at(index, key, value),
+            Self::Replaced(previous) => *map = previous,
+        }
+    }
+}
+
+impl<K, V> RollbackSeqMap<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new, empty map that keeps up to `history` saved states.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `history` is zero.
+    pub fn new(history: usize) -> Self {
+        Self::from_map(SeqMap::new(), history)
+    }
+
+    /// Creates a map starting out as `map`, that keeps up to `history` saved states.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `history` is zero.
+    pub fn from_map(map: SeqMap<K, V>, history: usize) -> Self {
+        assert!(history > 0, "RollbackSeqMap must keep at least one state");
+        Self {
+            current: map,
+            undo_log: VecDeque::new(),
+            dropped: 0,
+            saved: VecDeque::with_capacity(history),
+            history,
+        }
+    }
+
+    /// Saves the current state under `tick`.
+    ///
+    /// States saved under `tick` or later ticks are dropped first, so the saved ticks always
+    /// increase. If the history is full, the oldest state is dropped.
+    pub fn save_state(&mut self, tick: u64) {
+        self.discard_from(tick);
+        if self.saved.len() == self.history {
+            self.saved.pop_front();
+        }
+        self.saved.push_back((tick, self.logged()));
+        self.trim_log();
+    }
+
+    /// Restores the state saved under `tick`, and returns `false` if there is none.
+    ///
+    /// The states saved after `tick` are dropped, since the simulation is replayed from there.
+    /// The state for `tick` itself is kept, so it can be restored again.
+    pub fn restore_state(&mut self, tick: u64) -> bool {
+        let Ok(found) = self.saved.binary_search_by_key(&tick, |(t, _)| *t) else {
+            return false;
+        };
+        self.saved.truncate(found + 1);
+        let (_, logged) = self.saved[found];
+        while self.logged() > logged {
+            let undo = self.undo_log.pop_back().expect("saved changes are logged");
+            undo.take_back(&mut self.current);
+        }
+        true
+    }
+
+    /// Drops all saved states from `tick` on.
+    pub fn discard_from(&mut self, tick: u64) {
+        let keep = self.saved.partition_point(|(t, _)| *t < tick);
+        self.saved.truncate(keep);
+        self.trim_log();
+    }
+
+    /// Drops all saved states before `tick`, typically once that tick is confirmed.
+    pub fn discard_before(&mut self, tick: u64) {
+        let drop = self.saved.partition_point(|(t, _)| *t < tick);
+        self.saved.drain(..drop);
+        self.trim_log();
+    }
+
+    /// Returns the saved ticks, oldest first.
+    pub fn saved_ticks(&self) -> impl DoubleEndedIterator<Item = u64> + '_ {
+        self.saved.iter().map(|(tick, _)| *tick)
+    }
+
+    /// Returns the state saved under `tick`, rebuilt from a clone of the current state.
+    pub fn saved_state(&self, tick: u64) -> Option<SeqMap<K, V>>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let found = self.saved.binary_search_by_key(&tick, |(t, _)| *t).ok()?;
+        let (_, logged) = self.saved[found];
+        let mut map = self.current.clone();
+        for undo in self.undo_log.iter().skip(logged - self.dropped).rev() {
+            undo.clone().take_back(&mut map);
+        }
+        Some(map)
+    }
+
+    /// Returns the current state.
+    pub fn map(&self) -> &SeqMap<K, V> {
+        &self.current
+    }
+
+    /// Returns the current state for changing it.
+    ///
+    /// The changes made through it are not known, so if a state is saved, the whole map is
+    /// cloned into the log first. This happens at most once per saved tick.
+    pub fn map_mut(&mut self) -> &mut SeqMap<K, V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        if self.is_logging() {
+            self.undo_log
+                .push_back(Undo::Replaced(self.current.clone()));
+        }
+        &mut self.current
+    }
+
+    /// Retrieves a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.current.get(key)
+    }
+
+    /// Retrieves a mutable reference to the value corresponding to the key.
+    ///
+    /// If a state is saved, the value is cloned into the log first.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        V: Clone,
+    {
+        let index = self.current.get_index(key)?;
+        if self.is_logging() {
+            let (_, value) = self.current.get_at(index)?;
+            let undo = Undo::Changed(index.get(), value.clone());
+            self.undo_log.push_back(undo);
+        }
+        self.current.get_at_mut(index).map(|(_, value)| value)
+    }
+
+    /// Inserts a key-value pair at the end of the insertion order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), SeqMapError<K>> {
+        self.current.insert(key, value)?;
+        if self.is_logging() {
+            self.undo_log.push_back(Undo::Inserted);
+        }
+        Ok(())
+    }
+
+    /// Removes a key from the map, returning the value if it existed.
+    ///
+    /// If a state is saved, the removed entry is logged with a clone of the value.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        V: Clone,
+    {
+        let (index, key, value) = self.current.remove_full(key)?;
+        if self.is_logging() {
+            self.undo_log
+                .push_back(Undo::Removed(index, key, value.clone()));
+        }
+        Some(value)
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.current.contains_key(key)
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.current.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.current.is_empty()
+    }
+
+    /// Consumes the rollback map and returns its current state.
+    pub fn into_inner(self) -> SeqMap<K, V> {
+        self.current
+    }
+
+    /// Returns the number of changes logged so far, dropped ones included.
+    fn logged(&self) -> usize {
+        self.dropped + self.undo_log.len()
+    }
+
+    /// Returns `true` if changes need to be logged: a state is saved, and the whole map was
+    /// not already logged since the last save.
+    fn is_logging(&self) -> bool {
+        match self.saved.back() {
+            None => false,
+            Some(&(_, logged)) => {
+                logged == self.logged() || !matches!(self.undo_log.back(), Some(Undo::Replaced(_)))
+            }
+        }
+    }
+
+    /// Drops the changes logged before the oldest saved state, as no state before it can be
+    /// restored.
+    fn trim_log(&mut self) {
+        let oldest = self
+            .saved
+            .front()
+            .map_or_else(|| self.logged(), |&(_, logged)| logged);
+        self.undo_log.drain(..oldest - self.dropped);
+        self.dropped = oldest;
+    }
+}
+
+impl<K, V> Debug for RollbackSeqMap<K, V>
+where
+    K: Eq + Hash + Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RollbackSeqMap")
+            .field("current", &self.current)
+            .field("saved_ticks", &self.saved_ticks().collect::<Vec<_>>())
+            .finish()
+    }
+}
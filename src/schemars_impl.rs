@@ -0,0 +1,33 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{borrow::Cow, collections::BTreeMap};
+
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+use crate::SeqMap;
+
+/// Describes the map representation used for human readable formats, which is the same
+/// object schema as for the standard maps, with `additionalProperties` describing `V`.
+impl<K, V, S> JsonSchema for SeqMap<K, V, S>
+where
+    K: JsonSchema,
+    V: JsonSchema,
+{
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        <BTreeMap<K, V>>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        <BTreeMap<K, V>>::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        <BTreeMap<K, V>>::json_schema(generator)
+    }
+}
@@ -0,0 +1,284 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    fmt,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+};
+
+use serde::{
+    de::{Error as _, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{DefaultHashBuilder, DuplicatePolicy, SeqMap};
+
+impl<K, V, H> Serialize for SeqMap<K, V, H>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    /// Serializes the entries in insertion order.
+    ///
+    /// Human readable formats get a map. Binary formats get a sequence of `(key, value)`
+    /// tuples instead, which is more compact and works for formats that only allow string map
+    /// keys.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_entries(self.entries.iter(), serializer)
+    }
+}
+
+impl<'de, K, V, H> Deserialize<'de> for SeqMap<K, V, H>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    H: BuildHasher + Default,
+{
+    /// Deserializes from a map for human readable formats, or from a sequence of
+    /// `(key, value)` tuples for binary formats, keeping the order of the input. A key that
+    /// occurs more than once is an error, see [`duplicates`] for other choices.
+    ///
+    /// Keys and values are deserialized as they come, so borrowed types such as `&'de str`
+    /// point straight into the input buffer and no key is allocated. Note that serde always
+    /// deserializes `Cow<str>` as owned, so use `&str` where zero-copy matters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let input = String::from(r#"{"textures/grass.png": "a1f3", "models/tree.glb": "9bc0"}"#);
+    /// let manifest: SeqMap<&str, &str> = serde_json::from_str(&input).unwrap();
+    /// assert_eq!(manifest[&"models/tree.glb"], "9bc0");
+    /// ```
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_with_policy(deserializer, DuplicatePolicy::Error)
+    }
+}
+
+fn deserialize_with_policy<'de, K, V, H, D>(
+    deserializer: D,
+    policy: DuplicatePolicy<V>,
+) -> Result<SeqMap<K, V, H>, D::Error>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    H: BuildHasher + Default,
+    D: Deserializer<'de>,
+{
+    let visitor = SeqMapVisitor {
+        policy,
+        marker: PhantomData,
+    };
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_map(visitor)
+    } else {
+        deserializer.deserialize_seq(visitor)
+    }
+}
+
+struct SeqMapVisitor<K, V, H> {
+    policy: DuplicatePolicy<V>,
+    marker: PhantomData<(K, H)>,
+}
+
+impl<'de, K, V, H> Visitor<'de> for SeqMapVisitor<K, V, H>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    H: BuildHasher + Default,
+{
+    type Value = SeqMap<K, V, H>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map or a sequence of key-value pairs")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = SeqMap::default();
+        let mut index = 0;
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert_with_policy(index, key, value, self.policy)
+                .map_err(|err| A::Error::custom(duplicate_message(err.index)))?;
+            index += 1;
+        }
+        Ok(map)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = SeqMap::default();
+        let mut index = 0;
+        while let Some((key, value)) = access.next_element()? {
+            map.insert_with_policy(index, key, value, self.policy)
+                .map_err(|err| A::Error::custom(duplicate_message(err.index)))?;
+            index += 1;
+        }
+        Ok(map)
+    }
+}
+
+fn duplicate_message(index: usize) -> String {
+    format!("duplicate key in SeqMap at entry {index}")
+}
+
+macro_rules! duplicates_module {
+    ($(#[$doc:meta])* $name:ident => $policy:ident, $deserialize_doc:literal, $errors_doc:literal) => {
+        $(#[$doc])*
+        pub mod $name {
+            use std::hash::{BuildHasher, Hash};
+
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            use crate::{DuplicatePolicy, SeqMap};
+
+            /// Serializes the map like `SeqMap` does.
+            ///
+            /// # Errors
+            ///
+            /// Returns the errors of the serializer.
+            pub fn serialize<K, V, H, S>(
+                map: &SeqMap<K, V, H>,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error>
+            where
+                K: Serialize,
+                V: Serialize,
+                S: Serializer,
+            {
+                map.serialize(serializer)
+            }
+
+            #[doc = $deserialize_doc]
+            ///
+            /// # Errors
+            ///
+            #[doc = $errors_doc]
+            pub fn deserialize<'de, K, V, H, D>(
+                deserializer: D,
+            ) -> Result<SeqMap<K, V, H>, D::Error>
+            where
+                K: Deserialize<'de> + Eq + Hash,
+                V: Deserialize<'de>,
+                H: BuildHasher + Default,
+                D: Deserializer<'de>,
+            {
+                crate::serde_impl::deserialize_with_policy(deserializer, DuplicatePolicy::$policy)
+            }
+        }
+    };
+}
+
+/// Modules for `#[serde(with = "...")]` that choose what deserialization does with duplicate
+/// keys. Serialization is the same as for `SeqMap` itself.
+///
+/// Whatever the module, a key keeps the position of its first occurrence.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::SeqMap;
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     #[serde(with = "seq_map::duplicates::last_wins")]
+///     env: SeqMap<String, String>,
+/// }
+/// let config: Config =
+///     serde_json::from_str(r#"{"env": {"A": "1", "B": "2", "A": "3"}}"#).unwrap();
+/// assert_eq!(config.env.values().collect::<Vec<_>>(), vec!["3", "2"]);
+/// ```
+pub mod duplicates {
+    duplicates_module! {
+        /// Duplicate keys are an error. This is what `SeqMap` does without a `with` attribute.
+        reject => Error,
+        "Deserializes the map, failing on the first duplicate key.",
+        "Returns the errors of the deserializer, and an error for a duplicate key."
+    }
+
+    duplicates_module! {
+        /// The first value of a duplicated key is kept and later ones are ignored.
+        first_wins => FirstWins,
+        "Deserializes the map, keeping the first value of every key.",
+        "Returns the errors of the deserializer."
+    }
+
+    duplicates_module! {
+        /// The last value of a duplicated key replaces the earlier ones.
+        last_wins => LastWins,
+        "Deserializes the map, keeping the last value of every key.",
+        "Returns the errors of the deserializer."
+    }
+}
+
+/// Serializes a `SeqMap` with its entries sorted by key instead of in insertion order.
+///
+/// Two maps with the same contents produce the same output, whatever order the keys were
+/// inserted in, which makes it suitable for output that is hashed or signed. It deserializes
+/// back into a plain `SeqMap` in the sorted order.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::{CanonicalSeqMap, SeqMap};
+/// let a = SeqMap::from([("b", 2), ("a", 1)]);
+/// let b = SeqMap::from([("a", 1), ("b", 2)]);
+/// assert_eq!(
+///     serde_json::to_string(&CanonicalSeqMap(&a)).unwrap(),
+///     serde_json::to_string(&CanonicalSeqMap(&b)).unwrap()
+/// );
+/// ```
+pub struct CanonicalSeqMap<'a, K, V, H = DefaultHashBuilder>(pub &'a SeqMap<K, V, H>);
+
+impl<K, V, H> Serialize for CanonicalSeqMap<'_, K, V, H>
+where
+    K: Serialize + Ord,
+    V: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_sorted(self.0, serializer)
+    }
+}
+
+/// Serializes `map` with its entries sorted by key, for use with
+/// `#[serde(serialize_with = "seq_map::serialize_sorted")]`.
+///
+/// Uses the same map or pair sequence representation as the `Serialize` impl of `SeqMap`.
+///
+/// # Errors
+///
+/// Returns the errors of the serializer.
+pub fn serialize_sorted<K, V, H, S>(map: &SeqMap<K, V, H>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Serialize + Ord,
+    V: Serialize,
+    S: Serializer,
+{
+    let mut sorted: Vec<&(K, V)> = map.entries.iter().collect();
+    sorted.sort_by(|(a, _), (b, _)| a.cmp(b));
+    serialize_entries(sorted.into_iter(), serializer)
+}
+
+fn serialize_entries<'a, K, V, S>(
+    entries: impl ExactSizeIterator<Item = &'a (K, V)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    K: Serialize + 'a,
+    V: Serialize + 'a,
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (key, value) in entries {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    } else {
+        let mut seq = serializer.serialize_seq(Some(entries.len()))?;
+        for entry in entries {
+            seq.serialize_element(entry)?;
+        }
+        seq.end()
+    }
+}
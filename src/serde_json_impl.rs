@@ -0,0 +1,79 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use serde_json::{Map, Value};
+
+use crate::SeqMap;
+
+/// Converts in the order of the `Map`, which is insertion order since the `serde_json` feature
+/// enables `preserve_order` in `serde_json`.
+impl From<Map<String, Value>> for SeqMap<String, Value> {
+    fn from(map: Map<String, Value>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+/// Converts in insertion order, which the `Map` keeps.
+impl From<SeqMap<String, Value>> for Map<String, Value> {
+    fn from(map: SeqMap<String, Value>) -> Self {
+        map.entries.into_iter().collect()
+    }
+}
+
+/// Converts into a `Value::Object`.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::SeqMap;
+/// use serde_json::{json, Value};
+/// let map = SeqMap::from([("id".to_string(), json!(7))]);
+/// let value = Value::from(map);
+/// assert_eq!(value, json!({"id": 7}));
+/// let back = SeqMap::try_from(value).unwrap();
+/// assert_eq!(back[&"id".to_string()], 7);
+/// ```
+impl From<SeqMap<String, Value>> for Value {
+    fn from(map: SeqMap<String, Value>) -> Self {
+        Self::Object(map.into())
+    }
+}
+
+impl TryFrom<Value> for SeqMap<String, Value> {
+    type Error = NotAnObjectError;
+
+    /// Converts a `Value::Object`, anything else is handed back in the error.
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Object(map) => Ok(map.into()),
+            value => Err(NotAnObjectError { value }),
+        }
+    }
+}
+
+/// Error returned when converting a `serde_json::Value` that is not an object into a `SeqMap`.
+///
+/// Holds on to the value so that nothing is lost.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotAnObjectError {
+    /// The value that was not an object.
+    pub value: Value,
+}
+
+impl Display for NotAnObjectError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected a JSON object for the SeqMap, found {}",
+            self.value
+        )
+    }
+}
+
+impl Error for NotAnObjectError {}
@@ -0,0 +1,169 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+    sync::Arc,
+};
+
+use arc_swap::ArcSwap;
+
+use crate::{SeqMap, SeqMapError};
+
+/// A [`SeqMap`] shared between threads, for maps that are read far more often than written.
+///
+/// The map is held in an atomically swappable `Arc`. Readers take a snapshot without locking,
+/// and keep seeing that consistent, ordered state for as long as they hold it. Writers clone
+/// the current map, change the clone and publish it, so every write copies the whole map.
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::Arc, thread};
+/// use seq_map::SharedSeqMap;
+/// let assets = Arc::new(SharedSeqMap::new());
+/// assets.insert("ship", 1).unwrap();
+/// let reader = Arc::clone(&assets);
+/// let found = thread::spawn(move || reader.snapshot().get(&"ship").copied())
+///     .join()
+///     .unwrap();
+/// assert_eq!(found, Some(1));
+/// ```
+pub struct SharedSeqMap<K, V> {
+    map: ArcSwap<SeqMap<K, V>>,
+}
+
+impl<K, V> SharedSeqMap<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new, empty `SharedSeqMap`.
+    pub fn new() -> Self {
+        Self::from(SeqMap::new())
+    }
+
+    /// Returns the current state of the map.
+    ///
+    /// Later writes publish new maps and leave the snapshot as it was.
+    pub fn snapshot(&self) -> Arc<SeqMap<K, V>> {
+        self.map.load_full()
+    }
+
+    /// Calls `f` with the current state of the map and returns its result.
+    ///
+    /// This is cheaper than [`snapshot`](Self::snapshot) for short reads, since the reference
+    /// count is not touched.
+    pub fn with<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&SeqMap<K, V>) -> R,
+    {
+        f(&self.map.load())
+    }
+
+    /// Publishes `map` as the new state, replacing the current one.
+    pub fn store(&self, map: SeqMap<K, V>) {
+        self.map.store(Arc::new(map));
+    }
+
+    /// Publishes `map` as the new state and returns the state it replaced.
+    pub fn replace(&self, map: SeqMap<K, V>) -> Arc<SeqMap<K, V>> {
+        self.map.swap(Arc::new(map))
+    }
+
+    /// Changes a clone of the current map with `f`, publishes it and returns the result of `f`.
+    ///
+    /// If another writer publishes in the meantime, the change is made again on top of that
+    /// writer's map, so `f` may be called more than once and must not rely on running once.
+    pub fn update<R, F>(&self, mut f: F) -> R
+    where
+        K: Clone,
+        V: Clone,
+        F: FnMut(&mut SeqMap<K, V>) -> R,
+    {
+        let mut result = None;
+        self.map.rcu(|current| {
+            let mut next = SeqMap::clone(current);
+            result = Some(f(&mut next));
+            next
+        });
+        result.expect("rcu calls the update at least once")
+    }
+
+    /// Inserts a key-value pair at the end of the insertion order and publishes the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    pub fn insert(&self, key: K, value: V) -> Result<(), SeqMapError<K>>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.update(|map| map.insert(key.clone(), value.clone()))
+    }
+
+    /// Removes a key and publishes the result, returning the value if it existed.
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        K: Clone,
+        V: Clone,
+    {
+        self.update(|map| map.remove(key))
+    }
+
+    /// Checks if the current map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.load().contains_key(key)
+    }
+
+    /// Returns the number of key-value pairs in the current map.
+    pub fn len(&self) -> usize {
+        self.map.load().len()
+    }
+
+    /// Returns `true` if the current map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.load().is_empty()
+    }
+
+    /// Consumes the shared map and returns its current state.
+    pub fn into_inner(self) -> Arc<SeqMap<K, V>> {
+        self.map.into_inner()
+    }
+}
+
+impl<K, V> Default for SharedSeqMap<K, V>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> From<SeqMap<K, V>> for SharedSeqMap<K, V> {
+    fn from(map: SeqMap<K, V>) -> Self {
+        Self {
+            map: ArcSwap::from_pointee(map),
+        }
+    }
+}
+
+impl<K, V> Debug for SharedSeqMap<K, V>
+where
+    K: Eq + Hash + Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "SharedSeqMap({:?})", self.map.load())
+    }
+}
@@ -0,0 +1,345 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    hash::{BuildHasher, Hash},
+};
+
+use smallvec::SmallVec;
+
+use crate::{
+    key_index::{IndexEntry, KeyIndex},
+    map_index::Generation,
+    DefaultHashBuilder, SeqMap, SeqMapError, SeqMapIndex, SMALL_MAP_LEN,
+};
+
+/// An insertion ordered map that stores its first `N` entries inline.
+///
+/// As long as the map has at most `N` entries, nothing is allocated on the heap and keys are
+/// found by comparing them along the entries. Inserting more entries spills them to the heap
+/// and builds a key index, after which it behaves like a [`SeqMap`]. This suits having very
+/// many tiny maps, for example one per entity.
+///
+/// The key index is the one of `SeqMap`: it stores positions rather than keys, so keys do not
+/// have to be [`Clone`], it caches the hash of every key, and the positions of later entries
+/// are renumbered in batches after a [`remove`](Self::remove). Once built, the index is kept,
+/// even when removals bring the map back to `N` entries or fewer.
+///
+/// Keys are hashed with `S`, which defaults to [`DefaultHashBuilder`].
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::SmallSeqMap;
+/// let mut map: SmallSeqMap<&str, i32, 4> = SmallSeqMap::new();
+/// map.insert("a", 1).unwrap();
+/// map.insert("b", 2).unwrap();
+/// assert!(!map.spilled());
+/// assert_eq!(map.get(&"b"), Some(&2));
+/// ```
+#[derive(Clone)]
+pub struct SmallSeqMap<K, V, const N: usize, S = DefaultHashBuilder> {
+    // Positions in `entries` and the hashes of their keys. Empty until the map first grows
+    // past `N` entries, and complete from then on
+    key_index: KeyIndex,
+    hash_builder: S,
+    entries: SmallVec<[(K, V); N]>,
+    // Changed whenever entries are removed, to catch stale `SeqMapIndex` values in debug builds
+    generation: Generation,
+}
+
+impl<K, V, const N: usize> SmallSeqMap<K, V, N>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new, empty `SmallSeqMap` without allocating.
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultHashBuilder::default())
+    }
+}
+
+impl<K, V, const N: usize, S> SmallSeqMap<K, V, N, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Creates a new, empty `SmallSeqMap` that hashes keys with `hash_builder`, without
+    /// allocating.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            key_index: KeyIndex::new(),
+            hash_builder,
+            entries: SmallVec::new(),
+            generation: Generation::new(),
+        }
+    }
+
+    /// Returns the hasher the map hashes keys with.
+    pub const fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Returns `true` if the entries no longer fit inline and have moved to the heap.
+    pub fn spilled(&self) -> bool {
+        self.entries.spilled()
+    }
+
+    /// Inserts a key-value pair at the end of the insertion order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), SeqMapError<K>> {
+        self.insert_full(key, value).map(|_| ())
+    }
+
+    /// Inserts a key-value pair like [`insert`](Self::insert), returning the position of the
+    /// new entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    pub fn insert_full(&mut self, key: K, value: V) -> Result<SeqMapIndex<K, V>, SeqMapError<K>> {
+        let index = self.entries.len();
+        if self.key_index.is_empty() {
+            if self.index_of(&key).is_some() {
+                return Err(SeqMapError::KeyAlreadyExists(key));
+            }
+            self.entries.push((key, value));
+            self.generation.draw_once();
+            if self.entries.len() > N {
+                let hash_builder = &self.hash_builder;
+                self.key_index.build(
+                    self.entries
+                        .iter()
+                        .map(|(key, _)| hash_builder.hash_one(key)),
+                );
+            }
+            return Ok(self.generation.index(index));
+        }
+        let hash = self.hash_builder.hash_one(&key);
+        match self
+            .key_index
+            .entry(hash, |index| self.entries[index].0 == key)
+        {
+            IndexEntry::Occupied(_) => Err(SeqMapError::KeyAlreadyExists(key)),
+            IndexEntry::Vacant(slot) => {
+                self.entries.push((key, value));
+                slot.insert();
+                self.generation.draw_once();
+                Ok(self.generation.index(index))
+            }
+        }
+    }
+
+    /// Removes a key from the map, returning the value if it existed.
+    ///
+    /// The order of the remaining entries is kept.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = if self.key_index.is_empty() {
+            self.index_of(key)?
+        } else {
+            let hash = self.hash_builder.hash_one(key);
+            self.key_index
+                .remove(hash, |index| self.entries[index].0.borrow() == key)?
+        };
+        self.generation.bump();
+        let (_, value) = self.entries.remove(index);
+        Some(value)
+    }
+
+    /// Removes all elements from the map. Spilled entries keep their heap allocation.
+    pub fn clear(&mut self) {
+        self.generation.bump();
+        self.key_index.clear();
+        self.entries.clear();
+    }
+
+    /// Retrieves a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| &mut self.entries[index].1)
+    }
+
+    /// Returns the insertion index of the key.
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| self.generation.index(index))
+    }
+
+    /// Returns the key and value at `index`, or `None` if it is out of bounds.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `index` was taken before entries were removed, or was taken
+    /// from another map.
+    pub fn get_at(&self, index: SeqMapIndex<K, V>) -> Option<(&K, &V)> {
+        self.entries
+            .get(self.generation.position(index))
+            .map(|(k, v)| (k, v))
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).is_some()
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the entries in insertion order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Returns an iterator over the entries in insertion order, with mutable values.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (&K, &mut V)> + ExactSizeIterator {
+        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    /// Returns an iterator over the keys in insertion order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Returns an iterator over the values in insertion order.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    /// Converts into a [`SeqMap`], keeping the insertion order and the hasher.
+    ///
+    /// An index that is already built moves along, so no key is hashed.
+    pub fn into_seq_map(self) -> SeqMap<K, V, S> {
+        let mut map = SeqMap::from_entries(
+            self.hash_builder,
+            self.entries.into_vec().into(),
+            self.generation,
+        );
+        // Small maps have no index
+        if map.entries.len() > SMALL_MAP_LEN {
+            if self.key_index.is_empty() {
+                map.reindex_from(0);
+            } else {
+                map.key_index = self.key_index;
+            }
+        }
+        map.check_invariants();
+        map
+    }
+
+    fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        if self.key_index.is_empty() {
+            self.entries.iter().position(|(k, _)| k.borrow() == key)
+        } else {
+            let hash = self.hash_builder.hash_one(key);
+            self.key_index
+                .find(hash, |index| self.entries[index].0.borrow() == key)
+        }
+    }
+}
+
+impl<K, V, const N: usize, S> Default for SmallSeqMap<K, V, N, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+/// Takes over the index of the map, if it has one, so no key is hashed then.
+impl<K, V, const N: usize, S> From<SeqMap<K, V, S>> for SmallSeqMap<K, V, N, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn from(map: SeqMap<K, V, S>) -> Self {
+        let SeqMap {
+            mut key_index,
+            hash_builder,
+            entries,
+            generation,
+            ..
+        } = map;
+        if key_index.is_empty() && entries.len() > N {
+            key_index.build(entries.iter().map(|(key, _)| hash_builder.hash_one(key)));
+        }
+        Self {
+            key_index,
+            hash_builder,
+            entries: SmallVec::from_vec(entries.into_vec()),
+            generation,
+        }
+    }
+}
+
+impl<K, V, const N: usize, S> PartialEq for SmallSeqMap<K, V, N, S>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<K, V, const N: usize, S> Eq for SmallSeqMap<K, V, N, S>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V, const N: usize, S> Debug for SmallSeqMap<K, V, N, S>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "SmallSeqMap ")?;
+        f.debug_map()
+            .entries(self.entries.iter().map(|(k, v)| (k, v)))
+            .finish()
+    }
+}
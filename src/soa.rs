@@ -0,0 +1,363 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{
+    key_index::{IndexEntry, KeyIndex},
+    map_index::Generation,
+    DefaultHashBuilder, SeqMap, SeqMapError, SeqMapIndex, SMALL_MAP_LEN,
+};
+
+/// An insertion ordered map that keeps its keys and its values in two separate vectors.
+///
+/// With this structure-of-arrays layout, the values are one contiguous slice, see
+/// [`values_as_slice`](Self::values_as_slice). Scanning only the values then reads no key
+/// bytes, and the slice can be handed as is to code that processes plain arrays.
+///
+/// The index is the one of [`SeqMap`], with cached key hashes and batched renumbering after
+/// removals, so converting from and to a `SeqMap` with an index hashes no key.
+///
+/// Keys are hashed with `S`, which defaults to [`DefaultHashBuilder`].
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::SoaSeqMap;
+/// let mut speeds = SoaSeqMap::new();
+/// speeds.insert("a very long entity name", 1.5f32).unwrap();
+/// speeds.insert("another long entity name", 2.0).unwrap();
+/// for speed in speeds.values_as_mut_slice() {
+///     *speed *= 2.0;
+/// }
+/// assert_eq!(speeds.values_as_slice(), &[3.0, 4.0]);
+/// assert_eq!(speeds.get(&"another long entity name"), Some(&4.0));
+/// ```
+#[derive(Clone)]
+pub struct SoaSeqMap<K, V, S = DefaultHashBuilder> {
+    // Positions in `keys` and `values` and the hashes of their keys
+    key_index: KeyIndex,
+    hash_builder: S,
+    keys: Vec<K>,
+    values: Vec<V>,
+    // Changed whenever entries are removed, to catch stale `SeqMapIndex` values in debug builds
+    generation: Generation,
+}
+
+impl<K, V> SoaSeqMap<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new, empty `SoaSeqMap`.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a new, empty `SoaSeqMap` with room for `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, DefaultHashBuilder::default())
+    }
+}
+
+impl<K, V, S> SoaSeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Creates a new, empty `SoaSeqMap` that hashes keys with `hash_builder`.
+    pub const fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            key_index: KeyIndex::new(),
+            hash_builder,
+            keys: Vec::new(),
+            values: Vec::new(),
+            generation: Generation::new(),
+        }
+    }
+
+    /// Creates a new, empty `SoaSeqMap` with room for `capacity` entries, that hashes keys
+    /// with `hash_builder`.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            key_index: KeyIndex::with_capacity(capacity),
+            hash_builder,
+            keys: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+            generation: Generation::new(),
+        }
+    }
+
+    /// Returns the hasher the map hashes keys with.
+    pub const fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Inserts a key-value pair at the end of the insertion order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), SeqMapError<K>> {
+        self.insert_full(key, value).map(|_| ())
+    }
+
+    /// Inserts a key-value pair like [`insert`](Self::insert), returning the position of the
+    /// new entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    pub fn insert_full(&mut self, key: K, value: V) -> Result<SeqMapIndex<K, V>, SeqMapError<K>> {
+        let hash = self.hash_builder.hash_one(&key);
+        match self.key_index.entry(hash, |index| self.keys[index] == key) {
+            IndexEntry::Occupied(_) => Err(SeqMapError::KeyAlreadyExists(key)),
+            IndexEntry::Vacant(slot) => {
+                let index = self.keys.len();
+                self.keys.push(key);
+                self.values.push(value);
+                slot.insert();
+                self.generation.draw_once();
+                Ok(self.generation.index(index))
+            }
+        }
+    }
+
+    /// Removes a key from the map, returning the value if it existed.
+    ///
+    /// The order of the remaining entries is kept.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        let index = self
+            .key_index
+            .remove(hash, |index| self.keys[index].borrow() == key)?;
+        self.generation.bump();
+        self.keys.remove(index);
+        Some(self.values.remove(index))
+    }
+
+    /// Removes all elements from the map, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.generation.bump();
+        self.key_index.clear();
+        self.keys.clear();
+        self.values.clear();
+    }
+
+    /// Retrieves a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| &self.values[index])
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| &mut self.values[index])
+    }
+
+    /// Returns the insertion index of the key, which is also its position in both slices.
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| self.generation.index(index))
+    }
+
+    /// Returns the key and value at `index`, or `None` if it is out of bounds.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `index` was taken before entries were removed, or was taken
+    /// from another map.
+    pub fn get_at(&self, index: SeqMapIndex<K, V>) -> Option<(&K, &V)> {
+        let index = self.generation.position(index);
+        Some((self.keys.get(index)?, &self.values[index]))
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).is_some()
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the keys in insertion order, as one contiguous slice.
+    pub fn keys_as_slice(&self) -> &[K] {
+        &self.keys
+    }
+
+    /// Returns the values in insertion order, as one contiguous slice.
+    pub fn values_as_slice(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Returns the values in insertion order, as one contiguous mutable slice.
+    pub fn values_as_mut_slice(&mut self) -> &mut [V] {
+        &mut self.values
+    }
+
+    /// Returns an iterator over the entries in insertion order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator {
+        self.keys.iter().zip(&self.values)
+    }
+
+    /// Returns an iterator over the entries in insertion order, with mutable values.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (&K, &mut V)> + ExactSizeIterator {
+        self.keys.iter().zip(&mut self.values)
+    }
+
+    /// Returns an iterator over the keys in insertion order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+        self.keys.iter()
+    }
+
+    /// Returns an iterator over the values in insertion order.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.values.iter()
+    }
+
+    /// Returns an iterator over mutable references to the values in insertion order.
+    pub fn values_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut V> + ExactSizeIterator {
+        self.values.iter_mut()
+    }
+
+    /// Moves the entries into a [`SeqMap`], keeping the insertion order and the hasher.
+    ///
+    /// The index moves along, so no key is hashed.
+    pub fn into_seq_map(self) -> SeqMap<K, V, S> {
+        let mut map = SeqMap::from_entries(
+            self.hash_builder,
+            self.keys.into_iter().zip(self.values).collect(),
+            self.generation,
+        );
+        // Small maps have no index
+        if map.entries.len() > SMALL_MAP_LEN {
+            map.key_index = self.key_index;
+        }
+        map.check_invariants();
+        map
+    }
+
+    fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        self.key_index
+            .find(hash, |index| self.keys[index].borrow() == key)
+    }
+}
+
+impl<K, V, S> Default for SoaSeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+/// Takes over the index of the map, so only the keys of a small map, which has no index,
+/// are hashed.
+impl<K, V, S> From<SeqMap<K, V, S>> for SoaSeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn from(map: SeqMap<K, V, S>) -> Self {
+        let SeqMap {
+            mut key_index,
+            hash_builder,
+            entries,
+            generation,
+            ..
+        } = map;
+        if key_index.is_empty() {
+            key_index.build(entries.iter().map(|(key, _)| hash_builder.hash_one(key)));
+        }
+        let (keys, values) = entries.into_iter().unzip();
+        Self {
+            key_index,
+            hash_builder,
+            keys,
+            values,
+            generation,
+        }
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for SoaSeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::default();
+        for (k, v) in iter {
+            let _ = map.insert(k, v); // Intentionally ignore errors for this trait
+        }
+        map
+    }
+}
+
+impl<K, V, S> PartialEq for SoaSeqMap<K, V, S>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.keys == other.keys && self.values == other.values
+    }
+}
+
+impl<K, V, S> Eq for SoaSeqMap<K, V, S>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V, S> Debug for SoaSeqMap<K, V, S>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "SoaSeqMap ")?;
+        f.debug_map()
+            .entries(self.keys.iter().zip(&self.values))
+            .finish()
+    }
+}
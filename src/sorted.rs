@@ -0,0 +1,217 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    collections::BTreeSet,
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+    ops::RangeBounds,
+};
+
+use crate::{SeqMap, SeqMapError, SeqMapIndex};
+
+/// A [`SeqMap`] that also keeps its keys in a sorted secondary index.
+///
+/// Iteration is still in insertion order by default, but [`range`](Self::range) and
+/// [`iter_sorted`](Self::iter_sorted) give an ascending key order view of the same entries.
+/// Every mutation updates both, so inserts and removals also pay for the `BTreeSet` update.
+#[derive(Clone)]
+pub struct SortedSeqMap<K, V> {
+    map: SeqMap<K, V>,
+    sorted_keys: BTreeSet<K>,
+}
+
+impl<K, V> SortedSeqMap<K, V>
+where
+    K: Eq + Hash + Clone + Ord,
+{
+    /// Creates a new, empty `SortedSeqMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SortedSeqMap;
+    /// let map: SortedSeqMap<String, i32> = SortedSeqMap::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            map: SeqMap::new(),
+            sorted_keys: BTreeSet::new(),
+        }
+    }
+
+    /// Inserts a key-value pair at the end of the insertion order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeqMapError::KeyAlreadyExists` if the key is already present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SortedSeqMap;
+    /// let mut map = SortedSeqMap::new();
+    /// map.insert("b", 2).unwrap();
+    /// map.insert("a", 1).unwrap();
+    /// assert!(map.insert("a", 3).is_err());
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), SeqMapError<K>> {
+        self.map.insert(key.clone(), value)?;
+        self.sorted_keys.insert(key);
+        Ok(())
+    }
+
+    /// Removes a key from the map, returning the value if it existed.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + Ord + ?Sized,
+    {
+        let value = self.map.remove(key)?;
+        self.sorted_keys.remove(key);
+        Some(value)
+    }
+
+    /// Removes all elements from the map.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.sorted_keys.clear();
+    }
+
+    /// Retrieves a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get(key)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get_mut(key)
+    }
+
+    /// Checks if the map contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Returns the insertion index of the key.
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get_index(key)
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator over the entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.map.iter()
+    }
+
+    /// Returns an iterator over the entries in insertion order, with mutable values.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.map.iter_mut()
+    }
+
+    /// Returns an iterator over the entries in ascending key order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SortedSeqMap;
+    /// let mut map = SortedSeqMap::new();
+    /// map.insert("b", 2).unwrap();
+    /// map.insert("a", 1).unwrap();
+    /// let keys: Vec<_> = map.iter_sorted().map(|(k, _)| *k).collect();
+    /// assert_eq!(keys, vec!["a", "b"]);
+    /// ```
+    pub fn iter_sorted(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> {
+        self.range(..)
+    }
+
+    /// Returns an iterator over the entries whose keys are within `range`, in ascending key
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SortedSeqMap;
+    /// let mut map = SortedSeqMap::new();
+    /// for name in ["delta", "alpha", "charlie", "bravo"] {
+    ///     map.insert(name, name.len()).unwrap();
+    /// }
+    /// let keys: Vec<_> = map.range("b".."d").map(|(k, _)| *k).collect();
+    /// assert_eq!(keys, vec!["bravo", "charlie"]);
+    /// ```
+    pub fn range<R>(&self, range: R) -> impl DoubleEndedIterator<Item = (&K, &V)>
+    where
+        R: RangeBounds<K>,
+    {
+        self.sorted_keys
+            .range(range)
+            .filter_map(|key| self.map.get(key).map(|value| (key, value)))
+    }
+
+    /// Returns the underlying insertion ordered map.
+    pub fn as_seq_map(&self) -> &SeqMap<K, V> {
+        &self.map
+    }
+
+    /// Consumes the wrapper and returns the underlying insertion ordered map.
+    pub fn into_seq_map(self) -> SeqMap<K, V> {
+        self.map
+    }
+}
+
+impl<K, V> Default for SortedSeqMap<K, V>
+where
+    K: Eq + Hash + Clone + Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> From<SeqMap<K, V>> for SortedSeqMap<K, V>
+where
+    K: Eq + Hash + Clone + Ord,
+{
+    /// Wraps an existing map, building the sorted index from its keys.
+    fn from(map: SeqMap<K, V>) -> Self {
+        let sorted_keys = map.keys().cloned().collect();
+        Self { map, sorted_keys }
+    }
+}
+
+impl<K, V> Debug for SortedSeqMap<K, V>
+where
+    K: Eq + Hash + Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Sorted{:?}", self.map)
+    }
+}
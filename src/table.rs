@@ -0,0 +1,115 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::fmt::{self, Display, Formatter};
+
+use crate::{DefaultHashBuilder, SeqMap};
+
+impl<K, V, S> SeqMap<K, V, S>
+where
+    K: Display,
+    V: Display,
+{
+    /// Returns an adapter that displays the entries as an aligned two-column table, in
+    /// insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("player", "alice"), ("level", "dungeon-3")]);
+    /// assert_eq!(
+    ///     map.display_table().headers("key", "value").to_string(),
+    ///     "key    | value\n-------+----------\nplayer | alice\nlevel  | dungeon-3\n"
+    /// );
+    /// ```
+    pub fn display_table(&self) -> TableDisplay<'_, K, V, S> {
+        TableDisplay {
+            map: self,
+            headers: None,
+            max_cell_width: None,
+        }
+    }
+}
+
+/// Displays a `SeqMap` as an aligned table, with one `key | value` row per entry.
+///
+/// Created by [`SeqMap::display_table`]. Every row, including the last, ends with a newline.
+pub struct TableDisplay<'a, K, V, S = DefaultHashBuilder> {
+    map: &'a SeqMap<K, V, S>,
+    headers: Option<(&'a str, &'a str)>,
+    max_cell_width: Option<usize>,
+}
+
+impl<'a, K, V, S> TableDisplay<'a, K, V, S> {
+    /// Adds a header row, and a separator line below it.
+    #[must_use]
+    pub fn headers(mut self, key: &'a str, value: &'a str) -> Self {
+        self.headers = Some((key, value));
+        self
+    }
+
+    /// Cuts every cell to at most `max_cell_width` characters, ending cut cells with `…`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("description", "a very long text")]);
+    /// assert_eq!(
+    ///     map.display_table().truncate(6).to_string(),
+    ///     "descr… | a ver…\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn truncate(mut self, max_cell_width: usize) -> Self {
+        self.max_cell_width = Some(max_cell_width);
+        self
+    }
+
+    fn cell(&self, text: String) -> String {
+        match self.max_cell_width {
+            Some(max) if text.chars().count() > max => {
+                let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+                if max > 0 {
+                    cut.push('…');
+                }
+                cut
+            }
+            _ => text,
+        }
+    }
+}
+
+impl<K, V, S> Display for TableDisplay<'_, K, V, S>
+where
+    K: Display,
+    V: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut rows: Vec<(String, String)> = Vec::with_capacity(self.map.entries.len() + 1);
+        if let Some((key, value)) = self.headers {
+            rows.push((self.cell(key.to_string()), self.cell(value.to_string())));
+        }
+        for (key, value) in &self.map.entries {
+            rows.push((self.cell(key.to_string()), self.cell(value.to_string())));
+        }
+        let width_of = |text: &String| text.chars().count();
+        let key_width = rows.iter().map(|(k, _)| width_of(k)).max().unwrap_or(0);
+        let value_width = rows.iter().map(|(_, v)| width_of(v)).max().unwrap_or(0);
+
+        for (row, (key, value)) in rows.iter().enumerate() {
+            writeln!(f, "{key:<key_width$} | {value}")?;
+            if row == 0 && self.headers.is_some() {
+                writeln!(
+                    f,
+                    "{}+{}",
+                    "-".repeat(key_width + 1),
+                    "-".repeat(value_width + 1)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::fmt::{self, Display, Formatter};
+
+use crate::SeqMap;
+
+/// The label of a traced map, and how to display its keys.
+pub(crate) struct TraceLabel<K> {
+    label: &'static str,
+    fmt_key: fn(&K, &mut Formatter<'_>) -> fmt::Result,
+}
+
+impl<K> Clone for TraceLabel<K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K> Copy for TraceLabel<K> {}
+
+/// Displays a key with the function captured when the label was set.
+struct TracedKey<'a, K>(&'a K, fn(&K, &mut Formatter<'_>) -> fmt::Result);
+
+impl<K> Display for TracedKey<'_, K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        (self.1)(self.0, f)
+    }
+}
+
+impl<K, V, S> SeqMap<K, V, S> {
+    /// Turns on `tracing` events for this map, tagged with `label`.
+    ///
+    /// Inserts and removals are reported as `trace` events with the key and the new length,
+    /// and reorders such as sorting, rotating and shuffling as `debug` events. Maps without a
+    /// label emit nothing. The label is kept by clones and by the value mapping methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut registry = SeqMap::new();
+    /// registry.set_trace_label("registry");
+    /// registry.insert("render", 1).unwrap();
+    /// assert_eq!(registry.trace_label(), Some("registry"));
+    /// ```
+    pub fn set_trace_label(&mut self, label: &'static str)
+    where
+        K: Display,
+    {
+        self.trace = Some(TraceLabel {
+            label,
+            fmt_key: <K as Display>::fmt,
+        });
+    }
+
+    /// Turns off the `tracing` events set up with [`set_trace_label`](Self::set_trace_label).
+    pub fn clear_trace_label(&mut self) {
+        self.trace = None;
+    }
+
+    /// Returns the label set with [`set_trace_label`](Self::set_trace_label), if any.
+    pub fn trace_label(&self) -> Option<&'static str> {
+        self.trace.map(|trace| trace.label)
+    }
+
+    pub(crate) fn trace_insert(&self, index: usize) {
+        if let Some(trace) = self.trace {
+            tracing::trace!(
+                map = trace.label,
+                key = %TracedKey(&self.entries[index].0, trace.fmt_key),
+                index,
+                len = self.entries.len(),
+                "insert"
+            );
+        }
+    }
+
+    pub(crate) fn trace_remove(&self, key: &K, index: usize) {
+        if let Some(trace) = self.trace {
+            tracing::trace!(
+                map = trace.label,
+                key = %TracedKey(key, trace.fmt_key),
+                index,
+                len = self.entries.len(),
+                "remove"
+            );
+        }
+    }
+
+    pub(crate) fn trace_reorder(&self, operation: &'static str) {
+        if let Some(trace) = self.trace {
+            tracing::debug!(
+                map = trace.label,
+                len = self.entries.len(),
+                operation,
+                "reorder"
+            );
+        }
+    }
+}
@@ -0,0 +1,175 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{SeqMap, SMALL_MAP_LEN};
+
+/// A broken internal invariant of a `SeqMap`, as found by [`SeqMap::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantError {
+    /// The index does not hold exactly one position per entry.
+    IndexLength {
+        /// Number of entries in the map.
+        entries: usize,
+        /// Number of positions in the index.
+        index: usize,
+    },
+    /// The index holds a position that is out of bounds or occurs twice.
+    BadPosition {
+        /// The offending position.
+        position: usize,
+    },
+    /// The cached key hashes do not match the entries in number.
+    HashLength {
+        /// Number of entries in the map.
+        entries: usize,
+        /// Number of cached hashes.
+        hashes: usize,
+    },
+    /// The cached hash of the entry at `position` differs from the hash of its key.
+    StaleHash {
+        /// Position of the entry with the stale hash.
+        position: usize,
+    },
+    /// Looking up the key of the entry at `position` finds `found` instead.
+    MisplacedKey {
+        /// Position of the entry whose key was looked up.
+        position: usize,
+        /// Position the lookup returned, or `None` if the key was not found.
+        found: Option<usize>,
+    },
+    /// The key of the entry at `position` is equal to the key of an earlier entry.
+    DuplicateKey {
+        /// Position of the later of the two entries.
+        position: usize,
+    },
+    /// The map has too many entries to find keys by scanning, but no index.
+    MissingIndex {
+        /// Number of entries in the map.
+        entries: usize,
+    },
+}
+
+impl Display for InvariantError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IndexLength { entries, index } => {
+                write!(f, "index holds {index} positions for {entries} entries")
+            }
+            Self::BadPosition { position } => {
+                write!(
+                    f,
+                    "index holds position {position} more than once or out of bounds"
+                )
+            }
+            Self::HashLength { entries, hashes } => {
+                write!(f, "{hashes} cached hashes for {entries} entries")
+            }
+            Self::StaleHash { position } => {
+                write!(f, "cached hash of entry {position} does not match its key")
+            }
+            Self::MisplacedKey { position, found } => match found {
+                Some(found) => write!(f, "key of entry {position} is found at {found}"),
+                None => write!(f, "key of entry {position} is not found"),
+            },
+            Self::DuplicateKey { position } => {
+                write!(f, "key of entry {position} occurs earlier in the map")
+            }
+            Self::MissingIndex { entries } => {
+                write!(f, "no index for {entries} entries")
+            }
+        }
+    }
+}
+
+impl Error for InvariantError {}
+
+impl<K, V, S> SeqMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Checks that the key index and the entries agree.
+    ///
+    /// This hashes every key and looks it up again, so it is linear in the size of the map.
+    /// It is intended for tests and for checking custom bulk operations. With the
+    /// `strict-invariants` feature, debug builds run it after every mutating operation, which
+    /// makes every mutation linear too.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first broken invariant found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let mut map: SeqMap<_, _> = (0..100).map(|i| (i, i)).collect();
+    /// map.retain(|k, _| k % 3 != 0);
+    /// assert_eq!(map.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let entries = self.entries.len();
+        let hashes = self.key_index.hashes();
+        if self.key_index.is_empty() {
+            if entries > SMALL_MAP_LEN {
+                return Err(InvariantError::MissingIndex { entries });
+            }
+            if !hashes.is_empty() {
+                return Err(InvariantError::HashLength {
+                    entries,
+                    hashes: hashes.len(),
+                });
+            }
+            for (position, (key, _)) in self.entries.iter().enumerate() {
+                if self.entries[..position].iter().any(|(k, _)| k == key) {
+                    return Err(InvariantError::DuplicateKey { position });
+                }
+            }
+            return Ok(());
+        }
+
+        if self.key_index.len() != entries {
+            return Err(InvariantError::IndexLength {
+                entries,
+                index: self.key_index.len(),
+            });
+        }
+        if hashes.len() != entries {
+            return Err(InvariantError::HashLength {
+                entries,
+                hashes: hashes.len(),
+            });
+        }
+        let mut seen = vec![false; entries];
+        for index in self.key_index.positions() {
+            if index >= entries || std::mem::replace(&mut seen[index], true) {
+                return Err(InvariantError::BadPosition { position: index });
+            }
+        }
+        for (position, (key, _)) in self.entries.iter().enumerate() {
+            if hashes[position] != self.hash_builder.hash_one(key) {
+                return Err(InvariantError::StaleHash { position });
+            }
+            let found = self.index_of(key);
+            if found != Some(position) {
+                return Err(InvariantError::MisplacedKey { position, found });
+            }
+        }
+        Ok(())
+    }
+
+    /// Panics if an invariant is broken, in debug builds with the `strict-invariants` feature.
+    pub(crate) fn check_invariants(&self) {
+        #[cfg(all(feature = "strict-invariants", debug_assertions))]
+        if let Err(err) = self.validate() {
+            panic!("SeqMap invariant broken: {err}");
+        }
+    }
+}
@@ -0,0 +1,260 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    hash::{BuildHasher, Hash},
+    ops::{Range, RangeBounds},
+};
+
+use crate::{debug_entries, resolve_range, DefaultHashBuilder, SeqMap, SeqMapIndex};
+
+/// A borrowed, read-only view of a [`SeqMap`].
+///
+/// A view only offers lookups and iteration, so a library can hand one out, or take one in,
+/// without exposing mutation or committing to `&SeqMap` in its signatures. Views are `Copy`,
+/// and everything they return borrows from the map rather than from the view.
+///
+/// A view made with [`SeqMap::view_range`] covers only a window of positions, and behaves as
+/// if the entries outside it were not there. Positions are always those of the map, so an
+/// index taken from the map can be used with any view of it.
+///
+/// # Examples
+///
+/// ```
+/// use seq_map::{SeqMap, SeqMapView};
+/// fn total(scores: SeqMapView<'_, &str, u32>) -> u32 {
+///     scores.values().sum()
+/// }
+/// let map = SeqMap::from([("ann", 3), ("bob", 4)]);
+/// assert_eq!(total(map.view()), 7);
+/// assert_eq!(map.view().get(&"bob"), Some(&4));
+/// ```
+pub struct SeqMapView<'a, K, V, S = DefaultHashBuilder> {
+    map: &'a SeqMap<K, V, S>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, K, V, S> SeqMapView<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Creates a view of all of `map`.
+    pub fn new(map: &'a SeqMap<K, V, S>) -> Self {
+        map.view()
+    }
+
+    /// Retrieves a reference to the value corresponding to the key, if it lies within the view.
+    pub fn get<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = self.index_of(key)?;
+        Some(&self.map.entries[index].1)
+    }
+
+    /// Returns the insertion index of the key, if it lies within the view.
+    pub fn get_index<Q>(&self, key: &Q) -> Option<SeqMapIndex<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).map(|index| self.map.typed_index(index))
+    }
+
+    /// Returns the key and value at `index`, or `None` if it lies outside the view.
+    pub fn get_at(&self, index: SeqMapIndex<K, V>) -> Option<(&'a K, &'a V)> {
+        let index = self.map.checked_position(index);
+        if !self.range().contains(&index) {
+            return None;
+        }
+        let (key, value) = &self.map.entries[index];
+        Some((key, value))
+    }
+
+    /// Checks if the view contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of(key).is_some()
+    }
+
+    /// Returns the positions of the map covered by the view.
+    pub const fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the number of key-value pairs in the view.
+    pub const fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the view contains no elements.
+    pub const fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns an iterator over the entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a K, &'a V)> {
+        self.into_iter()
+    }
+
+    /// Returns an iterator over the keys in insertion order.
+    pub fn keys(&self) -> impl Iterator<Item = &'a K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values in insertion order.
+    pub fn values(&self) -> impl Iterator<Item = &'a V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over the entries in the positional `range`.
+    ///
+    /// An unbounded side of `range` ends where the view does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range does not lie within the view.
+    pub fn iter_range<R>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a V)> + ExactSizeIterator
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = resolve_range(range, self.range());
+        self.map.entries[range].iter().map(|(k, v)| (k, v))
+    }
+
+    /// Returns a view of the positional `range`, which must lie within this view.
+    ///
+    /// An unbounded side of `range` ends where this view does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range does not lie within the view.
+    pub fn view_range<R: RangeBounds<usize>>(&self, range: R) -> Self {
+        let range = resolve_range(range, self.range());
+        Self {
+            map: self.map,
+            start: range.start,
+            end: range.end,
+        }
+    }
+
+    /// Finds the position of `key` in the map, if it lies within the view.
+    fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map
+            .index_of(key)
+            .filter(|index| self.range().contains(index))
+    }
+}
+
+impl<'a, K, V, S> SeqMapView<'a, K, V, S> {
+    fn entries(&self) -> &'a [(K, V)] {
+        &self.map.entries[self.start..self.end]
+    }
+}
+
+impl<K, V, S> Clone for SeqMapView<'_, K, V, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V, S> Copy for SeqMapView<'_, K, V, S> {}
+
+impl<'a, K, V, S> From<&'a SeqMap<K, V, S>> for SeqMapView<'a, K, V, S> {
+    fn from(map: &'a SeqMap<K, V, S>) -> Self {
+        map.view()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for SeqMapView<'a, K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = <&'a SeqMap<K, V, S> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        (&self).into_iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &SeqMapView<'a, K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = <&'a SeqMap<K, V, S> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries().iter().map(|(k, v)| (k, v))
+    }
+}
+
+impl<K, V, S> PartialEq for SeqMapView<'_, K, V, S>
+where
+    K: Eq,
+    V: Eq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.entries() == other.entries()
+    }
+}
+
+impl<K, V, S> Debug for SeqMapView<'_, K, V, S>
+where
+    K: Eq + Hash + Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        debug_entries(f, self.entries())
+    }
+}
+
+impl<K, V, S> SeqMap<K, V, S> {
+    /// Returns a read-only view of the map.
+    pub fn view(&self) -> SeqMapView<'_, K, V, S> {
+        SeqMapView {
+            map: self,
+            start: 0,
+            end: self.entries.len(),
+        }
+    }
+
+    /// Returns a read-only view of the entries in the positional `range`.
+    ///
+    /// Lookups through the view only find keys whose entries lie within the range, so a phase
+    /// that must only see entries registered before a cutoff can be handed `view_range(..cutoff)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use seq_map::SeqMap;
+    /// let map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let early = map.view_range(..2);
+    /// assert_eq!(early.get(&"b"), Some(&2));
+    /// assert_eq!(early.get(&"c"), None);
+    /// assert_eq!(early.len(), 2);
+    /// ```
+    pub fn view_range<R: RangeBounds<usize>>(&self, range: R) -> SeqMapView<'_, K, V, S> {
+        let range = resolve_range(range, 0..self.entries.len());
+        SeqMapView {
+            map: self,
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "alloc_map")]
+
+use std::{alloc::Layout, cell::Cell, ptr::NonNull};
+
+use allocator_api2::alloc::{AllocError, Allocator, Global};
+use seq_map::{AllocSeqMap, FxBuildHasher, SeqMap, SeqMapError};
+
+#[derive(Clone, Copy)]
+struct Counting<'a>(&'a Cell<usize>);
+
+unsafe impl Allocator for Counting<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.set(self.0.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+#[test]
+fn entries_and_index_live_in_the_given_allocator() {
+    let allocations = Cell::new(0);
+    let mut map = AllocSeqMap::new_in(Counting(&allocations));
+    assert_eq!(allocations.get(), 0);
+
+    for i in 0..20 {
+        map.insert(i, i * 10).unwrap();
+    }
+    assert!(allocations.get() >= 2);
+    assert!(matches!(
+        map.insert(3, 0),
+        Err(SeqMapError::KeyAlreadyExists(3))
+    ));
+
+    assert_eq!(map.remove(&0), Some(0));
+    *map.get_mut(&5).unwrap() += 1;
+    assert_eq!(map.get(&5), Some(&51));
+    for (index, key) in map.keys().enumerate() {
+        assert_eq!(map.get_index(key).map(usize::from), Some(index));
+    }
+
+    let seq_map = map.into_seq_map();
+    assert_eq!(seq_map.len(), 19);
+    assert_eq!(seq_map.get_index(&1).map(usize::from), Some(0));
+}
+
+#[test]
+fn removals_keep_positions_with_a_custom_hasher() {
+    let mut map = AllocSeqMap::with_hasher_in(FxBuildHasher::new(), Global);
+    for i in 0..100 {
+        map.insert(i, i).unwrap();
+    }
+    for i in (0..100).step_by(3) {
+        assert_eq!(map.remove(&i), Some(i));
+    }
+    assert_eq!(map.len(), 66);
+    for (index, key) in map.keys().enumerate() {
+        assert_eq!(map.get_index(key).map(usize::from), Some(index));
+    }
+    assert!(map.insert(3, 0).is_ok());
+    assert_eq!(map.get_index(&3).map(usize::from), Some(66));
+
+    let seq_map: SeqMap<i32, i32, FxBuildHasher> = map.into_seq_map();
+    assert_eq!(seq_map.validate(), Ok(()));
+    assert_eq!(seq_map.get_index(&1).map(usize::from), Some(0));
+}
+
+#[test]
+// Validating the map after every operation hashes the keys again
+#[cfg(not(feature = "strict-invariants"))]
+fn into_seq_map_hashes_no_key() {
+    use std::hash::{BuildHasher, DefaultHasher};
+
+    struct CountingHasher<'a>(&'a Cell<usize>);
+
+    impl BuildHasher for CountingHasher<'_> {
+        type Hasher = DefaultHasher;
+
+        fn build_hasher(&self) -> DefaultHasher {
+            self.0.set(self.0.get() + 1);
+            DefaultHasher::new()
+        }
+    }
+
+    let hashed = Cell::new(0);
+    let mut map = AllocSeqMap::with_hasher_in(CountingHasher(&hashed), Global);
+    for i in 0..50 {
+        map.insert(i, i).unwrap();
+    }
+    map.remove(&7);
+
+    hashed.set(0);
+    let seq_map = map.into_seq_map();
+    assert_eq!(hashed.get(), 0);
+    assert_eq!(seq_map.validate(), Ok(()));
+    assert_eq!(seq_map.get_index(&8).map(usize::from), Some(7));
+}
+
+#[test]
+fn insert_full_returns_an_index_for_get_at() {
+    let mut map = AllocSeqMap::new();
+    map.insert("a", 1).unwrap();
+    let b = map.insert_full("b", 2).unwrap();
+    assert_eq!(map.get_index(&"b"), Some(b));
+    assert_eq!(map.get_at(b), Some((&"b", &2)));
+    assert_eq!(map.into_seq_map().get_at(b), Some((&"b", &2)));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "stale SeqMapIndex")]
+fn indices_taken_before_a_clear_panic_in_debug_builds() {
+    let mut map = AllocSeqMap::new();
+    let a = map.insert_full("a", 1).unwrap();
+    map.clear();
+    map.insert("b", 2).unwrap();
+    let _ = map.get_at(a);
+}
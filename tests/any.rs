@@ -0,0 +1,61 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::AnySeqMap;
+
+#[derive(Debug, PartialEq)]
+struct Physics {
+    gravity: f32,
+}
+
+#[test]
+fn typed_access_to_registered_resources() {
+    let mut registry = AnySeqMap::new();
+    registry
+        .insert("physics", Physics { gravity: 9.81 })
+        .unwrap();
+    registry.insert("frame", 0u64).unwrap();
+    registry.insert("name", "demo").unwrap();
+    assert!(registry.insert("frame", 1u32).is_err());
+
+    *registry.get_mut::<u64, _>(&"frame").unwrap() += 1;
+    assert_eq!(registry.get::<u64, _>(&"frame"), Some(&1));
+    assert!(registry.is::<Physics, _>(&"physics"));
+    assert!(!registry.is::<u32, _>(&"frame"));
+
+    assert_eq!(registry.remove::<u32, _>(&"physics"), None);
+    assert_eq!(
+        registry.remove::<Physics, _>(&"physics"),
+        Some(Physics { gravity: 9.81 })
+    );
+    assert_eq!(registry.get_index(&"name").map(usize::from), Some(1));
+    assert_eq!(registry.iter_of::<u64>().count(), 1);
+    assert_eq!(format!("{registry:?}"), r#"AnySeqMap ["frame", "name"]"#);
+}
+
+#[test]
+fn string_keys_are_looked_up_borrowed() {
+    let mut registry = AnySeqMap::new();
+    registry.insert(String::from("gravity"), 9.81f32).unwrap();
+    registry
+        .insert(String::from("title"), String::from("demo"))
+        .unwrap();
+
+    assert_eq!(registry.get::<f32, _>("gravity"), Some(&9.81));
+    *registry.get_mut::<f32, _>("gravity").unwrap() = 1.62;
+    assert!(registry.get_any("title").is_some());
+    assert!(registry.is::<String, _>("title"));
+    assert!(registry.contains_key("title"));
+
+    let title = registry.get_index("title").unwrap();
+    assert_eq!(registry.into_inner().get_at(title).unwrap().0, "title");
+
+    let mut registry = AnySeqMap::new();
+    registry.insert(String::from("a"), 1u8).unwrap();
+    registry.insert(String::from("b"), 2u8).unwrap();
+    assert_eq!(registry.remove::<u8, _>("a"), Some(1));
+    assert!(registry.remove_any("b").is_some());
+    assert!(registry.is_empty());
+}
@@ -0,0 +1,31 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use seq_map::SeqMap;
+
+#[test]
+fn arbitrary_maps_are_valid() {
+    let data: Vec<u8> = (0..=255u8)
+        .cycle()
+        .take(4096)
+        .map(|b| b.wrapping_mul(31))
+        .collect();
+    let mut u = Unstructured::new(&data);
+
+    for _ in 0..32 {
+        let map = SeqMap::<u8, u16>::arbitrary(&mut u).unwrap();
+        for (index, (key, _)) in map.iter().enumerate() {
+            assert_eq!(map.get_index(key).map(usize::from), Some(index));
+        }
+    }
+
+    let rest = SeqMap::<u8, u8>::arbitrary_take_rest(Unstructured::new(&data)).unwrap();
+    assert!(rest.len() <= 256);
+    for (index, (key, _)) in rest.iter().enumerate() {
+        assert_eq!(rest.get_index(key).map(usize::from), Some(index));
+    }
+}
@@ -0,0 +1,42 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use std::rc::Rc;
+
+use seq_map::SeqMapCell;
+
+struct Node {
+    registry: Rc<SeqMapCell<String, usize>>,
+}
+
+#[test]
+fn nodes_share_one_registry() {
+    let registry = Rc::new(SeqMapCell::new());
+    let nodes: Vec<Node> = (0..3)
+        .map(|_| Node {
+            registry: Rc::clone(&registry),
+        })
+        .collect();
+
+    for (i, node) in nodes.iter().enumerate() {
+        node.registry.insert(format!("n{i}"), i).unwrap();
+    }
+    assert!(nodes[0].registry.insert("n1".to_string(), 9).is_err());
+    assert!(nodes[2].registry.update("n0", |v| *v += 10));
+    assert_eq!(registry.with("n0", |v| *v * 2), Some(20));
+    assert_eq!(registry.remove("n1"), Some(1));
+    assert_eq!(registry.keys_cloned(), vec!["n0", "n2"]);
+    assert_eq!(
+        registry
+            .borrow()
+            .get_index(&"n2".to_string())
+            .map(usize::from),
+        Some(1)
+    );
+    assert_eq!(
+        format!("{:?}", SeqMapCell::from(seq_map::SeqMap::from([(1, 2)]))),
+        "SeqMapCell(SeqMap(1: 2))"
+    );
+}
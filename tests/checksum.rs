@@ -0,0 +1,22 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::SeqMap;
+
+#[test]
+fn checksums_match_the_documented_encoding() {
+    // Length, then each key (length-prefixed) and value, little-endian, hashed with FNV-1a.
+    let map: SeqMap<&str, i16> = SeqMap::from([("hp", 10), ("mp", -4)]);
+    assert_eq!(map.checksum64(), 0x03ae_c581_fb9f_78c9);
+    assert_eq!(map.checksum128(), 0xfcae_8fae_4353_67a5_d780_eba4_1e59_7f39);
+
+    let owned: SeqMap<String, i16> = map.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+    assert_eq!(owned.checksum64(), map.checksum64());
+
+    let mut changed = map.clone();
+    *changed.get_mut(&"mp").unwrap() = -5;
+    assert_ne!(changed.checksum64(), map.checksum64());
+    assert_ne!(changed.checksum128(), map.checksum128());
+}
@@ -0,0 +1,22 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "compact_index")]
+
+use seq_map::SeqMap;
+
+#[test]
+fn positions_survive_removal_and_reordering() {
+    let mut map: SeqMap<u32, u32> = (0..100).map(|i| (i, i * 2)).collect();
+    map.remove_many(&[3, 50]);
+    map.retain(|k, _| k % 10 != 0);
+    map.rotate_left(1);
+
+    assert_eq!(map.get_index(&1).map(usize::from), Some(map.len() - 1));
+    assert_eq!(map.get_index(&2).map(usize::from), Some(0));
+    assert_eq!(map.get(&99), Some(&198));
+    assert!(!map.contains_key(&50));
+    *map.get_mut_or_insert_with(100, || 0) += 7;
+    assert_eq!(map.get_index(&100).map(usize::from), Some(map.len() - 1));
+}
@@ -0,0 +1,31 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::{SeqCounter, SeqMap};
+
+#[test]
+fn counts_are_ranked_deterministically() {
+    let log = ["warn", "info", "error", "info", "warn", "debug", "info"];
+    let mut levels: SeqCounter<&str> = log.iter().copied().collect();
+    assert_eq!(levels.total(), 7);
+    assert_eq!(levels.len(), 4);
+    assert_eq!(levels.count(&"trace"), 0);
+
+    let ranked: Vec<_> = levels.most_common().collect();
+    assert_eq!(
+        ranked,
+        vec![(&"info", 3), (&"warn", 2), (&"error", 1), (&"debug", 1)]
+    );
+    assert_eq!(levels.add_n("debug", 2), 3);
+    assert_eq!(levels.most_common().nth(1), Some((&"debug", 3)));
+    assert_eq!(levels.most_common().next_back(), Some((&"error", 1)));
+
+    let counts = SeqMap::count_from_iter(log);
+    assert_eq!(
+        counts.keys().copied().collect::<Vec<_>>(),
+        vec!["warn", "info", "error", "debug"]
+    );
+    assert_eq!(counts[&"info"], 3);
+}
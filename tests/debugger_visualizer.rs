@@ -0,0 +1,28 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use std::{io::ErrorKind, path::Path, process::Command};
+
+#[test]
+fn gdb_printer_reads_the_current_layout() {
+    let check = Path::new(env!("CARGO_MANIFEST_DIR")).join("debug_metadata/check_gdb_printer.py");
+    let status = match Command::new("python3").arg(&check).status() {
+        Ok(status) => status,
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            eprintln!("python3 not found, skipping the gdb printer check");
+            return;
+        }
+        Err(error) => panic!("running {}: {error}", check.display()),
+    };
+    assert!(status.success(), "{} failed", check.display());
+}
+
+#[test]
+fn natvis_matches_every_map_and_reads_the_entries_deque() {
+    let natvis = include_str!("../debug_metadata/seq_map.natvis");
+    assert!(natvis.contains(r#"<Type Name="seq_map::SeqMap&lt;*,*,*&gt;">"#));
+    assert!(natvis.contains("entries.deque.len"));
+    assert!(natvis.contains("entries.deque.head"));
+    assert!(!natvis.contains("{entries.len}"));
+}
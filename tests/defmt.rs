@@ -0,0 +1,16 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "defmt")]
+
+use seq_map::SeqMap;
+
+#[test]
+fn implements_format() {
+    fn assert_format<T: defmt::Format>(_: &T) {}
+
+    let map: SeqMap<u8, (bool, i32)> = SeqMap::from([(1, (true, -4))]);
+    assert_format(&map);
+    assert_format(&SeqMap::<&str, u32>::new());
+}
@@ -0,0 +1,81 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::{DigestSeqMap, SeqMap};
+
+fn rebuilt(map: &DigestSeqMap<u32, String>) -> u64 {
+    let copy: SeqMap<u32, String> = map.iter().map(|(k, v)| (*k, v.clone())).collect();
+    DigestSeqMap::from(copy).current_digest()
+}
+
+#[test]
+fn incremental_digest_matches_a_full_recompute() {
+    let mut map = DigestSeqMap::new();
+    let empty = map.current_digest();
+    for i in 0..8 {
+        map.insert(i, format!("v{i}")).unwrap();
+        assert_eq!(map.current_digest(), rebuilt(&map));
+    }
+    assert!(map.insert(3, String::new()).is_err());
+
+    assert_eq!(map.replace(4, String::from("four")).as_deref(), Some("v4"));
+    assert_eq!(map.replace(8, String::from("eight")), None);
+    assert!(map.update(&0, |v| v.push('!')));
+    assert_eq!(map.current_digest(), rebuilt(&map));
+
+    for key in [0, 5, 8] {
+        map.remove(&key);
+        assert_eq!(map.current_digest(), rebuilt(&map));
+    }
+
+    let mut reordered: SeqMap<u32, String> = map.iter().map(|(k, v)| (*k, v.clone())).collect();
+    reordered.rotate_left(1);
+    assert_ne!(
+        DigestSeqMap::from(reordered).current_digest(),
+        map.current_digest()
+    );
+
+    map.clear();
+    assert_eq!(map.current_digest(), empty);
+}
+
+#[test]
+fn update_keeps_the_digest_when_the_closure_panics() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut map = DigestSeqMap::new();
+    for i in 0..4 {
+        map.insert(i, format!("v{i}")).unwrap();
+    }
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        map.update(&2, |value| {
+            value.push_str("-changed");
+            panic!("half way through");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(map.get(&2).map(String::as_str), Some("v2-changed"));
+    assert_eq!(map.current_digest(), rebuilt(&map));
+
+    assert_eq!(map.remove(&1), Some(String::from("v1")));
+    assert_eq!(map.current_digest(), rebuilt(&map));
+}
+
+#[test]
+fn removing_the_oldest_entry_every_tick_keeps_the_digest() {
+    let mut map = DigestSeqMap::new();
+    for i in 0..100 {
+        map.insert(i, format!("v{i}")).unwrap();
+    }
+    for tick in 100..400 {
+        map.remove(&(tick - 100));
+        map.insert(tick, format!("v{tick}")).unwrap();
+        if tick % 50 == 0 {
+            assert_eq!(map.current_digest(), rebuilt(&map));
+        }
+    }
+    assert_eq!(map.current_digest(), rebuilt(&map));
+    assert_eq!(map.keys().next(), Some(&300));
+}
@@ -0,0 +1,24 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "fast_hash")]
+
+use std::sync::Mutex;
+
+use seq_map::SeqMap;
+
+static REGISTRY: Mutex<SeqMap<&str, u32>> = Mutex::new(SeqMap::new());
+
+#[test]
+fn default_maps_can_be_created_in_a_static() {
+    let mut registry = REGISTRY.lock().unwrap();
+    for (index, name) in ["player", "enemy", "item"].into_iter().enumerate() {
+        registry.insert(name, index as u32).unwrap();
+    }
+    assert_eq!(registry["enemy"], 1);
+    assert_eq!(
+        registry.keys().copied().collect::<Vec<_>>(),
+        ["player", "enemy", "item"]
+    );
+}
@@ -0,0 +1,56 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::{FixedSeqMap, SeqMapError, SeqMapIndex};
+
+#[test]
+fn fills_up_and_frees_slots_in_order() {
+    let mut map: FixedSeqMap<&str, u32, 3> = FixedSeqMap::new();
+    map.insert("x", 1).unwrap();
+    map.insert("y", 2).unwrap();
+    assert!(matches!(
+        map.insert("x", 9),
+        Err(SeqMapError::KeyAlreadyExists("x"))
+    ));
+    map.insert("z", 3).unwrap();
+    assert!(map.is_full());
+    assert!(matches!(
+        map.insert("w", 4),
+        Err(SeqMapError::CapacityExceeded { capacity: 3 })
+    ));
+
+    assert_eq!(map.remove(&"y"), Some(2));
+    map.insert("w", 4).unwrap();
+    *map.get_mut(&"x").unwrap() += 10;
+
+    let entries: Vec<_> = map.iter().map(|(k, v)| (*k, *v)).collect();
+    assert_eq!(entries, vec![("x", 11), ("z", 3), ("w", 4)]);
+    let tail: Vec<_> = map.iter_range(1..).map(|(k, _)| *k).collect();
+    assert_eq!(tail, vec!["z", "w"]);
+    assert_eq!(
+        map.find(|_, v| *v > 3),
+        Some((SeqMapIndex::new(0), &"x", &11))
+    );
+    assert_eq!(
+        format!("{map:?}"),
+        r#"FixedSeqMap {"x": 11, "z": 3, "w": 4}"#
+    );
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map, FixedSeqMap::default());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "stale SeqMapIndex")]
+fn found_indices_taken_before_a_removal_panic_in_debug_builds() {
+    let mut map: FixedSeqMap<&str, u32, 4> = FixedSeqMap::new();
+    map.insert("a", 1).unwrap();
+    map.insert("b", 2).unwrap();
+    let (b, _, _) = map.find(|_, value| *value == 2).unwrap();
+    map.remove(&"a");
+    let _ = map.get_at(b);
+}
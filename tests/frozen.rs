@@ -0,0 +1,54 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::{FrozenSeqMap, SeqMap};
+
+#[test]
+fn frozen_maps_keep_entries_and_lookups() {
+    let mut map: SeqMap<String, usize> = (0..200).map(|i| (format!("tile-{i}"), i)).collect();
+    map.retain(|_, v| *v % 4 == 0);
+    let expected = map.clone();
+
+    let frozen = map.shrink_to_frozen();
+    assert_eq!(frozen.len(), 50);
+    assert_eq!(frozen["tile-196"], 196);
+    assert_eq!(frozen.get_index("tile-8").map(usize::from), Some(2));
+    assert!(!frozen.contains_key("tile-5"));
+    assert_eq!(frozen.as_entries(), expected.as_entries());
+
+    let small = FrozenSeqMap::from(SeqMap::from([("a", 1), ("b", 2)]));
+    assert_eq!(small.get("b"), Some(&2));
+
+    let mut thawed = frozen.into_seq_map();
+    assert_eq!(thawed, expected);
+    assert_eq!(thawed.validate(), Ok(()));
+    thawed.insert(String::from("tile-1"), 1).unwrap();
+    assert_eq!(
+        thawed.get_index(&String::from("tile-1")).map(usize::from),
+        Some(50)
+    );
+}
+
+#[test]
+fn indices_of_the_map_stay_valid_after_freezing() {
+    let mut map = SeqMap::new();
+    map.insert("grass", 1).unwrap();
+    let stone = map.insert_full("stone", 2).unwrap();
+    let frozen = map.shrink_to_frozen();
+    assert_eq!(frozen.get_at(stone), Some((&"stone", &2)));
+    assert_eq!(frozen.into_seq_map().get_at(stone), Some((&"stone", &2)));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "stale SeqMapIndex")]
+fn indices_from_another_map_panic_in_debug_builds() {
+    let mut map = SeqMap::new();
+    map.insert("grass", 1).unwrap();
+    let frozen = map.shrink_to_frozen();
+    let mut other = SeqMap::new();
+    let water = other.insert_full("water", 3).unwrap();
+    let _ = frozen.get_at(water);
+}
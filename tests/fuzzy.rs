@@ -0,0 +1,26 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "fuzzy")]
+
+use seq_map::SeqMap;
+
+#[test]
+fn closest_keys_are_ordered_by_distance_then_insertion() {
+    let map = SeqMap::from([
+        ("count".to_string(), 0),
+        ("counter".to_string(), 1),
+        ("mount".to_string(), 2),
+        ("amount".to_string(), 3),
+        ("cont".to_string(), 4),
+    ]);
+
+    let suggestions = map.closest_keys("coun", 2, 10);
+    assert_eq!(suggestions, vec!["count", "mount", "cont"]);
+
+    let limited = map.closest_keys("coun", 2, 2);
+    assert_eq!(limited, vec!["count", "mount"]);
+
+    assert_eq!(map.closest_keys("count", 0, 10), vec!["count"]);
+}
@@ -0,0 +1,245 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::{IndexedSeqMap, MatchingKeys, SeqMap};
+
+fn keys<K: Copy>(matching: Option<MatchingKeys<'_, K>>) -> Option<Vec<K>> {
+    matching.map(|keys| keys.copied().collect())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Kind {
+    Static,
+    Dynamic,
+}
+
+#[test]
+fn index_follows_mutations() {
+    let mut map = IndexedSeqMap::new();
+    map.add_index("kind", |(kind, _): &(Kind, i32)| *kind)
+        .unwrap();
+    assert!(map.add_index("kind", |(kind, _)| *kind).is_err());
+
+    map.insert("a", (Kind::Static, 1)).unwrap();
+    map.insert("b", (Kind::Dynamic, 2)).unwrap();
+    map.insert("c", (Kind::Static, 3)).unwrap();
+    map.insert("d", (Kind::Dynamic, 4)).unwrap();
+
+    assert_eq!(
+        keys(map.lookup_index("kind", &Kind::Static)),
+        Some(vec!["a", "c"])
+    );
+    assert!(map.lookup_index("missing", &Kind::Static).is_none());
+
+    assert!(map.update(&"c", |(kind, _)| *kind = Kind::Dynamic));
+    assert_eq!(
+        keys(map.lookup_index("kind", &Kind::Dynamic)),
+        Some(vec!["b", "c", "d"])
+    );
+
+    assert_eq!(map.remove(&"b"), Some((Kind::Dynamic, 2)));
+    assert_eq!(
+        keys(map.lookup_index("kind", &Kind::Dynamic)),
+        Some(vec!["c", "d"])
+    );
+
+    map.clear();
+    assert_eq!(keys(map.lookup_index("kind", &Kind::Dynamic)), Some(vec![]));
+}
+
+#[test]
+fn indexes_added_to_existing_map() {
+    let mut map = IndexedSeqMap::from(SeqMap::from([("x", 1), ("y", 2), ("z", 3)]));
+    map.add_index("odd", |v: &i32| v % 2 == 1).unwrap();
+
+    assert_eq!(keys(map.lookup_index("odd", &true)), Some(vec!["x", "z"]));
+    assert!(map.remove_index("odd"));
+    assert!(map.lookup_index("odd", &true).is_none());
+}
+
+#[test]
+fn cached_query_follows_mutations() {
+    let mut map: IndexedSeqMap<u32, bool> = IndexedSeqMap::new();
+    for id in 0..6 {
+        map.insert(id, id % 3 == 0).unwrap();
+    }
+    map.add_query("dirty", |dirty| *dirty).unwrap();
+    assert_eq!(keys(map.query("dirty")), Some(vec![0, 3]));
+
+    map.update(&1, |dirty| *dirty = true);
+    map.update(&3, |dirty| *dirty = false);
+    map.insert(6, true).unwrap();
+    assert_eq!(keys(map.query("dirty")), Some(vec![0, 1, 6]));
+
+    map.remove(&0);
+    assert_eq!(keys(map.query("dirty")), Some(vec![1, 6]));
+
+    assert!(map.remove_query("dirty"));
+    assert!(map.query("dirty").is_none());
+}
+
+#[test]
+fn indexes_have_their_own_field_types() {
+    let mut map = IndexedSeqMap::new();
+    map.add_index("kind", |(kind, _): &(Kind, &str)| *kind)
+        .unwrap();
+    map.add_index("name", |(_, name): &(Kind, &str)| *name)
+        .unwrap();
+    map.insert(1, (Kind::Static, "rock")).unwrap();
+    map.insert(2, (Kind::Dynamic, "ship")).unwrap();
+    map.insert(3, (Kind::Static, "ship")).unwrap();
+
+    assert_eq!(
+        keys(map.lookup_index("kind", &Kind::Static)),
+        Some(vec![1, 3])
+    );
+    assert_eq!(keys(map.lookup_index("name", &"ship")), Some(vec![2, 3]));
+    assert!(map.lookup_index("name", &Kind::Static).is_none());
+}
+
+#[test]
+fn update_catches_up_after_the_closure_panics() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut map = IndexedSeqMap::new();
+    map.add_index("kind", |(kind, _): &(Kind, i32)| *kind)
+        .unwrap();
+    map.add_query("large", |(_, size)| *size > 10).unwrap();
+    map.insert("a", (Kind::Static, 1)).unwrap();
+    map.insert("b", (Kind::Static, 2)).unwrap();
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        map.update(&"a", |(kind, size)| {
+            *kind = Kind::Dynamic;
+            *size = 20;
+            panic!("half way through");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(map.get(&"a"), Some(&(Kind::Dynamic, 20)));
+    // No extractor ran while unwinding, so "a" is still under its old field
+    assert_eq!(
+        keys(map.lookup_index("kind", &Kind::Static)),
+        Some(vec!["a", "b"])
+    );
+
+    map.insert("c", (Kind::Static, 3)).unwrap();
+    assert_eq!(
+        keys(map.lookup_index("kind", &Kind::Dynamic)),
+        Some(vec!["a"])
+    );
+    assert_eq!(
+        keys(map.lookup_index("kind", &Kind::Static)),
+        Some(vec!["b", "c"])
+    );
+    assert_eq!(keys(map.query("large")), Some(vec!["a"]));
+}
+
+#[test]
+fn insert_leaves_the_map_unchanged_when_an_index_panics() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut map = IndexedSeqMap::new();
+    map.add_index("kind", |(kind, _): &(Kind, i32)| *kind)
+        .unwrap();
+    map.add_index("size", |(_, size): &(Kind, i32)| {
+        assert!(*size >= 0, "negative size");
+        *size
+    })
+    .unwrap();
+    map.add_query("large", |(_, size)| *size > 10).unwrap();
+    map.insert("a", (Kind::Static, 20)).unwrap();
+
+    let result = catch_unwind(AssertUnwindSafe(|| map.insert("b", (Kind::Static, -1))));
+    assert!(result.is_err());
+    assert!(!map.contains_key(&"b"));
+    assert_eq!(map.len(), 1);
+    assert_eq!(
+        keys(map.lookup_index("kind", &Kind::Static)),
+        Some(vec!["a"])
+    );
+    assert_eq!(keys(map.query("large")), Some(vec!["a"]));
+
+    map.insert("b", (Kind::Static, 30)).unwrap();
+    assert_eq!(
+        keys(map.lookup_index("kind", &Kind::Static)),
+        Some(vec!["a", "b"])
+    );
+    assert_eq!(keys(map.query("large")), Some(vec!["a", "b"]));
+}
+
+#[test]
+fn insert_rolls_back_a_large_map_when_a_query_panics() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut map = IndexedSeqMap::new();
+    map.add_index("even", |size: &i32| size % 2 == 0).unwrap();
+    map.add_query("checked", |size: &i32| {
+        assert!(*size >= 0, "negative size");
+        *size > 10
+    })
+    .unwrap();
+    for id in 0..20 {
+        map.insert(id, id).unwrap();
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| map.insert(20, -2)));
+    assert!(result.is_err());
+    assert_eq!(map.len(), 20);
+    assert!(!map.contains_key(&20));
+    assert_eq!(
+        keys(map.lookup_index("even", &true)),
+        Some((0..20).step_by(2).collect())
+    );
+
+    map.insert(20, 20).unwrap();
+    assert_eq!(map.get(&20), Some(&20));
+    assert_eq!(keys(map.query("checked")), Some((11..=20).collect()));
+}
+
+#[cfg(not(feature = "strict-invariants"))]
+#[test]
+fn clearing_every_match_of_a_large_query() {
+    let len = 100_000;
+    let mut map = IndexedSeqMap::new();
+    map.add_query("dirty", |dirty: &bool| *dirty).unwrap();
+    map.add_index("dirty", |dirty: &bool| *dirty).unwrap();
+    for id in 0..len {
+        map.insert(id, true).unwrap();
+    }
+    assert_eq!(
+        map.query("dirty").map(|keys| keys.len()),
+        Some(len as usize)
+    );
+
+    // Every flip takes one key out of the matches, which must not scan the others
+    for id in (0..len).rev().step_by(2).chain((0..len).step_by(2)) {
+        assert!(map.update(&id, |dirty| *dirty = false));
+    }
+    assert_eq!(keys(map.query("dirty")), Some(vec![]));
+    assert_eq!(keys(map.lookup_index("dirty", &true)), Some(vec![]));
+    assert_eq!(
+        map.lookup_index("dirty", &false).map(|keys| keys.len()),
+        Some(len as usize)
+    );
+}
+
+#[test]
+fn keys_are_looked_up_borrowed() {
+    let mut map = IndexedSeqMap::new();
+    map.add_query("large", |size: &u32| *size > 10).unwrap();
+    map.insert(String::from("a"), 1).unwrap();
+    map.insert(String::from("b"), 20).unwrap();
+
+    assert!(map.update("a", |size| *size = 30));
+    assert_eq!(map.get("a"), Some(&30));
+    assert!(map.contains_key("b"));
+    assert_eq!(map.remove("b"), Some(20));
+    assert_eq!(
+        map.query("large")
+            .map(|keys| keys.cloned().collect::<Vec<_>>()),
+        Some(vec![String::from("a")])
+    );
+}
@@ -0,0 +1,31 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::{Interner, SeqMap};
+
+#[test]
+fn maps_share_interned_keys() {
+    let mut interner = Interner::new();
+    let maps: Vec<SeqMap<_, usize>> = (0..10)
+        .map(|i| {
+            ["x", "y", "z"]
+                .into_iter()
+                .map(|name| (interner.intern(name), i))
+                .collect()
+        })
+        .collect();
+    assert_eq!(interner.len(), 3);
+
+    let x = interner.get("x").unwrap();
+    for map in &maps {
+        let (key, _) = map.iter().next().unwrap();
+        assert!(key.ptr_eq(&x));
+        assert_eq!(map.get_index(&x).map(usize::from), Some(0));
+    }
+    assert_eq!(maps[3]["y"], 3);
+    assert_eq!(maps[3][&x], 3);
+    assert_eq!(format!("{x:?} {x}"), r#""x" x"#);
+    assert!(interner.get("w").is_none());
+}
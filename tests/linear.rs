@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::{LinearSeqMap, SeqMapError, SeqMapIndex};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Weight(f64);
+
+impl Eq for Weight {}
+
+#[test]
+fn keys_without_hash_keep_insertion_order() {
+    let mut map: LinearSeqMap<Weight, &str> = [
+        (Weight(0.5), "half"),
+        (Weight(2.0), "double"),
+        (Weight(0.5), "dup"),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(map.len(), 2);
+    assert_eq!(
+        map.insert(Weight(2.0), "again"),
+        Err(SeqMapError::KeyAlreadyExists(Weight(2.0)))
+    );
+    map.insert(Weight(1.0), "one").unwrap();
+    map[&Weight(0.5)] = "halved";
+
+    assert_eq!(map.remove(&Weight(2.0)), Some("double"));
+    assert_eq!(map.get_index(&Weight(1.0)).map(usize::from), Some(1));
+    assert_eq!(
+        map.get_at(SeqMapIndex::new(0)),
+        Some((&Weight(0.5), &"halved"))
+    );
+    assert_eq!(
+        format!("{map:?}"),
+        r#"LinearSeqMap {Weight(0.5): "halved", Weight(1.0): "one"}"#
+    );
+
+    map.retain(|k, _| k.0 > 0.75);
+    assert_eq!(map.into_vec(), vec![(Weight(1.0), "one")]);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "stale SeqMapIndex")]
+fn indices_taken_before_a_retain_panic_in_debug_builds() {
+    let mut map = LinearSeqMap::new();
+    map.insert("a", 1).unwrap();
+    map.insert("b", 2).unwrap();
+    let b = map.get_index(&"b").unwrap();
+    map.retain(|_, value| *value == 2);
+    let _ = map.get_at(b);
+}
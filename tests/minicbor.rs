@@ -0,0 +1,50 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "minicbor")]
+
+use minicbor::{Decoder, Encoder};
+use seq_map::{FxBuildHasher, SeqMap};
+
+#[test]
+fn cbor_round_trip_keeps_order() {
+    let map = SeqMap::from([(30u32, "thirty".to_string()), (10, "ten".to_string())]);
+
+    let bytes = minicbor::to_vec(&map).unwrap();
+    assert_eq!(bytes.len(), minicbor::len(&map));
+    assert_eq!(bytes[0], 0xa2);
+
+    let decoded: SeqMap<u32, String> = minicbor::decode(&bytes).unwrap();
+    assert_eq!(decoded, map);
+}
+
+#[test]
+fn cbor_round_trip_with_a_custom_hasher() {
+    let map: SeqMap<u32, u32, FxBuildHasher> = [(3, 30), (1, 10)].into_iter().collect();
+    let bytes = minicbor::to_vec(&map).unwrap();
+    let decoded: SeqMap<u32, u32, FxBuildHasher> = minicbor::decode(&bytes).unwrap();
+    assert_eq!(decoded, map);
+}
+
+#[test]
+fn cbor_indefinite_length_and_duplicates() {
+    let mut bytes = Vec::new();
+    let mut e = Encoder::new(&mut bytes);
+    e.begin_map().unwrap();
+    e.str("b").unwrap().u8(2).unwrap();
+    e.str("a").unwrap().u8(1).unwrap();
+    e.end().unwrap();
+
+    let decoded: SeqMap<String, u8> = Decoder::new(&bytes).decode().unwrap();
+    assert_eq!(decoded.keys().collect::<Vec<_>>(), vec!["b", "a"]);
+
+    let mut bytes = Vec::new();
+    let mut e = Encoder::new(&mut bytes);
+    e.map(2).unwrap();
+    e.str("a").unwrap().u8(1).unwrap();
+    e.str("a").unwrap().u8(2).unwrap();
+
+    let err = minicbor::decode::<SeqMap<String, u8>>(&bytes).unwrap_err();
+    assert!(err.to_string().contains("duplicate key in SeqMap"));
+}
@@ -0,0 +1,41 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use seq_map::SeqMap;
+
+#[allow(dead_code)]
+fn is_consistent<K: Eq + std::hash::Hash + Clone, V>(map: &SeqMap<K, V>) -> bool {
+    map.iter()
+        .enumerate()
+        .all(|(index, (key, _))| map.get_index(key).map(usize::from) == Some(index))
+}
+
+#[cfg(feature = "proptest")]
+mod with_proptest {
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn generated_maps_are_consistent(map in seq_map::seq_map(0u16..50, any::<i8>(), 2..20)) {
+            prop_assert!(map.len() >= 2 && map.len() < 20);
+            prop_assert!(super::is_consistent(&map));
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+#[test]
+fn quickcheck_shrinks_to_valid_maps() {
+    use quickcheck::{Arbitrary, Gen};
+
+    let mut g = Gen::new(20);
+    for _ in 0..20 {
+        let map = SeqMap::<u8, u8>::arbitrary(&mut g);
+        assert!(is_consistent(&map));
+        for smaller in map.shrink().take(50) {
+            assert!(is_consistent(&smaller));
+            assert!(smaller.len() <= map.len());
+        }
+    }
+}
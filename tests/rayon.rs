@@ -0,0 +1,36 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "rayon")]
+
+use rayon::prelude::*;
+use seq_map::SeqMap;
+
+#[test]
+fn parallel_building_and_sorting_match_the_sequential_result() {
+    let pairs: Vec<(u32, u32)> = (0..10_000).map(|i| ((i * 7919) % 5000, i)).collect();
+
+    let sequential: SeqMap<u32, u32> = pairs.iter().copied().collect();
+    let parallel: SeqMap<u32, u32> = pairs.par_iter().copied().collect();
+    assert_eq!(parallel, sequential);
+    assert_eq!(parallel.validate(), Ok(()));
+
+    let mut extended = SeqMap::from([(4999, 0), (1, 1)]);
+    extended.par_extend((0..20u32).into_par_iter().map(|i| (i, i + 100)));
+    assert_eq!(extended.len(), 21);
+    assert_eq!(extended[&1], 1);
+    assert_eq!(extended.get_index(&0).map(usize::from), Some(2));
+
+    let mut by_value = sequential.clone();
+    let mut expected = sequential;
+    by_value.par_sort_by(|_, a, _, b| (a % 10).cmp(&(b % 10)));
+    expected.sort_values_by(|a, b| (a % 10).cmp(&(b % 10)));
+    assert_eq!(by_value, expected);
+    assert_eq!(by_value.validate(), Ok(()));
+
+    by_value.par_sort_keys();
+    assert!(by_value.is_sorted());
+    let total: u64 = by_value.par_iter().map(|(_, v)| u64::from(*v)).sum();
+    assert_eq!(total, by_value.values().map(|v| u64::from(*v)).sum::<u64>());
+}
@@ -0,0 +1,45 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::{Registry, SeqMapError};
+
+#[test]
+fn ids_give_direct_access_to_registered_values() {
+    let mut textures = Registry::new();
+    let grass = textures.register("grass", 64u32).unwrap();
+    let stone = textures.register("stone", 128u32).unwrap();
+    assert!(matches!(
+        textures.register("grass", 1),
+        Err(SeqMapError::KeyAlreadyExists("grass"))
+    ));
+
+    textures[stone] *= 2;
+    assert_eq!(textures[stone], 256);
+    assert_eq!(*textures.key(grass), "grass");
+    assert_eq!(textures.id(&"stone"), Some(stone));
+    assert_eq!(stone.index(), 1);
+    let ids: Vec<_> = textures.iter().map(|(id, _, _)| id).collect();
+    assert_eq!(ids, vec![grass, stone]);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "does not belong")]
+fn ids_from_another_registry_are_rejected() {
+    let mut a = Registry::new();
+    let mut b = Registry::new();
+    let id = a.register("x", 1).unwrap();
+    b.register("x", 2).unwrap();
+    let _ = b.get(id);
+}
+
+#[test]
+fn keys_are_looked_up_borrowed() {
+    let mut sounds = Registry::new();
+    let step = sounds.register(String::from("step"), 1u8).unwrap();
+    assert_eq!(sounds.id("step"), Some(step));
+    assert_eq!(sounds.get_by_key("step"), Some(&1));
+    assert!(!sounds.contains_key("jump"));
+}
@@ -0,0 +1,111 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::RollbackSeqMap;
+
+#[test]
+fn rolling_back_replays_from_the_restored_tick() {
+    let mut world = RollbackSeqMap::new(3);
+    world.insert("a", 0).unwrap();
+    for tick in 1..=4 {
+        world.save_state(tick);
+        *world.get_mut(&"a").unwrap() += 1;
+        if tick == 2 {
+            world.insert("b", 0).unwrap();
+        }
+    }
+    assert_eq!(world.saved_ticks().collect::<Vec<_>>(), vec![2, 3, 4]);
+    assert!(!world.restore_state(1));
+
+    assert!(world.restore_state(3));
+    assert_eq!(world.get(&"a"), Some(&2));
+    assert!(world.contains_key(&"b"));
+    assert_eq!(world.saved_ticks().collect::<Vec<_>>(), vec![2, 3]);
+
+    world.remove(&"b");
+    assert_eq!(world.saved_state(3).unwrap().len(), 2);
+    assert!(world.restore_state(3));
+    assert_eq!(world.len(), 2);
+
+    world.discard_before(3);
+    assert_eq!(world.saved_ticks().collect::<Vec<_>>(), vec![3]);
+    assert_eq!(world.into_inner().into_vec(), vec![("a", 2), ("b", 0)]);
+}
+
+#[test]
+fn restoring_puts_removed_entries_back_in_place() {
+    let mut world = RollbackSeqMap::new(4);
+    for (key, value) in [("a", 1), ("b", 2), ("c", 3)] {
+        world.insert(key, value).unwrap();
+    }
+    world.save_state(1);
+    world.remove(&"b");
+    *world.get_mut(&"c").unwrap() = 30;
+    world.insert("d", 4).unwrap();
+    world.save_state(2);
+    world.map_mut().retain(|_, value| *value > 3);
+    world.remove(&"c");
+
+    assert_eq!(
+        world.saved_state(2).unwrap().into_vec(),
+        vec![("a", 1), ("c", 30), ("d", 4)]
+    );
+    assert_eq!(world.map().clone().into_vec(), vec![("d", 4)]);
+    assert!(world.restore_state(1));
+    assert_eq!(
+        world.into_inner().into_vec(),
+        vec![("a", 1), ("b", 2), ("c", 3)]
+    );
+}
+
+#[test]
+fn changing_an_entry_clones_only_its_value() {
+    use std::{cell::Cell, rc::Rc};
+
+    struct Counted(u32, Rc<Cell<usize>>);
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            self.1.set(self.1.get() + 1);
+            Self(self.0, Rc::clone(&self.1))
+        }
+    }
+
+    let clones = Rc::new(Cell::new(0));
+    let mut world = RollbackSeqMap::new(2);
+    for key in 0..1000 {
+        world.insert(key, Counted(0, Rc::clone(&clones))).unwrap();
+    }
+    for tick in 1..=10 {
+        world.save_state(tick);
+        world.get_mut(&7).unwrap().0 += 1;
+        world.remove(&(tick as i32 + 100));
+    }
+    assert_eq!(clones.get(), 20);
+
+    assert!(world.restore_state(9));
+    assert_eq!(world.get(&7).unwrap().0, 8);
+    assert_eq!(world.len(), 992);
+    assert!(world.contains_key(&109));
+    assert_eq!(world.saved_ticks().collect::<Vec<_>>(), vec![9]);
+}
+
+#[test]
+#[cfg(not(feature = "strict-invariants"))]
+fn restoring_removals_of_the_oldest_entries_keeps_the_order() {
+    let len = 50_000;
+    let mut world = RollbackSeqMap::from_map((0..len).map(|i| (i, i)).collect(), 2);
+    world.save_state(1);
+    for i in 0..1_000 {
+        assert_eq!(world.remove(&i), Some(i));
+        world.insert(len + i, 0).unwrap();
+    }
+    for i in (2_000..40_000).step_by(4_000) {
+        world.remove(&i);
+    }
+    assert!(world.restore_state(1));
+    let map = world.into_inner();
+    assert_eq!(map.validate(), Ok(()));
+    assert!(map.into_iter().eq((0..len).map(|i| (i, i))));
+}
@@ -0,0 +1,22 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "schemars")]
+
+use seq_map::SeqMap;
+
+#[test]
+fn schema_is_an_object_of_values() {
+    #[derive(schemars::JsonSchema)]
+    #[allow(dead_code)]
+    struct Settings {
+        volumes: SeqMap<String, f32>,
+    }
+
+    let schema = schemars::schema_for!(Settings);
+    let volumes = &schema.as_value()["properties"]["volumes"];
+
+    assert_eq!(volumes["type"], "object");
+    assert_eq!(volumes["additionalProperties"]["type"], "number");
+}
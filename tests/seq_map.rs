@@ -41,8 +41,8 @@ fn index() {
     map.insert(10, 20).expect("should work");
     map.insert(42, -13).expect("should work");
 
-    assert_eq!(map.get_index(&42), Some(1));
-    assert_eq!(map.get_index(&10), Some(0));
+    assert_eq!(map.get_index(&42).map(usize::from), Some(1));
+    assert_eq!(map.get_index(&10).map(usize::from), Some(0));
     assert_eq!(map.get_index(&100), None);
 }
 
@@ -53,7 +53,7 @@ fn index_struct() {
     map.insert(TestKey { a: 20 }, 20).expect("should work");
     map.insert(TestKey { a: 42 }, -13).expect("should work");
 
-    assert_eq!(map.get_index(&TestKey { a: 42 }), Some(1));
+    assert_eq!(map.get_index(&TestKey { a: 42 }).map(usize::from), Some(1));
 }
 
 #[test]
@@ -127,12 +127,12 @@ fn test_remove_and_reindex() {
     assert_eq!(keys, vec!["a", "c"]);
 
     // Verify indices were updated
-    assert_eq!(map.get_index(&"a"), Some(0));
-    assert_eq!(map.get_index(&"c"), Some(1));
+    assert_eq!(map.get_index(&"a").map(usize::from), Some(0));
+    assert_eq!(map.get_index(&"c").map(usize::from), Some(1));
 
     // Can still insert after remove
     map.insert("d", 4).unwrap();
-    assert_eq!(map.get_index(&"d"), Some(2));
+    assert_eq!(map.get_index(&"d").map(usize::from), Some(2));
 }
 
 #[test]
@@ -240,3 +240,1611 @@ fn empty_iterator() {
     assert!(map.is_empty());
     assert_eq!(map.len(), 0);
 }
+
+#[test]
+fn eq_ignore_order() {
+    let mut a = SeqMap::new();
+    a.insert("a", 1).unwrap();
+    a.insert("b", 2).unwrap();
+
+    let mut b = SeqMap::new();
+    b.insert("b", 2).unwrap();
+    b.insert("a", 1).unwrap();
+
+    assert_ne!(a, b);
+    assert!(a.eq_ignore_order(&b));
+    assert!(a.keys_eq_ignore_order(&b));
+
+    b.insert("c", 3).unwrap();
+    assert!(!a.eq_ignore_order(&b));
+    assert!(!a.keys_eq_ignore_order(&b));
+
+    let mut c = SeqMap::new();
+    c.insert("a", 1).unwrap();
+    c.insert("b", 99).unwrap();
+    assert!(!a.eq_ignore_order(&c));
+    assert!(a.keys_eq_ignore_order(&c));
+}
+
+#[test]
+fn ordering() {
+    use std::collections::BTreeMap;
+
+    let a: SeqMap<_, _> = vec![("a", 1), ("b", 2)].into_iter().collect();
+    let b: SeqMap<_, _> = vec![("a", 1), ("b", 3)].into_iter().collect();
+    let prefix: SeqMap<_, _> = vec![("a", 1)].into_iter().collect();
+
+    assert!(a < b);
+    assert!(prefix < a);
+    assert_eq!(a.cmp(&a.clone()), std::cmp::Ordering::Equal);
+
+    let mut sorted = vec![b.clone(), a.clone(), prefix.clone()];
+    sorted.sort();
+    assert_eq!(sorted, vec![prefix, a.clone(), b]);
+
+    let mut outer = BTreeMap::new();
+    outer.insert(a.clone(), "first");
+    assert_eq!(outer.get(&a), Some(&"first"));
+}
+
+#[test]
+fn eq_std_collections() {
+    use std::collections::{BTreeMap, HashMap};
+
+    let mut map = SeqMap::new();
+    map.insert("b", 2).unwrap();
+    map.insert("a", 1).unwrap();
+
+    let hash_map: HashMap<_, _> = [("a", 1), ("b", 2)].into_iter().collect();
+    let btree_map: BTreeMap<_, _> = [("a", 1), ("b", 2)].into_iter().collect();
+    assert!(map == hash_map);
+    assert!(map == btree_map);
+
+    let other_btree_map: BTreeMap<_, _> = [("a", 1), ("b", 3)].into_iter().collect();
+    assert!(map != other_btree_map);
+
+    let in_order: &[(&str, i32)] = &[("b", 2), ("a", 1)];
+    let out_of_order: &[(&str, i32)] = &[("a", 1), ("b", 2)];
+    assert!(map == in_order);
+    assert!(map != out_of_order);
+}
+
+#[test]
+fn std_conversions() {
+    use std::collections::{BTreeMap, HashMap};
+
+    let hash_map: HashMap<_, _> = [("c", 3), ("a", 1), ("b", 2)].into_iter().collect();
+    let map = SeqMap::from(hash_map);
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+
+    let btree_map: BTreeMap<_, _> = [("y", 2), ("x", 1)].into_iter().collect();
+    let map = SeqMap::from(btree_map);
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec!["x", "y"]);
+
+    let map = SeqMap::from(vec![("b", 2), ("a", 1), ("b", 3)]);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&"b"), Some(&2));
+
+    let pairs: Vec<(&str, i32)> = map.into();
+    assert_eq!(pairs, vec![("b", 2), ("a", 1)]);
+}
+
+#[test]
+fn from_array() {
+    pub struct NotClone(i32);
+
+    let map = SeqMap::from([("a", NotClone(1)), ("b", NotClone(2)), ("a", NotClone(3))]);
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&"a").map(|v| v.0), Some(1));
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec!["a", "b"]);
+}
+
+#[test]
+fn try_from_vec_rejects_duplicates() {
+    let map = SeqMap::try_from_vec(vec![("a", 1), ("b", 2)]).expect("should work");
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec!["a", "b"]);
+
+    let result = SeqMap::try_from_vec(vec![("a", 1), ("b", 2), ("a", 3)]);
+    assert!(matches!(
+        result,
+        Err(seq_map::SeqMapError::KeyAlreadyExists("a"))
+    ));
+}
+
+#[test]
+fn try_from_iter_reports_first_duplicate() {
+    let map = SeqMap::try_from_iter(vec![("a", 1), ("b", 2)]).expect("should work");
+    assert_eq!(map.get_index(&"b").map(usize::from), Some(1));
+
+    let err = SeqMap::try_from_iter(vec![("a", 1), ("b", 2), ("b", 3), ("a", 4)]).unwrap_err();
+    assert_eq!(err.index, 2);
+    assert_eq!(err.key, "b");
+    assert_eq!(err.value, 3);
+    assert_eq!(
+        err.to_string(),
+        "The key \"b\" at index 2 already exists in the SeqMap."
+    );
+}
+
+#[test]
+fn from_iter_with_policies() {
+    use seq_map::DuplicatePolicy;
+
+    let pairs = [("a", 1), ("b", 2), ("a", 3), ("c", 4), ("b", 5)];
+
+    let first = SeqMap::from_iter_with(pairs, DuplicatePolicy::FirstWins).unwrap();
+    assert_eq!(first.into_vec(), vec![("a", 1), ("b", 2), ("c", 4)]);
+
+    let last = SeqMap::from_iter_with(pairs, DuplicatePolicy::LastWins).unwrap();
+    assert_eq!(last.into_vec(), vec![("a", 3), ("b", 5), ("c", 4)]);
+
+    let merged = SeqMap::from_iter_with(pairs, DuplicatePolicy::Merge(|a, b| *a *= b)).unwrap();
+    assert_eq!(merged.into_vec(), vec![("a", 3), ("b", 10), ("c", 4)]);
+
+    let err = SeqMap::from_iter_with(pairs, DuplicatePolicy::Error).unwrap_err();
+    assert_eq!((err.index, err.key, err.value), (2, "a", 3));
+}
+
+#[test]
+fn append() {
+    let mut map = SeqMap::from([("a", 1), ("b", 2)]);
+    let mut other = SeqMap::from([("c", 3), ("a", 10), ("d", 4)]);
+
+    map.append(&mut other);
+
+    assert!(other.is_empty());
+    assert_eq!(map.get(&"a"), Some(&1));
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec!["a", "b", "c", "d"]
+    );
+    assert_eq!(map.get_index(&"d").map(usize::from), Some(3));
+
+    other.insert("e", 5).unwrap();
+    assert_eq!(other.get_index(&"e").map(usize::from), Some(0));
+}
+
+#[test]
+fn try_extend_is_all_or_nothing() {
+    let mut map = SeqMap::from([("a", 1), ("b", 2)]);
+
+    let result = map.try_extend([("c", 3), ("b", 20), ("d", 4), ("c", 30)]);
+    assert_eq!(result, Err(vec!["b", "c"]));
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec!["a", "b"]);
+    assert_eq!(map.get(&"c"), None);
+
+    map.try_extend([("c", 3), ("d", 4)]).expect("should work");
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec!["a", "b", "c", "d"]
+    );
+    assert_eq!(map.get_index(&"d").map(usize::from), Some(3));
+}
+
+#[test]
+fn insert_many_report() {
+    let mut map = SeqMap::from([("a", 1), ("b", 2)]);
+
+    let report = map.insert_many(vec![("c", 3), ("a", 10), ("d", 4), ("c", 30)]);
+
+    assert_eq!(
+        report
+            .inserted
+            .iter()
+            .copied()
+            .map(usize::from)
+            .collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+    assert_eq!(report.skipped, vec![("a", 10), ("c", 30)]);
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec!["a", "b", "c", "d"]
+    );
+    assert_eq!(map.get(&"a"), Some(&1));
+}
+
+#[test]
+// Validating the map after every operation hashes the keys again
+#[cfg(not(feature = "strict-invariants"))]
+fn bulk_inserts_hash_every_key_once() {
+    use std::{
+        cell::Cell,
+        hash::{Hash, Hasher},
+    };
+
+    thread_local! {
+        static HASHED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Counted(u32);
+
+    impl Hash for Counted {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            HASHED.with(|hashed| hashed.set(hashed.get() + 1));
+            self.0.hash(state);
+        }
+    }
+
+    let mut map: SeqMap<_, _> = (0..100).map(|i| (Counted(i), i)).collect();
+    HASHED.with(|hashed| hashed.set(0));
+
+    let report = map.insert_many((95..120).map(|i| (Counted(i), i)));
+    assert_eq!(report.skipped.len(), 5);
+    assert_eq!(HASHED.with(Cell::get), 25);
+
+    HASHED.with(|hashed| hashed.set(0));
+    map.try_extend((120..130).map(|i| (Counted(i), i))).unwrap();
+    assert_eq!(HASHED.with(Cell::get), 10);
+
+    HASHED.with(|hashed| hashed.set(0));
+    let collisions = map.try_extend([(Counted(130), 0), (Counted(5), 0), (Counted(130), 1)]);
+    assert_eq!(collisions, Err(vec![Counted(5), Counted(130)]));
+    assert_eq!(HASHED.with(Cell::get), 3);
+    assert_eq!(map.len(), 130);
+}
+
+#[test]
+fn splice() {
+    let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+
+    let removed = map
+        .splice(1..3, [("c", 30), ("x", 10)])
+        .expect("should work");
+    assert_eq!(removed, vec![("b", 2), ("c", 3)]);
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec!["a", "c", "x", "d"]
+    );
+    assert_eq!(map.get_index(&"d").map(usize::from), Some(3));
+    assert_eq!(map.get_index(&"b"), None);
+    assert_eq!(map.get(&"c"), Some(&30));
+
+    let err = map.splice(..1, [("z", 0), ("d", 40)]).unwrap_err();
+    assert_eq!((err.index, err.key, err.value), (1, "d", 40));
+    assert_eq!(map.len(), 4);
+
+    let removed = map.splice(2.., []).expect("should work");
+    assert_eq!(removed, vec![("x", 10), ("d", 4)]);
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+#[should_panic]
+fn splice_out_of_bounds() {
+    let mut map = SeqMap::from([("a", 1)]);
+    let _ = map.splice(0..2, []);
+}
+
+#[test]
+fn partition() {
+    let map = SeqMap::from([("a", 1), ("b", 20), ("c", 3), ("d", 40)]);
+
+    let (large, small) = map.partition(|_, v| *v >= 10);
+
+    assert_eq!(large.keys().copied().collect::<Vec<_>>(), vec!["b", "d"]);
+    assert_eq!(small.keys().copied().collect::<Vec<_>>(), vec!["a", "c"]);
+    assert_eq!(large.get_index(&"d").map(usize::from), Some(1));
+    assert_eq!(small.get_index(&"c").map(usize::from), Some(1));
+}
+
+#[test]
+fn chunks() {
+    let map: SeqMap<_, _> = (0..5).map(|i| (i, i * 10)).collect();
+
+    let sizes: Vec<_> = map.chunks(2).map(<[_]>::len).collect();
+    assert_eq!(sizes, vec![2, 2, 1]);
+
+    let chunks: Vec<_> = map.into_chunks(2).collect();
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[1].keys().copied().collect::<Vec<_>>(), vec![2, 3]);
+    assert_eq!(chunks[1].get_index(&3).map(usize::from), Some(1));
+    assert_eq!(chunks[2].get(&4), Some(&40));
+}
+
+#[test]
+fn map_values() {
+    let map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+
+    let strings = map.map_values(|v| format!("#{v}"));
+    assert_eq!(strings.get(&"b"), Some(&"#2".to_string()));
+    assert_eq!(strings.get_index(&"c").map(usize::from), Some(2));
+
+    let squared = map.into_map_values(|v| v * v);
+    assert_eq!(squared.into_vec(), vec![("a", 1), ("b", 4), ("c", 9)]);
+}
+
+#[test]
+fn try_map_values() {
+    let map = SeqMap::from([("a", "1"), ("b", "2"), ("c", "three"), ("d", "x")]);
+
+    let mut visited = Vec::new();
+    let err = map
+        .try_map_values(|k, v| {
+            visited.push(*k);
+            v.parse::<i32>()
+        })
+        .unwrap_err();
+    assert_eq!(visited, vec!["a", "b", "c"]);
+    assert_eq!((err.index, err.key), (2, "c"));
+    assert!(err
+        .to_string()
+        .starts_with("Failed to map the value for key \"c\""));
+
+    let ok = SeqMap::from([("a", "1"), ("b", "2")])
+        .try_into_map_values(|_, v| v.parse::<i32>())
+        .expect("should work");
+    assert_eq!(ok.get(&"b"), Some(&2));
+    assert_eq!(ok.get_index(&"b").map(usize::from), Some(1));
+}
+
+#[test]
+fn map_keys_with_policy() {
+    use seq_map::DuplicatePolicy;
+
+    let map = SeqMap::from([("x_a", 1), ("b", 2), ("a", 3)]);
+    let strip = |k: &'static str| k.trim_start_matches("x_");
+
+    let first = map
+        .clone()
+        .map_keys(strip, DuplicatePolicy::FirstWins)
+        .unwrap();
+    assert_eq!(first.into_vec(), vec![("a", 1), ("b", 2)]);
+
+    let last = map
+        .clone()
+        .map_keys(strip, DuplicatePolicy::LastWins)
+        .unwrap();
+    assert_eq!(last.into_vec(), vec![("a", 3), ("b", 2)]);
+
+    let err = map.map_keys(strip, DuplicatePolicy::Error).unwrap_err();
+    assert_eq!((err.index, err.key, err.value), (2, "a", 3));
+}
+
+#[test]
+fn filter_map() {
+    let map: SeqMap<_, _> = (0..6).map(|i| (i, i)).collect();
+
+    let map = map.filter_map(|k, v| (k % 2 == 1).then(|| v * 10));
+
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![1, 3, 5]);
+    assert_eq!(map.get(&3), Some(&30));
+    assert_eq!(map.get(&2), None);
+    assert_eq!(map.get_index(&5).map(usize::from), Some(2));
+}
+
+#[test]
+fn zip_and_join() {
+    let declarations = SeqMap::from([("a", "x"), ("b", "y"), ("c", "z")]);
+    let types = SeqMap::from([("c", 3), ("a", 1), ("d", 4)]);
+
+    let zipped: Vec<_> = declarations.zip(&types).collect();
+    assert_eq!(zipped, vec![(&"a", &"x", &1), (&"c", &"z", &3)]);
+
+    let joined = declarations.join_with(types, |name, ty| format!("{name}:{ty}"));
+    assert_eq!(joined.keys().copied().collect::<Vec<_>>(), vec!["a", "c"]);
+    assert_eq!(joined.get(&"c"), Some(&"z:3".to_string()));
+    assert_eq!(joined.get_index(&"c").map(usize::from), Some(1));
+}
+
+#[test]
+fn left_and_outer_join() {
+    let desired = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    let current = SeqMap::from([("d", 40), ("c", 30), ("e", 50)]);
+
+    let left: Vec<_> = desired.left_join(&current).collect();
+    assert_eq!(
+        left,
+        vec![(&"a", &1, None), (&"b", &2, None), (&"c", &3, Some(&30))]
+    );
+
+    let outer: Vec<_> = desired.outer_join(&current).collect();
+    assert_eq!(
+        outer,
+        vec![
+            (&"a", Some(&1), None),
+            (&"b", Some(&2), None),
+            (&"c", Some(&3), Some(&30)),
+            (&"d", None, Some(&40)),
+            (&"e", None, Some(&50)),
+        ]
+    );
+}
+
+#[test]
+fn retain_and_retain_drain() {
+    let mut map: SeqMap<_, _> = (0..6).map(|i| (i, i * 10)).collect();
+
+    map.retain(|k, v| {
+        *v += 1;
+        k % 3 != 0
+    });
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![1, 2, 4, 5]);
+    assert_eq!(map.get(&4), Some(&41));
+    assert_eq!(map.get_index(&4).map(usize::from), Some(2));
+
+    let removed = map.retain_drain(|_, v| *v > 20);
+    assert_eq!(removed, vec![(1, 11)]);
+    assert_eq!(map.get_index(&5).map(usize::from), Some(2));
+    assert_eq!(map.get(&1), None);
+}
+
+#[test]
+fn remove_many() {
+    let mut map: SeqMap<_, _> = (0..10).map(|i| (i, i)).collect();
+
+    let removed = map.remove_many(&[8, 1, 3, 42, 3]);
+
+    assert_eq!(removed, 3);
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec![0, 2, 4, 5, 6, 7, 9]
+    );
+    for (index, key) in map.keys().enumerate() {
+        assert_eq!(map.get_index(key).map(usize::from), Some(index));
+    }
+}
+
+#[test]
+fn update_in_place() {
+    let mut map = SeqMap::from([("a", vec![1]), ("b", vec![])]);
+
+    assert!(map.update(&"b", |v| v.push(2)));
+    assert!(!map.update(&"c", |v| v.push(3)));
+    assert_eq!(map.get(&"b"), Some(&vec![2]));
+
+    map.try_update(&"a", |v| v.clear()).expect("should work");
+    assert!(map.get(&"a").unwrap().is_empty());
+    assert!(matches!(
+        map.try_update(&"c", |v| v.clear()),
+        Err(seq_map::SeqMapError::KeyNotFound("c"))
+    ));
+}
+
+#[test]
+fn upsert_with() {
+    let mut map: SeqMap<&str, Vec<i32>> = SeqMap::new();
+
+    assert!(map.upsert_with("a", || vec![1], |v| v.push(1)));
+    assert!(map.upsert_with("b", || vec![2], |v| v.push(2)));
+    assert!(!map.upsert_with("a", || vec![10], |v| v.push(10)));
+
+    assert_eq!(map.into_vec(), vec![("a", vec![1, 10]), ("b", vec![2])]);
+}
+
+#[test]
+fn get_mut_or_default() {
+    let mut map: SeqMap<&str, Vec<i32>> = SeqMap::new();
+
+    map.get_mut_or_default("b").push(1);
+    map.get_mut_or_default("a").push(2);
+    map.get_mut_or_default("b").push(3);
+    map.get_mut_or_insert_with("c", || vec![0]).push(4);
+
+    assert_eq!(
+        map.into_vec(),
+        vec![("b", vec![1, 3]), ("a", vec![2]), ("c", vec![0, 4])]
+    );
+}
+
+#[test]
+fn value_search() {
+    use seq_map::SeqMapIndex;
+
+    let map = SeqMap::from([("a", 5), ("b", 7), ("c", 5)]);
+
+    assert!(map.contains_value(&7));
+    assert!(!map.contains_value(&8));
+
+    assert_eq!(
+        map.find(|_, v| *v == 5),
+        Some((SeqMapIndex::new(0), &"a", &5))
+    );
+    assert_eq!(
+        map.find(|k, v| *v == 5 && *k != "a"),
+        Some((SeqMapIndex::new(2), &"c", &5))
+    );
+    assert_eq!(map.find(|_, v| *v == 8), None);
+
+    assert_eq!(map.position_by(|_, v| *v == 7).map(usize::from), Some(1));
+    assert_eq!(map.position_by(|_, v| *v == 8), None);
+}
+
+#[test]
+fn min_max_break_ties_by_insertion_order() {
+    use seq_map::SeqMapIndex;
+
+    let map = SeqMap::from([("a", 2), ("b", 1), ("c", 3), ("d", 1), ("e", 3)]);
+
+    assert_eq!(
+        map.min_by(|(_, a), (_, b)| a.cmp(b)),
+        Some((SeqMapIndex::new(1), &"b", &1))
+    );
+    assert_eq!(
+        map.max_by(|(_, a), (_, b)| a.cmp(b)),
+        Some((SeqMapIndex::new(2), &"c", &3))
+    );
+    assert_eq!(
+        map.min_by_key(|_, v| *v),
+        Some((SeqMapIndex::new(1), &"b", &1))
+    );
+    assert_eq!(
+        map.max_by_key(|_, v| *v),
+        Some((SeqMapIndex::new(2), &"c", &3))
+    );
+
+    let empty: SeqMap<&str, i32> = SeqMap::new();
+    assert_eq!(empty.min_by_key(|_, v| *v), None);
+    assert_eq!(empty.max_by(|(_, a), (_, b)| a.cmp(b)), None);
+}
+
+#[test]
+fn iter_range() {
+    let mut map: SeqMap<_, _> = (0..5).map(|i| (i, i)).collect();
+
+    let window: Vec<_> = map.iter_range(1..3).map(|(k, _)| *k).collect();
+    assert_eq!(window, vec![1, 2]);
+
+    let reversed: Vec<_> = map.iter_range(3..=4).rev().map(|(k, _)| *k).collect();
+    assert_eq!(reversed, vec![4, 3]);
+    assert_eq!(map.iter_range(5..).len(), 0);
+
+    for (_, v) in map.iter_range_mut(3..) {
+        *v = -1;
+    }
+    assert_eq!(
+        map.values().copied().collect::<Vec<_>>(),
+        vec![0, 1, 2, -1, -1]
+    );
+}
+
+#[test]
+fn iter_from_key() {
+    let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+    map.remove(&"a");
+
+    let from: Vec<_> = map.iter_from(&"c").map(|(k, _)| *k).collect();
+    assert_eq!(from, vec!["c", "d"]);
+
+    let after: Vec<_> = map.iter_after(&"c").map(|(k, _)| *k).collect();
+    assert_eq!(after, vec!["d"]);
+
+    assert_eq!(map.iter_after(&"d").count(), 0);
+    assert_eq!(map.iter_from(&"a").count(), 0);
+    assert_eq!(map.iter_after(&"a").count(), 0);
+}
+
+#[test]
+fn neighbor_entries() {
+    let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3)]);
+
+    assert_eq!(map.entry_after(&"a"), Some((&"b", &2)));
+    assert_eq!(map.entry_before(&"c"), Some((&"b", &2)));
+
+    map.remove(&"b");
+    assert_eq!(map.entry_after(&"a"), Some((&"c", &3)));
+    assert_eq!(map.entry_before(&"c"), Some((&"a", &1)));
+    assert_eq!(map.entry_before(&"a"), None);
+    assert_eq!(map.entry_after(&"c"), None);
+    assert_eq!(map.entry_after(&"b"), None);
+}
+
+#[test]
+fn binary_search_after_sort_keys() {
+    let mut map = SeqMap::from([("d", 4), ("b", 2), ("a", 1), ("c", 3)]);
+    assert!(!map.is_sorted());
+    assert!(!map.is_sorted_by_key(|k, _| *k));
+
+    map.sort_keys();
+
+    assert!(map.is_sorted());
+    assert!(map.is_sorted_by_key(|_, v| *v));
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec!["a", "b", "c", "d"]
+    );
+    assert_eq!(map.get_index(&"d").map(usize::from), Some(3));
+    assert_eq!(map.binary_search_keys(&"c"), Ok(2));
+    assert_eq!(map.binary_search_keys(&"bb"), Err(2));
+    assert_eq!(map.binary_search_by(|_, v| v.cmp(&4)), Ok(3));
+}
+
+#[test]
+fn try_get_propagates_key() {
+    fn lookup(map: &SeqMap<String, i32>, name: &str) -> Result<i32, Box<dyn std::error::Error>> {
+        Ok(*map.try_get(&name.to_string())?)
+    }
+
+    let map = SeqMap::from([("x".to_string(), 1), ("y".to_string(), 2)]);
+
+    assert_eq!(lookup(&map, "y").unwrap(), 2);
+    let err = map.try_get(&"z".to_string()).unwrap_err();
+    assert_eq!(err.key, "z");
+    assert_eq!(err.map_len, 2);
+    assert!(lookup(&map, "z").is_err());
+}
+
+#[test]
+fn errors_carry_the_key() {
+    use seq_map::SeqMapError;
+
+    let mut map = SeqMap::from([("a".to_string(), 1)]);
+
+    let err = map.insert("a".to_string(), 2).unwrap_err();
+    assert_eq!(err.key(), Some(&"a".to_string()));
+    assert_eq!(
+        err.to_string(),
+        "The key \"a\" already exists in the SeqMap."
+    );
+    assert_eq!(format!("{err:?}"), "KeyAlreadyExists(..)");
+
+    let err = map.try_update(&"b".to_string(), |v| *v += 1).unwrap_err();
+    assert!(matches!(err, SeqMapError::KeyNotFound(ref key) if key == "b"));
+
+    let err: SeqMapError<String> = SeqMapError::IndexOutOfRange { index: 3, len: 1 };
+    assert_eq!(err.key(), None);
+    assert_eq!(
+        err.to_string(),
+        "The index 3 is out of range for a SeqMap with 1 entries."
+    );
+}
+
+#[test]
+fn errors_for_keys_without_debug_can_be_unwrapped() {
+    let mut map = SeqMap::new();
+    map.insert(TestKey { a: 1 }, 1).unwrap();
+
+    let err = map.insert(TestKey { a: 1 }, 2).unwrap_err();
+    assert_eq!(format!("{err:?}"), "KeyAlreadyExists(..)");
+    assert_eq!(err.key().map(|key| key.a), Some(1));
+}
+
+#[test]
+fn apply_permutation_reorders_once_validated() {
+    use seq_map::SeqMapError;
+
+    let mut map = SeqMap::from([("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+
+    assert_eq!(
+        map.apply_permutation(&[0, 1, 2]),
+        Err(SeqMapError::InvalidPermutation { len: 4 })
+    );
+    assert_eq!(
+        map.apply_permutation(&[0, 1, 2, 4]),
+        Err(SeqMapError::IndexOutOfRange { index: 4, len: 4 })
+    );
+    assert_eq!(
+        map.apply_permutation(&[3, 1, 1, 0]),
+        Err(SeqMapError::InvalidPermutation { len: 4 })
+    );
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec!["a", "b", "c", "d"]
+    );
+
+    map.apply_permutation(&[3, 1, 0, 2]).unwrap();
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec!["d", "b", "a", "c"]
+    );
+    assert_eq!(map.get_index(&"c").map(usize::from), Some(3));
+    assert_eq!(map[&"d"], 4);
+}
+
+#[test]
+fn rotate_as_round_robin() {
+    let mut map = SeqMap::from([("render", 1), ("audio", 2), ("net", 3)]);
+
+    map.rotate_left(1);
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec!["audio", "net", "render"]
+    );
+    assert_eq!(map.get_index(&"render").map(usize::from), Some(2));
+
+    map.rotate_right(2);
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec!["net", "render", "audio"]
+    );
+    assert_eq!(map.get_index(&"net").map(usize::from), Some(0));
+
+    map.rotate_left(3);
+    assert_eq!(map.get_index(&"audio").map(usize::from), Some(2));
+}
+
+#[test]
+fn shuffle_seeded_is_reproducible() {
+    let original: SeqMap<u32, u32> = (0..10).map(|i| (i, i * 10)).collect();
+
+    let mut first = original.clone();
+    first.shuffle_seeded(7);
+    let mut second = original.clone();
+    second.shuffle_seeded(7);
+    let mut other = original.clone();
+    other.shuffle_seeded(8);
+
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+    assert!(first.eq_ignore_order(&original));
+    for (index, (key, value)) in first.iter().enumerate() {
+        assert_eq!(first.get_index(key).map(usize::from), Some(index));
+        assert_eq!(*value, key * 10);
+    }
+}
+
+#[test]
+fn sort_values_by_keeps_join_order_among_ties() {
+    let mut leaderboard = SeqMap::new();
+    for (player, score) in [(7, 100), (3, 250), (9, 100), (1, 250), (4, 50)] {
+        leaderboard.insert(player, score).unwrap();
+    }
+
+    leaderboard.sort_values_by(|a, b| b.cmp(a));
+
+    assert_eq!(
+        leaderboard.keys().copied().collect::<Vec<_>>(),
+        vec![3, 1, 7, 9, 4]
+    );
+    assert_eq!(leaderboard.get_index(&9).map(usize::from), Some(3));
+}
+
+#[test]
+fn drain_leaves_map_empty_and_valid() {
+    let mut map: SeqMap<u32, String> = (0..8).map(|i| (i, i.to_string())).collect();
+
+    let mut drain = map.drain();
+    assert_eq!(drain.len(), 8);
+    assert_eq!(drain.next_back(), Some((7, "7".to_string())));
+    assert_eq!(drain.next(), Some((0, "0".to_string())));
+    drop(drain);
+
+    assert!(map.is_empty());
+    assert!(!map.contains_key(&3));
+
+    map.insert(3, "again".to_string()).unwrap();
+    std::mem::forget(map.drain());
+    assert!(map.is_empty());
+    assert_eq!(map.get(&3), None);
+}
+
+#[test]
+fn entries_slice_access() {
+    let map = SeqMap::from([("a", 1), ("b", 4), ("c", 9), ("d", 16)]);
+
+    let halves: Vec<_> = map.as_entries().chunks(2).collect();
+    assert_eq!(halves[1], &[("c", 9), ("d", 16)][..]);
+    assert_eq!(map.as_entries().binary_search_by(|(_, v)| v.cmp(&9)), Ok(2));
+
+    assert_eq!(map.into_entries().len(), 4);
+}
+
+#[test]
+fn index_mut_and_borrowed_index() {
+    struct Counter(u32);
+
+    let mut map = SeqMap::new();
+    map.insert("hits".to_string(), Counter(0)).unwrap();
+
+    map["hits"].0 += 2;
+    map[&"hits".to_string()] = Counter(map["hits"].0 + 1);
+
+    assert_eq!(map["hits"].0, 3);
+}
+
+#[test]
+#[should_panic(expected = "Key not found in SeqMap")]
+fn index_mut_panics_on_missing_key() {
+    let mut map = SeqMap::from([("a", 1)]);
+    map[&"b"] = 2;
+}
+
+#[test]
+fn iteration_needs_no_key_bounds() {
+    fn total<K, V: Copy + Into<u64>>(map: &SeqMap<K, V>) -> u64 {
+        map.into_iter().map(|(_, v)| (*v).into()).sum()
+    }
+
+    fn bump<K>(map: &mut SeqMap<K, u32>) {
+        for (_, v) in map {
+            *v += 1;
+        }
+    }
+
+    fn keys<K, V>(map: SeqMap<K, V>) -> Vec<K> {
+        map.into_iter().map(|(k, _)| k).collect()
+    }
+
+    let mut map = SeqMap::from([("a", 1u32), ("b", 2)]);
+    bump(&mut map);
+    assert_eq!(total(&map), 5);
+    assert_eq!(keys(map), vec!["a", "b"]);
+}
+
+#[test]
+fn alternate_debug_is_multiline() {
+    let map = SeqMap::from([("outer", SeqMap::from([("inner", vec![1])]))]);
+
+    assert_eq!(
+        format!("{map:?}"),
+        r#"SeqMap("outer": SeqMap("inner": [1]))"#
+    );
+    assert_eq!(
+        format!("{map:#?}"),
+        "SeqMap {
+    \"outer\": SeqMap {
+        \"inner\": [
+            1,
+        ],
+    },
+}"
+    );
+}
+
+#[test]
+fn display_width_and_nesting() {
+    enum Setting {
+        Value(i32),
+        Group(SeqMap<&'static str, Setting>),
+    }
+
+    impl std::fmt::Display for Setting {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Setting::Value(value) => write!(f, "{value}"),
+                Setting::Group(map) if f.alternate() => write!(f, "{map:#}"),
+                Setting::Group(map) => write!(f, "{map}"),
+            }
+        }
+    }
+
+    let mut limits = SeqMap::new();
+    limits.insert("depth", Setting::Value(3)).unwrap();
+    limits.insert("retries", Setting::Value(5)).unwrap();
+    let mut outer = SeqMap::new();
+    outer.insert("id", Setting::Value(7)).unwrap();
+    outer.insert("limits", Setting::Group(limits)).unwrap();
+
+    assert_eq!(
+        outer.to_string(),
+        "SeqMap(2)
+id: 7
+limits: SeqMap(2)
+  depth: 3
+  retries: 5"
+    );
+    assert_eq!(
+        format!("{outer:#}"),
+        "SeqMap(2)
+id    : 7
+limits: SeqMap(2)
+  depth  : 3
+  retries: 5"
+    );
+
+    let flat = SeqMap::from([("a", 1), ("bbb", 2)]);
+    assert_eq!(format!("{flat:5}"), "SeqMap(2)\na    : 1\nbbb  : 2");
+}
+
+#[test]
+fn table_display_aligns_and_truncates() {
+    let mut registry = SeqMap::new();
+    registry.insert("audio", "ready").unwrap();
+    registry.insert("network", "connecting to eu-west").unwrap();
+
+    assert_eq!(
+        registry.display_table().to_string(),
+        "audio   | ready\nnetwork | connecting to eu-west\n"
+    );
+    assert_eq!(
+        registry
+            .display_table()
+            .headers("system", "state")
+            .truncate(10)
+            .to_string(),
+        "system  | state\n--------+-----------\naudio   | ready\nnetwork | connectin…\n"
+    );
+    assert_eq!(SeqMap::<u8, u8>::new().display_table().to_string(), "");
+}
+
+#[test]
+fn canonical_string_round_trip() {
+    use seq_map::ParseCanonicalError;
+
+    let mut map = SeqMap::new();
+    map.insert("path\\to".to_string(), "C:\\tmp".to_string())
+        .unwrap();
+    map.insert("a = b ".to_string(), " multi\r\nline".to_string())
+        .unwrap();
+    map.insert(String::new(), String::new()).unwrap();
+
+    let text = map.to_canonical_string();
+    assert_eq!(text.lines().count(), 3);
+    assert_eq!(SeqMap::parse_canonical(&text), Ok(map));
+
+    let parse = SeqMap::<String, u8>::parse_canonical;
+    assert_eq!(
+        parse("x = 1\n\nno separator"),
+        Err(ParseCanonicalError::MissingSeparator { line: 3 })
+    );
+    assert_eq!(
+        parse("x = 1\nx = 2"),
+        Err(ParseCanonicalError::DuplicateKey { line: 2 })
+    );
+    assert_eq!(
+        parse("x = 300"),
+        Err(ParseCanonicalError::InvalidValue { line: 1 })
+    );
+    assert_eq!(
+        parse("x\\q = 1"),
+        Err(ParseCanonicalError::InvalidEscape { line: 1 })
+    );
+}
+
+#[test]
+fn memory_usage_accounts_for_capacity() {
+    let empty: SeqMap<u32, u32> = SeqMap::new();
+    let report = empty.memory_usage();
+    assert_eq!(report.entries, 0);
+    assert_eq!(report.index, 0);
+    assert_eq!(report.total(), report.inline);
+
+    let mut map = SeqMap::new();
+    for i in 0..10u32 {
+        map.insert(i, i.to_string()).unwrap();
+    }
+    let report = map.memory_usage_with(|_, v| v.capacity());
+    let entry_size = std::mem::size_of::<(u32, String)>();
+    assert!(report.entries >= 10 * entry_size);
+    assert_eq!(report.entries - report.entries_spare, 10 * entry_size);
+    assert!(report.index >= 10 * std::mem::size_of::<usize>());
+    assert_eq!(
+        report.deep,
+        map.values().map(String::capacity).sum::<usize>()
+    );
+}
+
+#[test]
+fn small_maps_have_no_index_until_they_grow() {
+    let mut map = SeqMap::new();
+    for i in 0..8u32 {
+        map.insert(i, i * 2).unwrap();
+    }
+    assert_eq!(map.memory_usage().index, 0);
+    assert_eq!(map.get(&7), Some(&14));
+    assert!(map.insert(3, 0).is_err());
+    assert_eq!(map.remove(&0), Some(0));
+    assert_eq!(map.get_index(&7).map(usize::from), Some(6));
+
+    for i in 8..20u32 {
+        *map.get_mut_or_default(i) += i;
+    }
+    assert!(map.memory_usage().index > 0);
+    for (index, (key, _)) in map.iter().enumerate() {
+        assert_eq!(map.get_index(key).map(usize::from), Some(index));
+    }
+
+    map.retain(|k, _| *k % 5 == 0);
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![5, 10, 15]);
+    assert_eq!(map.get_index(&15).map(usize::from), Some(2));
+    assert_eq!(map.remove_many([&5, &5, &10]), 2);
+    assert_eq!(map.get(&15), Some(&15));
+
+    let mut small = SeqMap::from([(1, 'a'), (2, 'b'), (3, 'c')]);
+    assert_eq!(small.remove_many([&1, &1, &3]), 2);
+    assert_eq!(small.get_index(&2).map(usize::from), Some(0));
+}
+
+#[test]
+fn keys_are_stored_once_and_need_not_be_clone() {
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    struct Name(String);
+
+    let mut map: SeqMap<_, _> = (0..30).map(|i| (Name(format!("n{i}")), i)).collect();
+    assert_eq!(map.remove(&Name("n3".into())), Some(3));
+    map.retain(|_, v| *v % 4 != 0);
+    map.splice(0..2, [(Name("x".into()), 100)]).unwrap();
+    map.sort_values_by(|a, b| b.cmp(a));
+    let map = map.filter_map(|_, v| (v != 29).then_some(v));
+
+    for (index, (key, _)) in map.iter().enumerate() {
+        assert_eq!(map.get_index(key).map(usize::from), Some(index));
+    }
+    assert_eq!(map[&Name("x".into())], 100);
+    assert!(!map.contains_key(&Name("n29".into())));
+    assert!(!map.contains_key(&Name("n1".into())));
+}
+
+#[test]
+// Validating the map after every operation hashes the keys again
+#[cfg(not(feature = "strict-invariants"))]
+fn restructuring_an_indexed_map_does_not_rehash_keys() {
+    use std::{
+        cell::Cell,
+        hash::{Hash, Hasher},
+    };
+
+    thread_local! {
+        static HASHED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Counted(u32);
+
+    impl Hash for Counted {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            HASHED.with(|hashed| hashed.set(hashed.get() + 1));
+            self.0.hash(state);
+        }
+    }
+
+    let mut map: SeqMap<_, _> = (0..100).map(|i| (Counted(i), i)).collect();
+    HASHED.with(|hashed| hashed.set(0));
+
+    map.sort_values_by(|a, b| b.cmp(a));
+    map.sort_keys();
+    map.rotate_left(10);
+    map.shuffle_seeded(7);
+    map.retain(|_, v| *v % 3 != 0);
+    map.splice(0..5, []).unwrap();
+    assert_eq!(HASHED.with(Cell::get), 0);
+
+    for (index, (key, _)) in map.iter().enumerate() {
+        assert_eq!(map.get_index(key).map(usize::from), Some(index));
+    }
+}
+
+#[test]
+fn removing_the_oldest_entries_keeps_positions_in_step() {
+    let mut map: SeqMap<u32, u32> = (0..1000).map(|i| (i, i)).collect();
+    for tick in 0..600 {
+        assert_eq!(map.remove(&tick), Some(tick));
+        map.insert(1000 + tick, tick).unwrap();
+        if tick % 97 == 0 {
+            for (index, (key, _)) in map.iter().enumerate() {
+                assert_eq!(map.get_index(key).map(usize::from), Some(index));
+            }
+        }
+    }
+    assert_eq!(map.len(), 1000);
+    assert_eq!(map.get_index(&600).map(usize::from), Some(0));
+    assert_eq!(map.get_index(&1599).map(usize::from), Some(999));
+    assert_eq!(map.remove(&599), None);
+
+    map.upsert_with(5000, || 1, |v| *v += 1);
+    map.sort_keys();
+    map.retain(|k, _| k % 2 == 0);
+    for (index, (key, _)) in map.iter().enumerate() {
+        assert_eq!(map.get_index(key).map(usize::from), Some(index));
+    }
+    let keys: Vec<u32> = map.keys().copied().collect();
+    for key in keys {
+        map.remove(&key);
+    }
+    assert!(map.is_empty());
+}
+
+#[test]
+fn removing_from_both_sides_matches_a_vec() {
+    let mut map = SeqMap::new();
+    let mut expected = Vec::new();
+    let mut state = 17u32;
+    for step in 0..5000u32 {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        let roll = (state >> 16) % 10;
+        if roll < 5 || expected.is_empty() {
+            map.insert(step, step * 2).unwrap();
+            expected.push((step, step * 2));
+        } else {
+            let index = match roll {
+                5 | 6 => 0,
+                7 => expected.len() - 1,
+                _ => (state >> 8) as usize % expected.len(),
+            };
+            let (key, value) = expected.remove(index);
+            assert_eq!(map.remove_full(&key), Some((index, key, value)));
+        }
+        if step % 251 == 0 {
+            assert_eq!(map.as_entries(), expected.as_slice());
+            for (index, (key, _)) in expected.iter().enumerate() {
+                assert_eq!(map.get_index(key).map(usize::from), Some(index));
+            }
+        }
+    }
+    assert_eq!(map.as_entries(), expected.as_slice());
+}
+
+// Validating the map after every operation makes every removal linear
+#[cfg(not(feature = "strict-invariants"))]
+#[test]
+fn removing_the_oldest_entry_moves_no_other_entry() {
+    let len = 10_000;
+    let ticks = 5 * len;
+    let mut map: SeqMap<u32, u32> = (0..len).map(|i| (i, i)).collect();
+    let mut runs_moved = 0;
+    for tick in 0..ticks {
+        let second = std::ptr::from_ref(&map.as_entries()[1]);
+        assert_eq!(map.remove(&tick), Some(tick));
+        assert_eq!(map.as_entries().as_ptr(), second);
+
+        map.insert(len + tick, len + tick).unwrap();
+        if map.as_entries().as_ptr() != second {
+            runs_moved += 1;
+        }
+    }
+    // The entries only move when a push wraps past the end of the buffer, which is at least
+    // `len` pushes apart, where a `Vec` would move all of them on every removal
+    assert!(runs_moved <= ticks / len + 1, "moved {runs_moved} times");
+    assert_eq!(map.get_index(&ticks).map(usize::from), Some(0));
+}
+
+#[test]
+fn custom_hasher_is_used_for_lookups() {
+    use seq_map::FxBuildHasher;
+    use std::hash::BuildHasher;
+
+    let mut map: SeqMap<String, usize, FxBuildHasher> =
+        SeqMap::with_capacity_and_hasher(32, FxBuildHasher::default());
+    for i in 0..32 {
+        map.insert(format!("symbol{i}"), i).unwrap();
+    }
+    assert_eq!(map["symbol17"], 17);
+    assert_eq!(map.remove(&"symbol3".to_string()), Some(3));
+    assert_eq!(
+        map.get_index(&"symbol4".to_string()).map(usize::from),
+        Some(3)
+    );
+
+    let other = FxBuildHasher::default();
+    assert_eq!(map.hasher().hash_one("abc"), other.hash_one("abc"));
+    let copied: SeqMap<String, usize, FxBuildHasher> = map.clone().into_iter().collect();
+    assert_eq!(copied, map);
+}
+
+#[test]
+#[cfg(not(feature = "strict-invariants"))]
+fn fx_hashes_spread_keys_with_trailing_zero_bits() {
+    use seq_map::FxBuildHasher;
+    use std::{collections::HashSet, hash::BuildHasher};
+
+    let hasher = FxBuildHasher::default();
+    let low_bits: HashSet<u64> = (0..10_000u64)
+        .map(|i| hasher.hash_one(i << 32) & 0xffff)
+        .collect();
+    assert!(
+        low_bits.len() > 5_000,
+        "{} distinct low bits",
+        low_bits.len()
+    );
+
+    let mut map = SeqMap::with_hasher(FxBuildHasher::default());
+    for i in 0..10_000u64 {
+        map.insert(i << 32, i).unwrap();
+    }
+    assert!((0..10_000u64).all(|i| map[&(i << 32)] == i));
+}
+
+#[test]
+fn custom_hasher_maps_compare_format_and_join() {
+    use seq_map::FxBuildHasher;
+    use std::collections::{BTreeMap, HashMap};
+
+    let fx: SeqMap<&str, i32, FxBuildHasher> = [("b", 2), ("a", 1)].into_iter().collect();
+    let std_map = SeqMap::from([("a", 10), ("b", 20)]);
+    assert_eq!(format!("{fx}"), "SeqMap(2)\nb: 2\na: 1");
+    assert!(fx < [("c", 0)].into_iter().collect());
+    assert_eq!(fx, HashMap::from([("a", 1), ("b", 2)]));
+    assert_eq!(fx, BTreeMap::from([("a", 1), ("b", 2)]));
+    assert_eq!(fx, &[("b", 2), ("a", 1)][..]);
+    assert!(fx.eq_ignore_order(&SeqMap::from([("a", 1), ("b", 2)])));
+    assert!(fx.keys_eq_ignore_order(&std_map));
+    assert_eq!(fx.zip(&std_map).count(), 2);
+    assert_eq!(fx.left_join(&std_map).count(), 2);
+    assert_eq!(fx.outer_join(&std_map).count(), 2);
+    assert!(fx.memory_usage().total() > 0);
+    assert_eq!(fx.display_table().to_string(), "b | 2\na | 1\n");
+    let text = fx.to_canonical_string();
+    let parsed: SeqMap<String, i32, FxBuildHasher> = SeqMap::parse_canonical(&text).unwrap();
+    assert_eq!(parsed.to_canonical_string(), text);
+
+    let joined: SeqMap<&str, i32, FxBuildHasher> = fx.clone().join_with(std_map, |a, b| a + b);
+    assert_eq!(joined.into_vec(), vec![("b", 22), ("a", 11)]);
+    let by_len = fx
+        .map_keys(str::len, seq_map::DuplicatePolicy::FirstWins)
+        .unwrap();
+    let _: &SeqMap<usize, i32, FxBuildHasher> = &by_len;
+    assert_eq!(by_len.into_vec(), vec![(1, 2)]);
+}
+
+#[test]
+fn maps_can_be_created_in_a_static() {
+    use seq_map::FxBuildHasher;
+    use std::sync::Mutex;
+
+    static SYMBOLS: Mutex<SeqMap<u32, &str, FxBuildHasher>> =
+        Mutex::new(SeqMap::with_hasher(FxBuildHasher::new()));
+    const EMPTY: SeqMap<u32, u32, FxBuildHasher> = SeqMap::with_hasher(FxBuildHasher::new());
+
+    let mut symbols = SYMBOLS.lock().unwrap();
+    for i in 0..20 {
+        symbols.insert(i, "symbol").unwrap();
+    }
+    assert_eq!(symbols.get_index(&19).map(usize::from), Some(19));
+    assert!(EMPTY.is_empty());
+}
+
+#[test]
+fn typed_indices_address_entries() {
+    use seq_map::SeqMapIndex;
+
+    let mut map = SeqMap::new();
+    map.insert("a", 1).unwrap();
+    let b = map.insert_full("b", 2).unwrap();
+    assert!(map.insert_full("a", 3).is_err());
+
+    let (index, value) = map.get_full(&"b").unwrap();
+    assert_eq!((index, *value), (b, 2));
+    *map.get_at_mut(b).unwrap().1 += 10;
+    assert_eq!(map.get_at(b), Some((&"b", &12)));
+    assert_eq!(b, map.get_index(&"b").unwrap());
+    assert_eq!(map.get_at(SeqMapIndex::new(2)), None);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "stale SeqMapIndex")]
+fn stale_indices_panic_in_debug_builds() {
+    let mut map = SeqMap::new();
+    map.insert("a", 1).unwrap();
+    let b = map.insert_full("b", 2).unwrap();
+    map.insert("c", 3).unwrap();
+    assert_eq!(map.get_at(b), Some((&"b", &2)));
+    map.remove(&"a");
+    let _ = map.get_at(b);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "stale SeqMapIndex")]
+fn indices_from_another_map_panic_in_debug_builds() {
+    let mut players = SeqMap::new();
+    let mut enemies = SeqMap::new();
+    let ann = players.insert_full("ann", 1).unwrap();
+    enemies.insert("orc", 2).unwrap();
+    assert_eq!(players.get_at(ann), Some((&"ann", &1)));
+    let _ = enemies.get_at(ann);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "stale SeqMapIndex")]
+fn clones_accept_indices_of_their_original_until_entries_move() {
+    let mut original = SeqMap::new();
+    original.insert("ann", 1).unwrap();
+    let bob = original.insert_full("bob", 2).unwrap();
+    let mut clone = original.clone();
+    assert_eq!(clone.get_at(bob), Some((&"bob", &2)));
+    clone.remove(&"ann");
+    let _ = clone.get_at(bob);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "stale SeqMapIndex")]
+fn filter_map_makes_indices_stale_when_it_drops_entries() {
+    let mut map = SeqMap::new();
+    map.insert("a", 1).unwrap();
+    let b = map.insert_full("b", 2).unwrap();
+    map.insert("c", 3).unwrap();
+    let map = map.filter_map(|key, value| (*key != "a").then_some(value));
+    let _ = map.get_at(b);
+}
+
+#[test]
+fn validate_accepts_maps_after_every_kind_of_mutation() {
+    let mut map: SeqMap<u32, u32> = (0..50).map(|i| (i, i)).collect();
+    assert_eq!(map.validate(), Ok(()));
+    map.remove(&0);
+    map.retain(|k, _| k % 4 != 1);
+    map.sort_values_by(|a, b| b.cmp(a));
+    map.splice(2..6, [(100, 0), (101, 1)]).unwrap();
+    map.upsert_with(7, || 0, |v| *v += 1);
+    assert_eq!(map.validate(), Ok(()));
+    map.clear();
+    map.insert(1, 1).unwrap();
+    assert_eq!(map.validate(), Ok(()));
+}
+
+#[test]
+fn panicking_closures_leave_the_map_valid() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut map: SeqMap<u32, u32> = (0..50).map(|i| (i, i)).collect();
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        map.retain(|k, _| {
+            assert!(*k != 20, "retain closure panics");
+            k % 2 == 0
+        });
+    }));
+    assert!(result.is_err());
+    assert_eq!(map.validate(), Ok(()));
+    // Entries up to 19 were filtered, 20 was lost to the panic and the rest were kept
+    assert_eq!(map.len(), 10 + 29);
+    assert_eq!(map.get_index(&21).map(usize::from), Some(10));
+    assert!(!map.contains_key(&20));
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        map.sort_values_by(|a, b| {
+            assert!(*a != 33 && *b != 33, "compare panics");
+            b.cmp(a)
+        });
+    }));
+    assert!(result.is_err());
+    assert_eq!(map.validate(), Ok(()));
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        map.upsert_with(100, || panic!("insert panics"), |_| {});
+    }));
+    assert!(result.is_err());
+    assert!(!map.contains_key(&100));
+    assert_eq!(map.validate(), Ok(()));
+
+    for (index, (key, _)) in map.iter().enumerate() {
+        assert_eq!(map.get_index(key).map(usize::from), Some(index));
+    }
+}
+
+fn extend_generic<E>(target: &mut E, source: &SeqMap<u32, u32>)
+where
+    E: for<'a> Extend<(&'a u32, &'a u32)>,
+{
+    target.extend(source);
+}
+
+#[test]
+fn maps_extend_from_borrowed_pairs() {
+    let source: SeqMap<u32, u32> = (0..12).map(|i| (i, i * 10)).collect();
+    let mut std_map = std::collections::HashMap::new();
+    let mut map = SeqMap::from([(3, 300)]);
+    extend_generic(&mut std_map, &source);
+    extend_generic(&mut map, &source);
+    assert_eq!(std_map.len(), 12);
+    assert_eq!(map.len(), 12);
+    assert_eq!(map[&3], 300);
+    assert_eq!(map.get_index(&11).map(usize::from), Some(11));
+
+    let names: SeqMap<String, Vec<u8>> = (0..12).map(|i| (i.to_string(), vec![i])).collect();
+    let mut copy = SeqMap::new();
+    copy.extend_from_map(&names);
+    assert_eq!(copy, names);
+    assert_eq!(copy.validate(), Ok(()));
+}
+
+#[test]
+fn concatenated_maps_keep_module_order() {
+    let modules: Vec<SeqMap<String, usize>> = (0..3)
+        .map(|m| (0..6).map(|i| (format!("{}", m * 4 + i), m)).collect())
+        .collect();
+
+    let global = SeqMap::concat(modules.clone());
+    assert_eq!(global.len(), 14);
+    assert_eq!(global[&String::from("4")], 0);
+    assert_eq!(
+        global.get_index(&String::from("13")).map(usize::from),
+        Some(13)
+    );
+    assert_eq!(global.validate(), Ok(()));
+
+    let mut extended = SeqMap::new();
+    extended.extend(modules.clone());
+    assert_eq!(extended, global);
+
+    let err = SeqMap::concat_with(modules, seq_map::DuplicatePolicy::Error).unwrap_err();
+    assert_eq!(err.key, "4");
+    assert_eq!(err.index, 6);
+}
+
+#[test]
+fn retain_with_index_sees_original_positions() {
+    let mut map: SeqMap<u32, bool> = (0..300).map(|i| (i, i % 50 == 7)).collect();
+    let mut seen = Vec::new();
+    map.retain_with_index(|index, key, pinned| {
+        seen.push(index);
+        assert_eq!(index, *key as usize);
+        index < 100 || *pinned
+    });
+    assert_eq!(seen, (0..300).collect::<Vec<_>>());
+    assert_eq!(map.len(), 104);
+    assert_eq!(map.get_index(&257).map(usize::from), Some(103));
+    assert_eq!(map.validate(), Ok(()));
+}
+
+#[test]
+fn indexed_iterators_report_positions_from_both_ends() {
+    let mut map: SeqMap<char, u32> = ('a'..='e').zip(0..).collect();
+    map.remove(&'b');
+    {
+        let mut iter = map.indexed_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some((0, &'a', &0)));
+        assert_eq!(iter.next_back(), Some((3, &'e', &4)));
+        assert_eq!(iter.next(), Some((1, &'c', &2)));
+        assert_eq!(iter.len(), 1);
+    }
+
+    let mut visited = Vec::new();
+    for (index, key, value) in map.indexed_iter_mut().rev().take(2) {
+        visited.push((index, *key));
+        *value += 100;
+    }
+    assert_eq!(visited, vec![(3, 'e'), (2, 'd')]);
+    assert_eq!(
+        map.values().copied().collect::<Vec<_>>(),
+        vec![0, 2, 103, 104]
+    );
+}
+
+#[test]
+fn removed_entries_hand_back_the_owned_key() {
+    let mut source: SeqMap<String, u32> = (0..20).map(|i| (format!("k{i}"), i)).collect();
+    let mut target = SeqMap::new();
+    let (index, key, value) = source.remove_full(&String::from("k5")).unwrap();
+    assert_eq!((index, key.as_str(), value), (5, "k5", 5));
+    target.insert(key, value).unwrap();
+
+    let (key, value) = source.remove_entry(&String::from("k19")).unwrap();
+    target.insert(key, value).unwrap();
+    assert_eq!(source.remove_entry(&String::from("k5")), None);
+    assert_eq!(source.len(), 18);
+    assert_eq!(
+        source.get_index(&String::from("k6")).map(usize::from),
+        Some(5)
+    );
+    assert_eq!(source.validate(), Ok(()));
+    assert_eq!(
+        target.keys().cloned().collect::<Vec<_>>(),
+        vec!["k5", "k19"]
+    );
+}
+
+#[test]
+fn maps_can_be_reconciled_against_a_key_list() {
+    let mut map: SeqMap<u32, u32> = (0..40).map(|i| (i, i)).collect();
+    let authoritative: Vec<u32> = (0..60).rev().step_by(3).collect();
+    assert_eq!(map.keep_only(&authoritative), 27);
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec![2, 5, 8, 11, 14, 17, 20, 23, 26, 29, 32, 35, 38]
+    );
+    assert_eq!(map.remove_all_in(&[5, 38, 99]), 2);
+    assert_eq!(map.get_index(&35).map(usize::from), Some(10));
+    assert_eq!(map.validate(), Ok(()));
+}
+
+#[test]
+fn maps_can_be_subtracted_and_intersected_in_place() {
+    let full: SeqMap<u32, String> = (0..30).map(|i| (i, i.to_string())).collect();
+    let evens: SeqMap<u32, ()> = (0..60).step_by(2).map(|i| (i, ())).collect();
+
+    let mut odds = full.clone();
+    assert_eq!(odds.subtract(&evens), 15);
+    assert!(odds.keys().all(|k| k % 2 == 1));
+    assert_eq!(odds.get_index(&29).map(usize::from), Some(14));
+    assert_eq!(odds.validate(), Ok(()));
+
+    let mut kept = full;
+    assert_eq!(kept.intersect_with(&evens), 15);
+    assert_eq!(
+        kept.keys().copied().take(3).collect::<Vec<_>>(),
+        vec![0, 2, 4]
+    );
+    assert_eq!(kept.intersect_with(&odds), 15);
+    assert!(kept.is_empty());
+}
+
+#[test]
+fn grouping_keeps_first_seen_and_insertion_order() {
+    let map: SeqMap<u32, &str> = (0..20)
+        .map(|i| (i, ["low", "mid", "high"][(i * 7 % 3) as usize]))
+        .collect();
+    let by_ref: Vec<(&str, Vec<u32>)> = map
+        .group_by_ref(|_, v| *v)
+        .map(|(group, entries)| (group, entries.into_iter().map(|(k, _)| *k).collect()))
+        .collect();
+
+    let groups = map.group_by(|_, v| *v);
+    assert_eq!(
+        groups.keys().copied().collect::<Vec<_>>(),
+        vec!["low", "mid", "high"]
+    );
+    for (group, keys) in by_ref {
+        let members = &groups[&group];
+        assert_eq!(members.keys().copied().collect::<Vec<_>>(), keys);
+        assert!(members.keys().is_sorted());
+        assert_eq!(members.validate(), Ok(()));
+    }
+    assert_eq!(groups.values().map(SeqMap::len).sum::<usize>(), 20);
+}
+
+#[test]
+fn from_keys_initializes_each_distinct_key_once() {
+    let mut calls = 0;
+    let keys = (0..40).chain(10..30).map(|i| i % 25);
+    let map = SeqMap::from_keys(keys, |&key| {
+        calls += 1;
+        key * 2
+    });
+    assert_eq!(calls, 25);
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        (0..25).collect::<Vec<_>>()
+    );
+    assert_eq!(map[&24], 48);
+    assert_eq!(map.validate(), Ok(()));
+}
+
+#[test]
+fn lookups_take_borrowed_keys() {
+    let mut map = SeqMap::from([(String::from("a"), 1), (String::from("b"), 2)]);
+    assert_eq!(map.get("a"), Some(&1));
+    assert!(map.contains_key("b"));
+    *map.get_mut("b").unwrap() += 10;
+    let (index, value) = map.get_full("b").unwrap();
+    assert_eq!((index.get(), *value), (1, 12));
+    assert_eq!(map.get_index("b"), Some(index));
+    assert_eq!(map.remove("a"), Some(1));
+    assert_eq!(map.remove_full("b"), Some((0, String::from("b"), 12)));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn navigation_and_updates_take_borrowed_keys() {
+    let mut map: SeqMap<String, u32> = ["a", "b", "c"]
+        .into_iter()
+        .zip(1..)
+        .map(|(key, value)| (key.to_owned(), value))
+        .collect();
+    assert!(map.update("a", |value| *value += 10));
+    assert_eq!(map.try_update("b", |value| *value += 10), Ok(()));
+    assert_eq!(
+        map.try_update("x", |_| {}),
+        Err(seq_map::SeqMapError::KeyNotFound(String::from("x")))
+    );
+    assert_eq!(map.try_get("a"), Ok(&11));
+    assert_eq!(map.try_get("x").unwrap_err().key, "x");
+
+    let from_b: Vec<_> = map.iter_from("b").map(|(_, value)| *value).collect();
+    assert_eq!(from_b, vec![12, 3]);
+    assert_eq!(map.iter_after("b").count(), 1);
+    assert_eq!(map.entry_before("b").map(|(_, value)| *value), Some(11));
+    assert_eq!(map.entry_after("b").map(|(_, value)| *value), Some(3));
+    assert_eq!(map.binary_search_keys("b"), Ok(1));
+    assert_eq!(map.binary_search_keys("bb"), Err(2));
+}
+
+#[test]
+fn sibling_maps_return_and_accept_typed_indices() {
+    use seq_map::{FixedSeqMap, SeqMapIndex, SoaSeqMap};
+
+    let mut fixed: FixedSeqMap<&str, u32, 4> = FixedSeqMap::new();
+    fixed.insert("a", 1).unwrap();
+    let b = fixed.insert_full("b", 2).unwrap();
+    assert_eq!(fixed.get_index(&"b"), Some(b));
+    assert_eq!(fixed.get_at(b), Some((&"b", &2)));
+    assert_eq!(fixed.get_at(SeqMapIndex::new(4)), None);
+
+    let mut soa: SoaSeqMap<&str, u32> = SoaSeqMap::new();
+    soa.insert("a", 1).unwrap();
+    let b = soa.insert_full("b", 2).unwrap();
+    assert_eq!(soa.get_index(&"b"), Some(b));
+    assert_eq!(soa.get_at(b), Some((&"b", &2)));
+
+    let map: SeqMap<&str, u32> = soa.into_seq_map();
+    assert_eq!(map.get_at(b), Some((&"b", &2)));
+    let frozen = map.shrink_to_frozen();
+    assert_eq!(frozen.get_index(&"b"), Some(b));
+    assert_eq!(frozen.get_at(b), Some((&"b", &2)));
+}
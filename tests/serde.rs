@@ -0,0 +1,162 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "serde")]
+
+use seq_map::{CanonicalSeqMap, FxBuildHasher, SeqMap};
+
+#[test]
+fn round_trip_keeps_insertion_order() {
+    let map = SeqMap::from([("zeta".to_string(), 1), ("alpha".to_string(), 2)]);
+
+    let json = serde_json::to_string(&map).unwrap();
+    assert_eq!(json, r#"{"zeta":1,"alpha":2}"#);
+
+    let back: SeqMap<String, i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, map);
+
+    assert!(serde_json::from_str::<SeqMap<String, i32>>(r#"{"a":1,"a":2}"#).is_err());
+}
+
+#[test]
+fn maps_with_a_custom_hasher_round_trip() {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Config {
+        #[serde(with = "seq_map::duplicates::last_wins")]
+        env: SeqMap<String, u32, FxBuildHasher>,
+    }
+
+    let map: SeqMap<&str, u32, FxBuildHasher> = [("b", 2), ("a", 1)].into_iter().collect();
+    let json = serde_json::to_string(&map).unwrap();
+    assert_eq!(json, r#"{"b":2,"a":1}"#);
+    let back: SeqMap<&str, u32, FxBuildHasher> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, map);
+    assert_eq!(
+        serde_json::to_string(&CanonicalSeqMap(&map)).unwrap(),
+        r#"{"a":1,"b":2}"#
+    );
+
+    let config: Config = serde_json::from_str(r#"{"env": {"A": 1, "A": 3}}"#).unwrap();
+    assert_eq!(config.env["A"], 3);
+}
+
+#[test]
+fn canonical_output_ignores_insertion_order() {
+    #[derive(serde::Serialize)]
+    struct Signed {
+        #[serde(serialize_with = "seq_map::serialize_sorted")]
+        claims: SeqMap<&'static str, u32>,
+    }
+
+    let first = SeqMap::from([("sub", 1), ("exp", 2), ("aud", 3)]);
+    let mut second = first.clone();
+    second.rotate_left(1);
+
+    let expected = r#"{"aud":3,"exp":2,"sub":1}"#;
+    assert_eq!(
+        serde_json::to_string(&CanonicalSeqMap(&first)).unwrap(),
+        expected
+    );
+    assert_eq!(
+        serde_json::to_string(&CanonicalSeqMap(&second)).unwrap(),
+        expected
+    );
+    assert_eq!(
+        serde_json::to_string(&Signed { claims: second }).unwrap(),
+        format!(r#"{{"claims":{expected}}}"#)
+    );
+}
+
+#[test]
+fn binary_formats_use_pair_sequence() {
+    use serde_test::{assert_tokens, Configure, Token};
+
+    let map = SeqMap::from([(2u8, "b"), (1, "a")]);
+
+    assert_tokens(
+        &map.clone().readable(),
+        &[
+            Token::Map { len: Some(2) },
+            Token::U8(2),
+            Token::BorrowedStr("b"),
+            Token::U8(1),
+            Token::BorrowedStr("a"),
+            Token::MapEnd,
+        ],
+    );
+    assert_tokens(
+        &map.compact(),
+        &[
+            Token::Seq { len: Some(2) },
+            Token::Tuple { len: 2 },
+            Token::U8(2),
+            Token::BorrowedStr("b"),
+            Token::TupleEnd,
+            Token::Tuple { len: 2 },
+            Token::U8(1),
+            Token::BorrowedStr("a"),
+            Token::TupleEnd,
+            Token::SeqEnd,
+        ],
+    );
+}
+
+#[test]
+fn duplicate_key_policies() {
+    #[derive(serde::Deserialize)]
+    struct Loaded {
+        #[serde(with = "seq_map::duplicates::reject")]
+        strict: SeqMap<String, u32>,
+        #[serde(with = "seq_map::duplicates::first_wins")]
+        first: SeqMap<String, u32>,
+        #[serde(with = "seq_map::duplicates::last_wins")]
+        last: SeqMap<String, u32>,
+    }
+
+    let json = r#"{
+        "strict": {"a": 1},
+        "first": {"a": 1, "b": 2, "a": 3},
+        "last": {"a": 1, "b": 2, "a": 3}
+    }"#;
+    let loaded: Loaded = serde_json::from_str(json).unwrap();
+    assert_eq!(loaded.strict.len(), 1);
+    assert_eq!(
+        loaded.first,
+        SeqMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+    );
+    assert_eq!(
+        loaded.last,
+        SeqMap::from([("a".to_string(), 3), ("b".to_string(), 2)])
+    );
+
+    let err =
+        serde_json::from_str::<Loaded>(r#"{"strict": {"a": 1, "a": 2}, "first": {}, "last": {}}"#)
+            .err()
+            .unwrap();
+    assert!(err
+        .to_string()
+        .contains("duplicate key in SeqMap at entry 1"));
+}
+
+#[test]
+fn borrowed_keys_point_into_the_input() {
+    let input = r#"{"b": "two", "a": "one"}"#.to_string();
+    let range = input.as_bytes().as_ptr_range();
+
+    let map: SeqMap<&str, &str> = serde_json::from_str(&input).unwrap();
+
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec!["b", "a"]);
+    for (key, value) in &map {
+        assert!(range.contains(&key.as_ptr()));
+        assert!(range.contains(&value.as_ptr()));
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Manifest<'a> {
+        #[serde(borrow)]
+        files: SeqMap<&'a str, u32>,
+    }
+    let manifest: Manifest<'_> = serde_json::from_str(r#"{"files": {"x.png": 3}}"#).unwrap();
+    assert_eq!(manifest.files[&"x.png"], 3);
+}
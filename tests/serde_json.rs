@@ -0,0 +1,40 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "serde_json")]
+
+use seq_map::SeqMap;
+use serde_json::{json, Map, Value};
+
+#[test]
+fn json_value_conversions() {
+    let mut map = SeqMap::new();
+    map.insert("open".to_string(), json!(true)).unwrap();
+    map.insert("name".to_string(), json!("gate")).unwrap();
+    map.insert("id".to_string(), json!(7)).unwrap();
+    let keys = vec!["open", "name", "id"];
+
+    let object: Map<String, Value> = map.clone().into();
+    assert_eq!(object.keys().map(String::as_str).collect::<Vec<_>>(), keys);
+    assert_eq!(
+        SeqMap::from(object)
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+        keys
+    );
+
+    let value = Value::from(map.clone());
+    assert_eq!(value, json!({"open": true, "name": "gate", "id": 7}));
+    let back = SeqMap::try_from(value).unwrap();
+    assert_eq!(back.keys().map(String::as_str).collect::<Vec<_>>(), keys);
+    assert_eq!(back, map);
+
+    let err = SeqMap::try_from(json!([1, 2])).unwrap_err();
+    assert_eq!(err.value, json!([1, 2]));
+    assert_eq!(
+        err.to_string(),
+        "expected a JSON object for the SeqMap, found [1,2]"
+    );
+}
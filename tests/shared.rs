@@ -0,0 +1,41 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "arc-swap")]
+
+use std::{sync::Arc, thread};
+
+use seq_map::SharedSeqMap;
+
+#[test]
+fn readers_keep_their_snapshot_while_writers_publish() {
+    let assets = Arc::new(SharedSeqMap::new());
+    assets.insert(0, String::from("base")).unwrap();
+    let before = assets.snapshot();
+
+    let writers: Vec<_> = (1..=4)
+        .map(|i| {
+            let assets = Arc::clone(&assets);
+            thread::spawn(move || {
+                for j in 0..25 {
+                    assets.insert(i * 100 + j, format!("{i}.{j}")).unwrap();
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    assert_eq!(before.len(), 1);
+    assert_eq!(assets.len(), 101);
+    assert!(assets.insert(0, String::new()).is_err());
+    assert_eq!(assets.remove(&0).as_deref(), Some("base"));
+    assets.with(|map| {
+        let mut keys: Vec<_> = map.keys().copied().collect();
+        keys.sort_unstable();
+        assert_eq!(keys.first(), Some(&100));
+        assert_eq!(map.validate(), Ok(()));
+    });
+}
@@ -0,0 +1,88 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "smallvec")]
+
+use seq_map::{FxBuildHasher, SeqMap, SmallSeqMap};
+
+#[test]
+fn insert_full_returns_an_index_for_get_at() {
+    let mut map: SmallSeqMap<&str, u32, 2> = SmallSeqMap::new();
+    for (i, key) in ["a", "b", "c"].into_iter().enumerate() {
+        let index = map.insert_full(key, i as u32).unwrap();
+        assert_eq!(map.get_index(&key), Some(index));
+        assert_eq!(map.get_at(index), Some((&key, &(i as u32))));
+    }
+    assert!(map.insert("a", 9).is_err());
+}
+
+#[test]
+fn stays_inline_until_it_spills() {
+    let mut map: SmallSeqMap<u32, char, 2> = SmallSeqMap::new();
+    map.insert(7, 'a').unwrap();
+    map.insert(3, 'b').unwrap();
+    assert!(!map.spilled());
+    assert!(map.insert(7, 'c').is_err());
+
+    map.insert(5, 'c').unwrap();
+    map.insert(1, 'd').unwrap();
+    assert!(map.spilled());
+    assert_eq!(map.get_index(&5).map(usize::from), Some(2));
+
+    assert_eq!(map.remove(&3), Some('b'));
+    assert_eq!(map.get_index(&5).map(usize::from), Some(1));
+    assert_eq!(map.get_index(&1).map(usize::from), Some(2));
+    *map.get_mut(&1).unwrap() = 'x';
+
+    let keys: Vec<_> = map.keys().copied().collect();
+    assert_eq!(keys, vec![7, 5, 1]);
+    assert_eq!(
+        map.clone().into_seq_map(),
+        SeqMap::from([(7, 'a'), (5, 'c'), (1, 'x')])
+    );
+    assert_eq!(SmallSeqMap::from(map.clone().into_seq_map()), map);
+}
+
+#[derive(PartialEq, Eq, Hash, Debug)]
+struct Name(String);
+
+#[test]
+fn keys_need_not_be_clone_and_positions_survive_removals() {
+    let mut map: SmallSeqMap<Name, usize, 4, FxBuildHasher> =
+        SmallSeqMap::with_hasher(FxBuildHasher::default());
+    for i in 0..40 {
+        map.insert(Name(format!("n{i}")), i).unwrap();
+    }
+    assert!(map.spilled());
+    for i in (0..40).step_by(3) {
+        assert_eq!(map.remove(&Name(format!("n{i}"))), Some(i));
+    }
+    assert!(map.insert(Name(String::from("n1")), 0).is_err());
+    for (index, key) in map.keys().enumerate() {
+        assert_eq!(map.get_index(key).map(usize::from), Some(index));
+    }
+
+    let seq = map.into_seq_map();
+    assert_eq!(seq.validate(), Ok(()));
+    assert_eq!(
+        seq.get_index(&Name(String::from("n2"))).map(usize::from),
+        Some(1)
+    );
+    let small: SmallSeqMap<Name, usize, 4, FxBuildHasher> = SmallSeqMap::from(seq);
+    assert_eq!(
+        small.get_index(&Name(String::from("n38"))).map(usize::from),
+        Some(25)
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "stale SeqMapIndex")]
+fn indices_taken_before_a_removal_panic_in_debug_builds() {
+    let mut map: SmallSeqMap<&str, u32, 4> = SmallSeqMap::new();
+    map.insert("a", 1).unwrap();
+    let b = map.insert_full("b", 2).unwrap();
+    map.remove(&"a");
+    let _ = map.get_at(b);
+}
@@ -0,0 +1,66 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::{FxBuildHasher, SeqMap, SoaSeqMap};
+
+#[test]
+fn keys_and_values_stay_aligned_in_separate_slices() {
+    let mut map: SoaSeqMap<String, u32> = (0..5).map(|i| (format!("entity-{i}"), i)).collect();
+    assert_eq!(map.values_as_slice(), &[0, 1, 2, 3, 4]);
+
+    assert_eq!(map.remove("entity-1"), Some(1));
+    assert!(map.insert(String::from("entity-0"), 9).is_err());
+    map.insert(String::from("entity-9"), 9).unwrap();
+    assert_eq!(map.values_as_slice(), &[0, 2, 3, 4, 9]);
+    assert_eq!(map.keys_as_slice()[1], "entity-2");
+    assert_eq!(map.get_index("entity-4").map(usize::from), Some(3));
+
+    for value in map.values_as_mut_slice() {
+        *value += 1;
+    }
+    assert_eq!(map.get("entity-9"), Some(&10));
+
+    let seq = map.clone().into_seq_map();
+    assert_eq!(
+        seq.get_index(&String::from("entity-3")).map(usize::from),
+        Some(2)
+    );
+    assert_eq!(SoaSeqMap::from(seq), map);
+}
+
+#[test]
+fn removals_and_conversions_keep_positions_with_a_custom_hasher() {
+    let mut map = SoaSeqMap::with_hasher(FxBuildHasher::default());
+    for i in 0..100u32 {
+        map.insert(i, i * 2).unwrap();
+    }
+    for i in (0..100).step_by(4) {
+        assert_eq!(map.remove(&i), Some(i * 2));
+    }
+    for (index, key) in map.keys().enumerate() {
+        assert_eq!(map.get_index(key).map(usize::from), Some(index));
+    }
+
+    let seq: SeqMap<u32, u32, FxBuildHasher> = map.clone().into_seq_map();
+    assert_eq!(seq.validate(), Ok(()));
+    assert_eq!(seq.get_index(&1).map(usize::from), Some(0));
+    assert_eq!(SoaSeqMap::from(seq), map);
+
+    let small = SeqMap::from([("a", 1), ("b", 2)]);
+    let soa = SoaSeqMap::from(small.clone());
+    assert_eq!(soa.get_index("b").map(usize::from), Some(1));
+    assert_eq!(soa.into_seq_map(), small);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "stale SeqMapIndex")]
+fn indices_from_another_map_panic_in_debug_builds() {
+    let mut players = SoaSeqMap::new();
+    let mut enemies = SoaSeqMap::new();
+    let ann = players.insert_full("ann", 1).unwrap();
+    enemies.insert("orc", 2).unwrap();
+    let _ = enemies.get_at(ann);
+}
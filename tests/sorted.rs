@@ -0,0 +1,43 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::{SeqMap, SortedSeqMap};
+
+#[test]
+fn insertion_and_sorted_views() {
+    let mut map = SortedSeqMap::new();
+    map.insert("delta", 4).unwrap();
+    map.insert("alpha", 1).unwrap();
+    map.insert("charlie", 3).unwrap();
+    map.insert("bravo", 2).unwrap();
+
+    let declaration_order: Vec<_> = map.iter().map(|(k, _)| *k).collect();
+    assert_eq!(
+        declaration_order,
+        vec!["delta", "alpha", "charlie", "bravo"]
+    );
+
+    let alphabetical: Vec<_> = map.iter_sorted().map(|(k, _)| *k).collect();
+    assert_eq!(alphabetical, vec!["alpha", "bravo", "charlie", "delta"]);
+
+    let window: Vec<_> = map.range("b"..="charlie").map(|(_, v)| *v).collect();
+    assert_eq!(window, vec![2, 3]);
+}
+
+#[test]
+fn mutations_keep_index_in_sync() {
+    let mut map = SortedSeqMap::from(SeqMap::from([(3, "c"), (1, "a"), (2, "b")]));
+
+    assert!(map.insert(2, "again").is_err());
+    assert_eq!(map.remove(&1), Some("a"));
+    assert_eq!(
+        map.range(..).map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.range(..).count(), 0);
+}
@@ -0,0 +1,69 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+
+use seq_map::SeqMap;
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+/// Records the message and fields of every event as one line.
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+struct Line(String);
+
+impl Visit for Line {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push_str(&format!(" {}={:?}", field.name(), value));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+    fn event(&self, event: &Event<'_>) {
+        let mut line = Line(event.metadata().level().to_string());
+        event.record(&mut line);
+        self.0.lock().unwrap().push(line.0);
+    }
+    fn enter(&self, _: &span::Id) {}
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[test]
+fn labeled_maps_emit_events() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+
+    tracing::subscriber::with_default(Recorder(lines.clone()), || {
+        let mut quiet = SeqMap::new();
+        quiet.insert("ignored", 0).unwrap();
+
+        let mut registry = SeqMap::new();
+        registry.set_trace_label("registry");
+        registry.insert("render", 1).unwrap();
+        registry.insert("audio", 2).unwrap();
+        registry.remove(&"render");
+        registry.rotate_left(1);
+    });
+
+    assert_eq!(
+        *lines.lock().unwrap(),
+        vec![
+            "TRACE message=insert map=\"registry\" key=render index=0 len=1",
+            "TRACE message=insert map=\"registry\" key=audio index=1 len=2",
+            "TRACE message=remove map=\"registry\" key=render index=0 len=1",
+            "DEBUG message=reorder map=\"registry\" len=1 operation=\"rotate_left\"",
+        ]
+    );
+}
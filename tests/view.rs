@@ -0,0 +1,80 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/seq-map
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+
+use seq_map::{SeqMap, SeqMapView};
+
+struct Library {
+    books: SeqMap<&'static str, u32>,
+}
+
+impl Library {
+    fn books(&self) -> SeqMapView<'_, &'static str, u32> {
+        self.books.view()
+    }
+}
+
+#[test]
+fn views_expose_reads_that_outlive_the_view() {
+    let mut books = SeqMap::new();
+    let index = books.insert_full("dune", 1965).unwrap();
+    books.insert("emma", 1815).unwrap();
+    let library = Library { books };
+
+    let year = {
+        let view = library.books();
+        view.get(&"emma")
+    };
+    assert_eq!(year, Some(&1815));
+
+    let view = library.books();
+    let copy = view;
+    assert_eq!(copy, view);
+    assert_eq!(view.len(), 2);
+    assert_eq!(view.get_at(index), Some((&"dune", &1965)));
+    assert_eq!(view.iter_range(1..).count(), 1);
+    let keys: Vec<_> = view.into_iter().map(|(k, _)| *k).collect();
+    assert_eq!(keys, vec!["dune", "emma"]);
+    assert_eq!(format!("{view:?}"), format!("{:?}", library.books));
+}
+
+#[test]
+fn range_views_only_see_entries_inside_the_window() {
+    let map: SeqMap<_, _> = (0..6).map(|i| (i, i * 10)).collect();
+    let index = map.get_full(&4).unwrap().0;
+
+    let early = map.view_range(..3);
+    assert_eq!(early.len(), 3);
+    assert_eq!(early.get(&2), Some(&20));
+    assert_eq!(early.get(&3), None);
+    assert!(!early.contains_key(&5));
+    assert_eq!(early.get_at(index), None);
+
+    let middle = map.view_range(2..5);
+    assert_eq!(middle.get_index(&4).map(usize::from), Some(4));
+    assert_eq!(middle.get_at(index), Some((&4, &40)));
+    assert_eq!(middle.keys().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+    assert_eq!(middle.iter_range(3..).count(), 2);
+
+    let inner = middle.view_range(3..);
+    assert_eq!(inner.range(), 3..5);
+    assert_eq!(inner, map.view_range(3..5));
+    assert_eq!(format!("{inner:?}"), "SeqMap(3: 30, 4: 40)");
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn range_views_cannot_widen_their_window() {
+    let map: SeqMap<_, _> = (0..6).map(|i| (i, i)).collect();
+    let _ = map.view_range(..3).view_range(2..4);
+}
+
+#[test]
+fn views_take_borrowed_keys() {
+    let books = SeqMap::from([(String::from("dune"), 1965), (String::from("emma"), 1815)]);
+    let view = books.view_range(1..);
+    assert_eq!(view.get("emma"), Some(&1815));
+    assert_eq!(view.get_index("emma").map(usize::from), Some(1));
+    assert!(!view.contains_key("dune"));
+}